@@ -5,17 +5,21 @@ use crate::attr::{AggregatedSpirvAttributes, IntrinsicType};
 use crate::codegen_cx::CodegenCx;
 use crate::spirv_type::SpirvType;
 use rspirv::spirv::{Capability, StorageClass, Word};
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_errors::ErrorReported;
 use rustc_middle::bug;
 use rustc_middle::ty::layout::{FnAbiExt, TyAndLayout};
 use rustc_middle::ty::subst::SubstsRef;
-use rustc_middle::ty::{GeneratorSubsts, PolyFnSig, Ty, TyKind, TypeAndMut};
+use rustc_middle::ty::{
+    AssocKind, GeneratorSubsts, ParamEnv, PolyFnSig, PredicateKind, Ty, TyKind, TypeAndMut,
+};
 use rustc_span::def_id::DefId;
+use rustc_span::symbol::sym;
 use rustc_span::Span;
 use rustc_target::abi::call::{CastTarget, FnAbi, PassMode, Reg, RegKind};
 use rustc_target::abi::{
-    Abi, Align, FieldsShape, LayoutOf, Primitive, Scalar, Size, TagEncoding, VariantIdx, Variants,
+    Abi, Align, FieldsShape, Integer, LayoutOf, Primitive, Scalar, Size, TagEncoding, VariantIdx,
+    Variants,
 };
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
@@ -464,6 +468,17 @@ fn dig_scalar_pointee<'tcx>(
                 None => PointeeTy::Ty(elem),
                 Some(index) => {
                     if elem.is_unsized() {
+                        let tail = cx
+                            .tcx
+                            .struct_tail_erasing_lifetimes(elem_ty, ParamEnv::reveal_all());
+                        if let (1, TyKind::Dynamic(predicates, _)) = (index, tail.kind()) {
+                            // Vtables are arrays of pointer-sized integers (see
+                            // `CodegenCx::vtable_addr_of`), and this has to be the whole array,
+                            // not the `&[usize; 3]` rustc uses, for indexing them to work.
+                            let len = vtable_len(cx, predicates.principal_def_id());
+                            let array = cx.tcx.mk_array(cx.tcx.types.usize, len);
+                            return PointeeTy::Ty(cx.layout_of(array));
+                        }
                         dig_scalar_pointee(cx, ty.field(cx, index), None)
                     } else {
                         // This can sometimes happen in weird cases when going through the Adt case below - an ABI
@@ -490,6 +505,36 @@ fn dig_scalar_pointee<'tcx>(
     }
 }
 
+/// The number of entries in the vtables of `dyn Trait`, i.e. `drop_in_place`, the size and the
+/// alignment, followed by all the methods of `Trait` and its supertraits.
+fn vtable_len(cx: &CodegenCx<'_>, principal: Option<DefId>) -> u64 {
+    let mut len = 3;
+    let mut visited = FxHashSet::default();
+    let mut stack: Vec<DefId> = principal.into_iter().collect();
+    while let Some(def_id) = stack.pop() {
+        if !visited.insert(def_id) {
+            continue;
+        }
+        len += cx
+            .tcx
+            .associated_items(def_id)
+            .in_definition_order()
+            .filter(|item| item.kind == AssocKind::Fn)
+            .count() as u64;
+        stack.extend(
+            cx.tcx
+                .super_predicates_of(def_id)
+                .predicates
+                .iter()
+                .filter_map(|(predicate, _)| match predicate.kind().skip_binder() {
+                    PredicateKind::Trait(trait_predicate, _) => Some(trait_predicate.def_id()),
+                    _ => None,
+                }),
+        );
+    }
+    len
+}
+
 fn dig_scalar_pointee_adt<'tcx>(
     cx: &CodegenCx<'tcx>,
     ty: TyAndLayout<'tcx>,
@@ -694,6 +739,344 @@ fn trans_struct<'tcx>(cx: &CodegenCx<'tcx>, span: Span, ty: TyAndLayout<'tcx>) -
     .def_with_name(cx, span, TyLayoutNameKey::from(ty))
 }
 
+/// How a value has to be stored differently in a `Uniform`, `StorageBuffer` or `PushConstant`
+/// block than in its Rust layout, which may contain `bool`s (not allowed in blocks at all), the
+/// narrow integers fieldless `enum`s default to, and niche-encoded `Option`s.
+///
+/// The entry-point stub copies the value from the block to a variable of the original type
+/// (see `copy_from_interface` in `codegen_cx/entry.rs`), so only `&` parameters can be lowered.
+pub struct InterfaceLowering {
+    /// The type the value has in the block.
+    pub ty: Word,
+    pub kind: InterfaceLoweringKind,
+}
+
+pub enum InterfaceLoweringKind {
+    /// Stored as a `u32`, `0` or `1`.
+    Bool,
+    /// A fieldless `enum`, stored as its discriminant, widened to 32 bits.
+    Discriminant { signed: bool },
+    /// Stored as `struct { is_some: u32, value: T }`.
+    Option {
+        tag: OptionTag,
+        value: Option<Box<InterfaceLowering>>,
+    },
+    /// Stored with only the non-ZST fields, as `(original member index, lowering)`.
+    Struct(Vec<(u64, Option<InterfaceLowering>)>),
+    Array {
+        count: u64,
+        element: Box<InterfaceLowering>,
+    },
+}
+
+/// How `Option<T>` encodes `None` in its original layout.
+pub enum OptionTag {
+    /// The original type is a pair of the tag and the value.
+    Direct {
+        tag_member: u64,
+        value_member: u64,
+        none: u128,
+        some: u128,
+    },
+    /// `None` is a value `T` can't have, and the original type is the same as `T`'s.
+    Niche { none: u128 },
+}
+
+/// Computes how `layout` has to be lowered to be stored in an interface block, if at all, or
+/// an error naming the offending field (`path` being how the value is reached from the
+/// entry-point parameter).
+pub fn lower_interface_type<'tcx>(
+    cx: &CodegenCx<'tcx>,
+    span: Span,
+    layout: TyAndLayout<'tcx>,
+    path: &str,
+) -> Result<Option<InterfaceLowering>, String> {
+    let unrepresentable = |reason| Err(unrepresentable_interface_type(path, layout, reason));
+    // The layout of e.g. a newtype around a scalar is that of the scalar, so look through it.
+    let mut inner = layout;
+    while matches!(
+        (&inner.variants, &inner.fields),
+        (Variants::Single { .. }, FieldsShape::Arbitrary { .. })
+    ) {
+        let mut fields = (0..inner.fields.count())
+            .map(|i| inner.field(cx, i))
+            .filter(|field| !field.is_zst());
+        match (fields.next(), fields.next()) {
+            (Some(field), None) if field.size == inner.size => inner = field,
+            _ => break,
+        }
+    }
+    let (is_option, is_fieldless_enum) = match *inner.ty.kind() {
+        TyKind::Adt(adt, _) => (
+            cx.tcx.is_diagnostic_item(sym::option_type, adt.did),
+            adt.is_enum() && adt.is_payloadfree(),
+        ),
+        _ => (false, false),
+    };
+    if let Variants::Multiple { .. } = inner.variants {
+        if is_option {
+            return lower_interface_option(cx, span, inner, path);
+        }
+        if !is_fieldless_enum {
+            return unrepresentable("only fieldless `enum`s and `Option` are supported");
+        }
+    }
+
+    match layout.abi {
+        Abi::Scalar(ref scalar) if scalar.is_bool() => Ok(Some(InterfaceLowering {
+            ty: SpirvType::Integer(32, false).def(span, cx),
+            kind: InterfaceLoweringKind::Bool,
+        })),
+        Abi::Scalar(ref scalar) if is_fieldless_enum => match scalar.value {
+            Primitive::Int(width, signed) if width.size().bits() < 32 => {
+                Ok(Some(InterfaceLowering {
+                    ty: SpirvType::Integer(32, signed).def(span, cx),
+                    kind: InterfaceLoweringKind::Discriminant { signed },
+                }))
+            }
+            _ => Ok(None),
+        },
+        Abi::Scalar(_) | Abi::Vector { .. } | Abi::Uninhabited => Ok(None),
+        // The original type is a pair of its two scalars.
+        Abi::ScalarPair(ref a, ref b) => {
+            let fields: Vec<_> = inner
+                .fields
+                .index_by_increasing_offset()
+                .map(|i| (i, inner.field(cx, i)))
+                .filter(|(_, field)| !field.is_zst())
+                .collect();
+            if fields.len() == 2 && fields.iter().all(|(_, field)| field.abi.is_scalar()) {
+                let fields = fields
+                    .into_iter()
+                    .enumerate()
+                    .map(|(member, (i, field))| (member as u64, i, field))
+                    .collect();
+                lower_interface_struct(cx, span, inner, fields, path)
+            } else if a.is_bool() || b.is_bool() {
+                unrepresentable("nested pairs of scalars containing `bool`s aren't supported")
+            } else {
+                Ok(None)
+            }
+        }
+        Abi::Aggregate { .. } => match layout.fields {
+            FieldsShape::Array { count, .. } => {
+                let element = layout.field(cx, 0);
+                let element =
+                    match lower_interface_type(cx, span, element, &format!("{}[..]", path))? {
+                        Some(element) => element,
+                        None => return Ok(None),
+                    };
+                if layout.is_unsized() {
+                    return unrepresentable(
+                        "runtime arrays can't contain values that have to be stored differently \
+                         than in Rust (e.g. `bool`s)",
+                    );
+                }
+                Ok(Some(InterfaceLowering {
+                    ty: SpirvType::Array {
+                        element: element.ty,
+                        count: cx.constant_u32(span, count as u32),
+                    }
+                    .def(span, cx),
+                    kind: InterfaceLoweringKind::Array {
+                        count,
+                        element: Box::new(element),
+                    },
+                }))
+            }
+            // See `trans_struct` for the order of the members.
+            FieldsShape::Arbitrary { .. } => {
+                let fields = layout
+                    .fields
+                    .index_by_increasing_offset()
+                    .enumerate()
+                    .map(|(member, i)| (member as u64, i, layout.field(cx, i)))
+                    .filter(|(_, _, field)| !field.is_zst())
+                    .collect();
+                lower_interface_struct(cx, span, layout, fields, path)
+            }
+            FieldsShape::Primitive | FieldsShape::Union(_) => Ok(None),
+        },
+    }
+}
+
+fn unrepresentable_interface_type(path: &str, layout: TyAndLayout<'_>, reason: &str) -> String {
+    format!(
+        "`{}` has type `{}`, which can't be represented in an interface block: {}",
+        path, layout.ty, reason
+    )
+}
+
+/// `fields` are `(original member index, field index, field layout)`.
+fn lower_interface_struct<'tcx>(
+    cx: &CodegenCx<'tcx>,
+    span: Span,
+    layout: TyAndLayout<'tcx>,
+    fields: Vec<(u64, usize, TyAndLayout<'tcx>)>,
+    path: &str,
+) -> Result<Option<InterfaceLowering>, String> {
+    // Only plain slices can be unsized, and lowering would copy the value through a variable,
+    // which can't hold a runtime array, so reject these before looking at the other fields.
+    if layout.is_unsized() {
+        let (tail_path, tail) = unsized_tail(cx, layout, path.to_string());
+        return Err(unrepresentable_interface_type(
+            &tail_path,
+            tail,
+            "only plain slices are supported as unsized types, not structs ending in one",
+        ));
+    }
+    let mut lowered_fields = Vec::with_capacity(fields.len());
+    let mut field_types = Vec::with_capacity(fields.len());
+    let mut field_names = Vec::with_capacity(fields.len());
+    for (member, i, field) in fields {
+        let name = field_name(layout, i);
+        let lowering = lower_interface_type(cx, span, field, &format!("{}.{}", path, name))?;
+        field_types.push(
+            lowering
+                .as_ref()
+                .map_or_else(|| field.spirv_type(span, cx), |lowering| lowering.ty),
+        );
+        field_names.push(name);
+        lowered_fields.push((member, lowering));
+    }
+    if lowered_fields
+        .iter()
+        .all(|(_, lowering)| lowering.is_none())
+    {
+        return Ok(None);
+    }
+    let (field_offsets, size, align) = auto_struct_layout(cx, &field_types);
+    Ok(Some(InterfaceLowering {
+        ty: SpirvType::Adt {
+            def_id: None,
+            size,
+            align,
+            field_types,
+            field_offsets,
+            field_names: Some(field_names),
+        }
+        .def(span, cx),
+        kind: InterfaceLoweringKind::Struct(lowered_fields),
+    }))
+}
+
+fn field_name(layout: TyAndLayout<'_>, i: usize) -> String {
+    match (layout.ty.kind(), &layout.variants) {
+        (TyKind::Adt(adt, _), &Variants::Single { index }) => {
+            adt.variants[index].fields[i].ident.to_string()
+        }
+        _ => i.to_string(),
+    }
+}
+
+/// The path to (and layout of) the runtime array an unsized struct ends in.
+fn unsized_tail<'tcx>(
+    cx: &CodegenCx<'tcx>,
+    layout: TyAndLayout<'tcx>,
+    path: String,
+) -> (String, TyAndLayout<'tcx>) {
+    if let FieldsShape::Arbitrary { .. } = layout.fields {
+        let tail = (0..layout.fields.count())
+            .map(|i| (i, layout.field(cx, i)))
+            .find(|(_, field)| field.is_unsized());
+        if let Some((i, field)) = tail {
+            return unsized_tail(cx, field, format!("{}.{}", path, field_name(layout, i)));
+        }
+    }
+    (path, layout)
+}
+
+/// `Option<T>` is lowered to `struct { is_some: u32, value: T }`, which is already its layout
+/// for e.g. `Option<u32>` and `Option<f32>`.
+fn lower_interface_option<'tcx>(
+    cx: &CodegenCx<'tcx>,
+    span: Span,
+    layout: TyAndLayout<'tcx>,
+    path: &str,
+) -> Result<Option<InterfaceLowering>, String> {
+    let unrepresentable = |reason| Err(unrepresentable_interface_type(path, layout, reason));
+    let not_scalar =
+        "`Option<T>` is only supported when `T` is a scalar (e.g. `u32`, `f32` or `bool`)";
+    let (tag, tag_encoding, tag_field) = match layout.variants {
+        Variants::Multiple {
+            ref tag,
+            ref tag_encoding,
+            tag_field,
+            ..
+        } => (tag, tag_encoding, tag_field),
+        Variants::Single { .. } => return unrepresentable(not_scalar),
+    };
+    let none_variant = VariantIdx::new(0);
+    let some_variant = VariantIdx::new(1);
+    let value = layout.for_variant(cx, some_variant).field(cx, 0);
+    if !value.abi.is_scalar() {
+        return unrepresentable(not_scalar);
+    }
+    if let Primitive::Pointer = tag.value {
+        return unrepresentable("pointers can't be stored in interface blocks");
+    }
+    let value_lowering = lower_interface_type(cx, span, value, &format!("{}.0", path))?;
+
+    let tag = match *tag_encoding {
+        TagEncoding::Direct => {
+            if !matches!(layout.abi, Abi::ScalarPair(..)) {
+                return unrepresentable(not_scalar);
+            }
+            let tag_member = if layout.fields.offset(tag_field) == Size::ZERO {
+                0
+            } else {
+                1
+            };
+            let discr = |variant| {
+                let discr = layout.ty.discriminant_for_variant(cx.tcx, variant).unwrap();
+                tag.value.size(cx).truncate(discr.val)
+            };
+            let (none, some) = (discr(none_variant), discr(some_variant));
+            let is_u32 = tag.value == Primitive::Int(Integer::I32, false);
+            if tag_member == 0 && is_u32 && (none, some) == (0, 1) && value_lowering.is_none() {
+                return Ok(None);
+            }
+            OptionTag::Direct {
+                tag_member,
+                value_member: 1 - tag_member,
+                none,
+                some,
+            }
+        }
+        TagEncoding::Niche {
+            ref niche_variants,
+            niche_start,
+            ..
+        } => {
+            let niche = u128::from(none_variant.as_u32() - niche_variants.start().as_u32());
+            OptionTag::Niche {
+                none: tag.value.size(cx).truncate(niche.wrapping_add(niche_start)),
+            }
+        }
+    };
+
+    let value_ty = value_lowering
+        .as_ref()
+        .map_or_else(|| value.spirv_type(span, cx), |lowering| lowering.ty);
+    let field_types = vec![SpirvType::Integer(32, false).def(span, cx), value_ty];
+    let (field_offsets, size, align) = auto_struct_layout(cx, &field_types);
+    Ok(Some(InterfaceLowering {
+        ty: SpirvType::Adt {
+            def_id: None,
+            size,
+            align,
+            field_types,
+            field_offsets,
+            field_names: Some(vec!["is_some".to_string(), "value".to_string()]),
+        }
+        .def(span, cx),
+        kind: InterfaceLoweringKind::Option {
+            tag,
+            value: value_lowering.map(Box::new),
+        },
+    }))
+}
+
 /// Grab a `DefId` from the type if possible to avoid too much deduplication,
 /// which could result in one SPIR-V `OpType*` having many names
 /// (not in itself an issue, but it makes error reporting harder).
@@ -742,6 +1125,22 @@ impl fmt::Display for TyLayoutNameKey<'_> {
     }
 }
 
+/// The type argument of the first `PhantomData` field of the struct `ty`, if it has one.
+fn phantom_data_arg<'tcx>(cx: &CodegenCx<'tcx>, ty: TyAndLayout<'tcx>) -> Option<Ty<'tcx>> {
+    match *ty.ty.kind() {
+        TyKind::Adt(adt, substs) => {
+            adt.all_fields()
+                .find_map(|field| match *field.ty(cx.tcx, substs).kind() {
+                    TyKind::Adt(field_adt, field_substs) if field_adt.is_phantom_data() => {
+                        Some(field_substs.type_at(0))
+                    }
+                    _ => None,
+                })
+        }
+        _ => None,
+    }
+}
+
 fn trans_intrinsic_type<'tcx>(
     cx: &CodegenCx<'tcx>,
     span: Span,
@@ -766,8 +1165,24 @@ fn trans_intrinsic_type<'tcx>(
                     .err("#[spirv(image_type)] type must have size 4");
                 return Err(ErrorReported);
             }
-            // Hardcode to float for now
-            let sampled_type = SpirvType::Float(32).def(span, cx);
+            // The sampled type is taken from the `PhantomData<T>` field (if any),
+            // defaulting to `f32` for non-generic image types.
+            let sampled_type = match phantom_data_arg(cx, ty) {
+                Some(sampled_ty) => {
+                    let sampled_type = trans_type_impl(cx, span, cx.layout_of(sampled_ty), false);
+                    match cx.lookup_type(sampled_type) {
+                        SpirvType::Float(32) | SpirvType::Integer(32, _) => sampled_type,
+                        other => {
+                            cx.tcx.sess.err(&format!(
+                                "#[spirv(image_type)] sampled type must be f32, i32 or u32, not {}",
+                                other.debug(sampled_type, cx)
+                            ));
+                            return Err(ErrorReported);
+                        }
+                    }
+                }
+                None => SpirvType::Float(32).def(span, cx),
+            };
             let ty = SpirvType::Image {
                 sampled_type,
                 dim,
@@ -810,5 +1225,42 @@ fn trans_intrinsic_type<'tcx>(
                 Err(ErrorReported)
             }
         }
+        IntrinsicType::AccelerationStructureKhr => {
+            // see SpirvType::sizeof
+            if ty.size != Size::from_bytes(4) {
+                cx.tcx
+                    .sess
+                    .err("#[spirv(acceleration_structure)] type must have size 4");
+                return Err(ErrorReported);
+            }
+            Ok(SpirvType::AccelerationStructureKhr.def(span, cx))
+        }
+        IntrinsicType::RayQueryKhr => {
+            // see SpirvType::sizeof
+            if ty.size != Size::from_bytes(4) {
+                cx.tcx.sess.err("#[spirv(ray_query)] type must have size 4");
+                return Err(ErrorReported);
+            }
+            Ok(SpirvType::RayQueryKhr.def(span, cx))
+        }
+        IntrinsicType::TypedBuffer => {
+            // The buffer's contents are the type of its only field (rather than whichever
+            // generic parameter happens to come first).
+            match ty.fields {
+                FieldsShape::Arbitrary { .. }
+                    if ty.fields.count() == 1 && ty.field(cx, 0).size == ty.size =>
+                {
+                    let inner_type = trans_type_impl(cx, span, ty.field(cx, 0), false);
+                    Ok(SpirvType::InterfaceBlock { inner_type }.def(span, cx))
+                }
+                _ => {
+                    cx.tcx.sess.err(
+                        "#[spirv(typed_buffer)] type must be a transparent wrapper \
+                         around its generic type",
+                    );
+                    Err(ErrorReported)
+                }
+            }
+        }
     }
 }
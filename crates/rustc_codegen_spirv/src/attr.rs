@@ -5,9 +5,9 @@
 use crate::codegen_cx::CodegenCx;
 use crate::symbols::Symbols;
 use rspirv::spirv::{
-    AccessQualifier, BuiltIn, Dim, ExecutionMode, ExecutionModel, ImageFormat, StorageClass,
+    AccessQualifier, BuiltIn, Dim, ExecutionMode, ExecutionModel, ImageFormat, Scope, StorageClass,
 };
-use rustc_ast::Attribute;
+use rustc_ast::{Attribute, LitKind};
 use rustc_hir as hir;
 use rustc_hir::def_id::LocalDefId;
 use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
@@ -46,6 +46,12 @@ pub struct Entry {
     pub execution_model: ExecutionModel,
     pub execution_modes: Vec<(ExecutionMode, ExecutionModeExtra)>,
     pub name: Option<Symbol>,
+    /// `SpecId`s of the `threads` dimensions given a `local_size_id_{x,y,z}`,
+    /// which are emitted as specialization constants through `LocalSizeId`.
+    pub local_size_id: [Option<u32>; 3],
+    /// Memory scope of the `core::sync::atomic` operations reachable from
+    /// this entry point, overriding the crate-wide `--atomic-scope`.
+    pub atomic_scope: Option<Scope>,
 }
 
 impl From<ExecutionModel> for Entry {
@@ -54,6 +60,37 @@ impl From<ExecutionModel> for Entry {
             execution_model,
             execution_modes: Vec::new(),
             name: None,
+            local_size_id: [None; 3],
+            atomic_scope: None,
+        }
+    }
+}
+
+/// Entry-point parameter declared as a specialization constant, i.e.
+/// `#[spirv(spec_constant(id = 0, default = 1))]`.
+#[derive(Clone, Debug)]
+pub struct SpecConstant {
+    pub id: u32,
+    /// Literal used as the value when the constant isn't specialized.
+    pub default: Option<LitKind>,
+}
+
+/// Layout rules for `#[spirv(block(layout = "..."))]`, which the offsets and array strides
+/// of a uniform, storage buffer or push constant block are checked against.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlockLayout {
+    Std140,
+    Std430,
+    /// Requires `VK_EXT_scalar_block_layout`.
+    Scalar,
+}
+
+impl BlockLayout {
+    pub fn name(self) -> &'static str {
+        match self {
+            BlockLayout::Std140 => "std140",
+            BlockLayout::Std430 => "std430",
+            BlockLayout::Scalar => "scalar",
         }
     }
 }
@@ -72,6 +109,9 @@ pub enum IntrinsicType {
     },
     Sampler,
     SampledImage,
+    AccelerationStructureKhr,
+    RayQueryKhr,
+    TypedBuffer,
 }
 
 // NOTE(eddyb) when adding new `#[spirv(...)]` attributes, the tests found inside
@@ -81,6 +121,7 @@ pub enum SpirvAttribute {
     // `struct` attributes:
     IntrinsicType(IntrinsicType),
     Block,
+    BlockLayout(BlockLayout),
 
     // `fn` attributes:
     Entry(Entry),
@@ -90,8 +131,11 @@ pub enum SpirvAttribute {
     Builtin(BuiltIn),
     DescriptorSet(u32),
     Binding(u32),
+    InputAttachmentIndex(u32),
     Flat,
     Invariant,
+    PerPrimitiveNv,
+    SpecConstant(SpecConstant),
 
     // `fn`/closure attributes:
     UnrollLoops,
@@ -113,6 +157,7 @@ pub struct AggregatedSpirvAttributes {
     // `struct` attributes:
     pub intrinsic_type: Option<Spanned<IntrinsicType>>,
     pub block: Option<Spanned<()>>,
+    pub block_layout: Option<Spanned<BlockLayout>>,
 
     // `fn` attributes:
     pub entry: Option<Spanned<Entry>>,
@@ -122,8 +167,11 @@ pub struct AggregatedSpirvAttributes {
     pub builtin: Option<Spanned<BuiltIn>>,
     pub descriptor_set: Option<Spanned<u32>>,
     pub binding: Option<Spanned<u32>>,
+    pub input_attachment_index: Option<Spanned<u32>>,
     pub flat: Option<Spanned<()>>,
     pub invariant: Option<Spanned<()>>,
+    pub per_primitive_nv: Option<Spanned<()>>,
+    pub spec_constant: Option<Spanned<SpecConstant>>,
 
     // `fn`/closure attributes:
     pub unroll_loops: Option<Spanned<()>>,
@@ -193,6 +241,12 @@ impl AggregatedSpirvAttributes {
                 try_insert(&mut self.intrinsic_type, value, span, "intrinsic type")
             }
             Block => try_insert(&mut self.block, (), span, "#[spirv(block)]"),
+            BlockLayout(value) => try_insert(
+                &mut self.block_layout,
+                value,
+                span,
+                "#[spirv(block(layout = ...))]",
+            ),
             Entry(value) => try_insert(&mut self.entry, value, span, "entry-point"),
             StorageClass(value) => {
                 try_insert(&mut self.storage_class, value, span, "storage class")
@@ -205,8 +259,26 @@ impl AggregatedSpirvAttributes {
                 "#[spirv(descriptor_set)]",
             ),
             Binding(value) => try_insert(&mut self.binding, value, span, "#[spirv(binding)]"),
+            InputAttachmentIndex(value) => try_insert(
+                &mut self.input_attachment_index,
+                value,
+                span,
+                "#[spirv(input_attachment_index)]",
+            ),
             Flat => try_insert(&mut self.flat, (), span, "#[spirv(flat)]"),
             Invariant => try_insert(&mut self.invariant, (), span, "#[spirv(invariant)]"),
+            PerPrimitiveNv => try_insert(
+                &mut self.per_primitive_nv,
+                (),
+                span,
+                "#[spirv(per_primitive_nv)]",
+            ),
+            SpecConstant(value) => try_insert(
+                &mut self.spec_constant,
+                value,
+                span,
+                "#[spirv(spec_constant)]",
+            ),
             UnrollLoops => try_insert(&mut self.unroll_loops, (), span, "#[spirv(unroll_loops)]"),
         }
     }
@@ -262,7 +334,9 @@ impl CheckSpirvAttrVisitor<'_> {
             struct Expected<T>(T);
 
             let valid_target = match parsed_attr {
-                SpirvAttribute::IntrinsicType(_) | SpirvAttribute::Block => match target {
+                SpirvAttribute::IntrinsicType(_)
+                | SpirvAttribute::Block
+                | SpirvAttribute::BlockLayout(_) => match target {
                     Target::Struct => {
                         // FIXME(eddyb) further check type attribute validity,
                         // e.g. layout, generics, other attributes, etc.
@@ -288,8 +362,11 @@ impl CheckSpirvAttrVisitor<'_> {
                 | SpirvAttribute::Builtin(_)
                 | SpirvAttribute::DescriptorSet(_)
                 | SpirvAttribute::Binding(_)
+                | SpirvAttribute::InputAttachmentIndex(_)
                 | SpirvAttribute::Flat
-                | SpirvAttribute::Invariant => match target {
+                | SpirvAttribute::Invariant
+                | SpirvAttribute::PerPrimitiveNv
+                | SpirvAttribute::SpecConstant(_) => match target {
                     Target::Param => {
                         let parent_hir_id = self.tcx.hir().get_parent_node(hir_id);
                         let parent_is_entry_point =
@@ -2,22 +2,25 @@ use super::Builder;
 use crate::builder_spirv::{BuilderCursor, SpirvConst, SpirvValue, SpirvValueExt, SpirvValueKind};
 use crate::spirv_type::SpirvType;
 use rspirv::dr::{InsertPoint, Instruction, Operand};
-use rspirv::spirv::{Capability, MemoryModel, MemorySemantics, Op, Scope, StorageClass, Word};
+use rspirv::spirv::{
+    Capability, MemoryAccess, MemoryModel, MemorySemantics, Op, Scope, StorageClass, Word,
+};
 use rustc_codegen_ssa::common::{
     AtomicOrdering, AtomicRmwBinOp, IntPredicate, RealPredicate, SynchronizationScope,
 };
 use rustc_codegen_ssa::mir::operand::{OperandRef, OperandValue};
 use rustc_codegen_ssa::mir::place::PlaceRef;
 use rustc_codegen_ssa::traits::{
-    BuilderMethods, ConstMethods, IntrinsicCallMethods, LayoutTypeMethods, OverflowOp,
+    BaseTypeMethods, BuilderMethods, ConstMethods, IntrinsicCallMethods, LayoutTypeMethods,
+    OverflowOp,
 };
 use rustc_codegen_ssa::MemFlags;
 use rustc_middle::bug;
-use rustc_middle::ty::Ty;
+use rustc_middle::ty::{Ty, TyKind};
 use rustc_span::Span;
 use rustc_target::abi::{Abi, Align, Scalar, Size};
 use std::convert::TryInto;
-use std::iter::empty;
+use std::iter::{empty, once};
 use std::ops::Range;
 
 macro_rules! simple_op {
@@ -140,32 +143,67 @@ fn memset_dynamic_scalar(
 }
 
 impl<'a, 'tcx> Builder<'a, 'tcx> {
+    fn vulkan_memory_model(&self) -> bool {
+        self.emit()
+            .module_ref()
+            .memory_model
+            .as_ref()
+            .map_or(false, |inst| {
+                inst.operands[1].unwrap_memory_model() == MemoryModel::Vulkan
+            })
+    }
+
     fn ordering_to_semantics_def(&self, ordering: AtomicOrdering) -> SpirvValue {
         let mut invalid_seq_cst = false;
-        let semantics = match ordering {
+        let mut semantics = match ordering {
             AtomicOrdering::NotAtomic | AtomicOrdering::Unordered | AtomicOrdering::Monotonic => {
                 MemorySemantics::NONE
             }
             // Note: rustc currently has AtomicOrdering::Consume commented out, if it ever becomes
             // uncommented, it should be MakeVisible | Acquire.
-            AtomicOrdering::Acquire => MemorySemantics::MAKE_VISIBLE | MemorySemantics::ACQUIRE,
-            AtomicOrdering::Release => MemorySemantics::MAKE_AVAILABLE | MemorySemantics::RELEASE,
-            AtomicOrdering::AcquireRelease => {
-                MemorySemantics::MAKE_AVAILABLE
-                    | MemorySemantics::MAKE_VISIBLE
-                    | MemorySemantics::ACQUIRE_RELEASE
-            }
+            AtomicOrdering::Acquire => MemorySemantics::ACQUIRE,
+            AtomicOrdering::Release => MemorySemantics::RELEASE,
+            AtomicOrdering::AcquireRelease => MemorySemantics::ACQUIRE_RELEASE,
             AtomicOrdering::SequentiallyConsistent => {
-                let emit = self.emit();
-                let memory_model = emit.module_ref().memory_model.as_ref().unwrap();
-                if memory_model.operands[1].unwrap_memory_model() == MemoryModel::Vulkan {
+                if self.vulkan_memory_model() {
                     invalid_seq_cst = true;
                 }
-                MemorySemantics::MAKE_AVAILABLE
-                    | MemorySemantics::MAKE_VISIBLE
-                    | MemorySemantics::SEQUENTIALLY_CONSISTENT
+                MemorySemantics::SEQUENTIALLY_CONSISTENT
             }
         };
+        if !semantics.is_empty() {
+            // The ordering only applies to the storage classes given by the memory semantics,
+            // which default to all of the ones that atomics can be used with on the target.
+            semantics |= self
+                .codegen_args
+                .atomic_memory_semantics
+                .unwrap_or_else(|| {
+                    if self.kernel_mode {
+                        MemorySemantics::WORKGROUP_MEMORY | MemorySemantics::CROSS_WORKGROUP_MEMORY
+                    } else {
+                        MemorySemantics::UNIFORM_MEMORY
+                            | MemorySemantics::WORKGROUP_MEMORY
+                            | MemorySemantics::IMAGE_MEMORY
+                    }
+                });
+            // Availability and visibility operations are only explicit in the Vulkan memory model.
+            if self.vulkan_memory_model() {
+                if semantics.intersects(
+                    MemorySemantics::RELEASE
+                        | MemorySemantics::ACQUIRE_RELEASE
+                        | MemorySemantics::SEQUENTIALLY_CONSISTENT,
+                ) {
+                    semantics |= MemorySemantics::MAKE_AVAILABLE;
+                }
+                if semantics.intersects(
+                    MemorySemantics::ACQUIRE
+                        | MemorySemantics::ACQUIRE_RELEASE
+                        | MemorySemantics::SEQUENTIALLY_CONSISTENT,
+                ) {
+                    semantics |= MemorySemantics::MAKE_VISIBLE;
+                }
+            }
+        }
         let semantics = self.constant_u32(self.span(), semantics.bits());
         if invalid_seq_cst {
             self.zombie(
@@ -176,10 +214,183 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         semantics
     }
 
+    /// Lowers `AtomicNand` (which has no SPIR-V instruction) to a compare-exchange loop.
+    fn atomic_nand(
+        &mut self,
+        dst: SpirvValue,
+        src: SpirvValue,
+        order: AtomicOrdering,
+    ) -> SpirvValue {
+        let zero_align = Align::from_bytes(0).unwrap();
+        let size = self.lookup_type(src.ty).sizeof(self).unwrap();
+        let initial = self.atomic_load(dst, AtomicOrdering::Monotonic, size);
+        let expected = self.alloca(src.ty, zero_align);
+        self.store(initial, expected, zero_align);
+
+        let mut cas_loop = self.build_sibling_block("atomic_nand_loop");
+        let exit = self.build_sibling_block("atomic_nand_exit");
+        self.br(cas_loop.llbb());
+
+        let current = cas_loop.load(expected, zero_align);
+        let and = cas_loop.and(current, src);
+        let nand = cas_loop.not(and);
+        let old =
+            cas_loop.atomic_cmpxchg(dst, current, nand, order, AtomicOrdering::Monotonic, false);
+        cas_loop.store(old, expected, zero_align);
+        let success = cas_loop.icmp(IntPredicate::IntEQ, old, current);
+        cas_loop.cond_br(success, exit.llbb(), cas_loop.llbb());
+
+        *self = exit;
+        old
+    }
+
+    /// Maps rustc's `MemFlags` to the `MemoryAccess` mask (and its extra operands) of a memory
+    /// access, where `make_available`/`make_visible` say whether the access writes/reads memory.
+    ///
+    /// Under the Vulkan memory model, volatile accesses are also made available/visible (and
+    /// non-private), so that they're coherent with accesses from other invocations.
+    fn memory_access(
+        &self,
+        align: Align,
+        flags: MemFlags,
+        make_available: bool,
+        make_visible: bool,
+    ) -> (Option<MemoryAccess>, Vec<Operand>) {
+        let mut access = MemoryAccess::NONE;
+        let mut operands = Vec::new();
+        if flags.contains(MemFlags::VOLATILE) {
+            access |= MemoryAccess::VOLATILE;
+        }
+        // `Aligned` has to be the first extra operand, as it has the lowest bit.
+        if flags.contains(MemFlags::UNALIGNED) {
+            access |= MemoryAccess::ALIGNED;
+            operands.push(Operand::LiteralInt32(1));
+        } else if !flags.is_empty() && align.bytes() > 1 {
+            access |= MemoryAccess::ALIGNED;
+            operands.push(Operand::LiteralInt32(align.bytes() as u32));
+        }
+        if flags.contains(MemFlags::NONTEMPORAL) {
+            access |= MemoryAccess::NONTEMPORAL;
+        }
+        if self.vulkan_memory_model() && flags.contains(MemFlags::VOLATILE) {
+            // Device scope needs an extra capability under the Vulkan memory model, so fall back
+            // to queue family scope (enough for a single queue) when it's not declared.
+            let scope = if self
+                .builder
+                .has_capability(Capability::VulkanMemoryModelDeviceScope)
+            {
+                Scope::Device
+            } else {
+                Scope::QueueFamily
+            };
+            let scope = self.constant_u32(self.span(), scope as u32).def(self);
+            access |= MemoryAccess::NON_PRIVATE_POINTER;
+            if make_available {
+                access |= MemoryAccess::MAKE_POINTER_AVAILABLE;
+                operands.push(Operand::IdRef(scope));
+            }
+            if make_visible {
+                access |= MemoryAccess::MAKE_POINTER_VISIBLE;
+                operands.push(Operand::IdRef(scope));
+            }
+        }
+        if access.is_empty() {
+            (None, operands)
+        } else {
+            (Some(access), operands)
+        }
+    }
+
+    /// The memory operands of `OpCopyMemory`/`OpCopyMemorySized`, as a flat operand list (each
+    /// `MemoryAccess` mask followed by its extra operands).
+    ///
+    /// Since SPIR-V 1.4, the target and the source each get their own memory operand, so only the
+    /// target is made available and only the source is made visible. Before that, a single memory
+    /// operand applies to both, so neither is (as the two need different pointers).
+    fn copy_memory_access(
+        &self,
+        dst_align: Align,
+        src_align: Align,
+        flags: MemFlags,
+    ) -> Vec<Operand> {
+        let mut operands = Vec::new();
+        if self.emit().version().unwrap() >= (1, 4) {
+            let (dst_access, dst_operands) = self.memory_access(dst_align, flags, true, false);
+            let (src_access, src_operands) = self.memory_access(src_align, flags, false, true);
+            if dst_access.is_some() || src_access.is_some() {
+                operands.push(Operand::MemoryAccess(
+                    dst_access.unwrap_or(MemoryAccess::NONE),
+                ));
+                operands.extend(dst_operands);
+                operands.push(Operand::MemoryAccess(
+                    src_access.unwrap_or(MemoryAccess::NONE),
+                ));
+                operands.extend(src_operands);
+            }
+        } else {
+            let (access, access_operands) =
+                self.memory_access(dst_align.min(src_align), flags, false, false);
+            if let Some(access) = access {
+                operands.push(Operand::MemoryAccess(access));
+                operands.extend(access_operands);
+            }
+        }
+        operands
+    }
+
+    pub fn load_with_flags(
+        &mut self,
+        ptr: SpirvValue,
+        align: Align,
+        flags: MemFlags,
+    ) -> SpirvValue {
+        // See comment on `SpirvValueKind::ConstantPointer`
+        if let Some(value) = ptr.const_ptr_val(self) {
+            return value;
+        }
+        let ty = match self.lookup_type(ptr.ty) {
+            SpirvType::Pointer { pointee } => pointee,
+            ty => self.fatal(&format!(
+                "load called on variable that wasn't a pointer: {:?}",
+                ty
+            )),
+        };
+        // Function pointers in vtables are stored as the integers the linker turns them into
+        // (see `gep_help` and `CodegenCx::vtable_addr_of`).
+        if let SpirvValueKind::LogicalPtrCast {
+            original_ptr,
+            original_pointee_ty,
+            zombie_target_undef: _,
+        } = ptr.kind
+        {
+            if self.is_fn_ptr_type(ty) {
+                if let SpirvType::Integer(..) = self.lookup_type(original_pointee_ty) {
+                    let (access, operands) = self.memory_access(align, flags, false, true);
+                    let index = self
+                        .emit()
+                        .load(original_pointee_ty, None, original_ptr, access, operands)
+                        .unwrap();
+                    return self.emit().bitcast(ty, None, index).unwrap().with_type(ty);
+                }
+            }
+        }
+        let (access, operands) = self.memory_access(align, flags, false, true);
+        self.emit()
+            .load(ty, None, ptr.def(self), access, operands)
+            .unwrap()
+            .with_type(ty)
+    }
+
     fn memset_const_pattern(&self, ty: &SpirvType, fill_byte: u8) -> Word {
         match *ty {
             SpirvType::Void => self.fatal("memset invalid on void pattern"),
-            SpirvType::Bool => self.fatal("memset invalid on bool pattern"),
+            SpirvType::Bool => match fill_byte {
+                0 | 1 => self.constant_bool(self.span(), fill_byte != 0).def(self),
+                _ => self.fatal(&format!(
+                    "memset on bool with fill byte {} is not a valid bool",
+                    fill_byte
+                )),
+            },
             SpirvType::Integer(width, _signedness) => match width {
                 8 => self.constant_u8(self.span(), fill_byte).def(self),
                 16 => self
@@ -191,24 +402,47 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                 64 => self
                     .constant_u64(self.span(), memset_fill_u64(fill_byte))
                     .def(self),
-                _ => self.fatal(&format!(
-                    "memset on integer width {} not implemented yet",
-                    width
-                )),
+                // `constant_int` takes care of zombie-ing e.g. `u128`.
+                _ => self
+                    .constant_int(
+                        ty.clone().def(self.span(), self),
+                        memset_fill_u64(fill_byte),
+                    )
+                    .def(self),
             },
             SpirvType::Float(width) => match width {
+                // 16-bit float literals are stored in the low-order bits of a 32-bit word.
+                16 => self
+                    .builder
+                    .def_constant(SpirvConst::U32(
+                        ty.clone().def(self.span(), self),
+                        memset_fill_u16(fill_byte) as u32,
+                    ))
+                    .def(self),
                 32 => self
                     .constant_f32(self.span(), f32::from_bits(memset_fill_u32(fill_byte)))
                     .def(self),
                 64 => self
                     .constant_f64(self.span(), f64::from_bits(memset_fill_u64(fill_byte)))
                     .def(self),
-                _ => self.fatal(&format!(
-                    "memset on float width {} not implemented yet",
-                    width
-                )),
+                _ => self.fatal(&format!("memset on float width {} is not supported", width)),
             },
-            SpirvType::Adt { .. } => self.fatal("memset on structs not implemented yet"),
+            SpirvType::Adt {
+                ref field_types, ..
+            } => {
+                let ty = ty.clone().def(self.span(), self);
+                if fill_byte == 0 || field_types.is_empty() {
+                    self.constant_null(ty).def(self)
+                } else {
+                    let field_pats = field_types
+                        .iter()
+                        .map(|&field| {
+                            self.memset_const_pattern(&self.lookup_type(field), fill_byte)
+                        })
+                        .collect();
+                    self.constant_composite(ty, field_pats).def(self)
+                }
+            }
             SpirvType::Opaque { .. } => self.fatal("memset on opaque type is invalid"),
             SpirvType::Vector { element, count } => {
                 let elem_pat = self.memset_const_pattern(&self.lookup_type(element), fill_byte);
@@ -224,14 +458,28 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                 self.constant_composite(ty.clone().def(self.span(), self), vec![elem_pat; count])
                     .def(self)
             }
+            // Top-level runtime arrays are handled in `memset`, by filling each element.
             SpirvType::RuntimeArray { .. } => {
-                self.fatal("memset on runtime arrays not implemented yet")
+                self.fatal("memset on runtime arrays nested in other types is invalid")
+            }
+            SpirvType::Pointer { .. } => {
+                let ty = ty.clone().def(self.span(), self);
+                if fill_byte == 0 {
+                    self.constant_null(ty).def(self)
+                } else {
+                    let result = self.undef(ty).def(self);
+                    self.zombie(result, "memset on pointers with a non-zero fill byte");
+                    result
+                }
             }
-            SpirvType::Pointer { .. } => self.fatal("memset on pointers not implemented yet"),
-            SpirvType::Function { .. } => self.fatal("memset on functions not implemented yet"),
+            SpirvType::Function { .. } => self.fatal("cannot memset function"),
             SpirvType::Image { .. } => self.fatal("cannot memset image"),
             SpirvType::Sampler => self.fatal("cannot memset sampler"),
             SpirvType::SampledImage { .. } => self.fatal("cannot memset sampled image"),
+            SpirvType::AccelerationStructureKhr => {
+                self.fatal("cannot memset acceleration structure")
+            }
+            SpirvType::RayQueryKhr => self.fatal("cannot memset ray query"),
             SpirvType::InterfaceBlock { .. } => self.fatal("cannot memset interface block"),
         }
     }
@@ -239,26 +487,48 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
     fn memset_dynamic_pattern(&self, ty: &SpirvType, fill_var: Word) -> Word {
         match *ty {
             SpirvType::Void => self.fatal("memset invalid on void pattern"),
-            SpirvType::Bool => self.fatal("memset invalid on bool pattern"),
+            // Only fill bytes of `0` and `1` are valid `bool`s, so this can just test for `0`.
+            SpirvType::Bool => {
+                let zero = self.constant_u8(self.span(), 0).def(self);
+                self.emit()
+                    .i_not_equal(ty.clone().def(self.span(), self), None, fill_var, zero)
+                    .unwrap()
+            }
             SpirvType::Integer(width, _signedness) => match width {
                 8 => fill_var,
                 16 => memset_dynamic_scalar(self, fill_var, 2, false),
                 32 => memset_dynamic_scalar(self, fill_var, 4, false),
                 64 => memset_dynamic_scalar(self, fill_var, 8, false),
-                _ => self.fatal(&format!(
-                    "memset on integer width {} not implemented yet",
-                    width
-                )),
+                _ => {
+                    let result = self.undef(ty.clone().def(self.span(), self)).def(self);
+                    self.zombie(result, "memset on integer width other than 8/16/32/64");
+                    result
+                }
             },
             SpirvType::Float(width) => match width {
+                16 => memset_dynamic_scalar(self, fill_var, 2, true),
                 32 => memset_dynamic_scalar(self, fill_var, 4, true),
                 64 => memset_dynamic_scalar(self, fill_var, 8, true),
-                _ => self.fatal(&format!(
-                    "memset on float width {} not implemented yet",
-                    width
-                )),
+                _ => self.fatal(&format!("memset on float width {} is not supported", width)),
             },
-            SpirvType::Adt { .. } => self.fatal("memset on structs not implemented yet"),
+            SpirvType::Adt {
+                ref field_types, ..
+            } => {
+                let ty = ty.clone().def(self.span(), self);
+                if field_types.is_empty() {
+                    self.constant_null(ty).def(self)
+                } else {
+                    let field_pats = field_types
+                        .iter()
+                        .map(|&field| {
+                            self.memset_dynamic_pattern(&self.lookup_type(field), fill_var)
+                        })
+                        .collect::<Vec<_>>();
+                    self.emit()
+                        .composite_construct(ty, None, field_pats)
+                        .unwrap()
+                }
+            }
             SpirvType::Opaque { .. } => self.fatal("memset on opaque type is invalid"),
             SpirvType::Array { element, count } => {
                 let elem_pat = self.memset_dynamic_pattern(&self.lookup_type(element), fill_var);
@@ -281,41 +551,93 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                     )
                     .unwrap()
             }
+            // Top-level runtime arrays are handled in `memset`, by filling each element.
             SpirvType::RuntimeArray { .. } => {
-                self.fatal("memset on runtime arrays not implemented yet")
+                self.fatal("memset on runtime arrays nested in other types is invalid")
             }
-            SpirvType::Pointer { .. } => self.fatal("memset on pointers not implemented yet"),
-            SpirvType::Function { .. } => self.fatal("memset on functions not implemented yet"),
+            SpirvType::Pointer { .. } => {
+                let ptr_size = self.tcx.data_layout.pointer_size.bytes() as usize;
+                let int = memset_dynamic_scalar(self, fill_var, ptr_size, false);
+                let result = self
+                    .emit()
+                    .convert_u_to_ptr(ty.clone().def(self.span(), self), None, int)
+                    .unwrap();
+                self.zombie_convert_u_to_ptr(result);
+                result
+            }
+            SpirvType::Function { .. } => self.fatal("cannot memset function"),
             SpirvType::Image { .. } => self.fatal("cannot memset image"),
             SpirvType::Sampler => self.fatal("cannot memset sampler"),
             SpirvType::SampledImage { .. } => self.fatal("cannot memset sampled image"),
+            SpirvType::AccelerationStructureKhr => {
+                self.fatal("cannot memset acceleration structure")
+            }
+            SpirvType::RayQueryKhr => self.fatal("cannot memset ray query"),
             SpirvType::InterfaceBlock { .. } => self.fatal("cannot memset interface block"),
         }
     }
 
-    fn memset_constant_size(&mut self, ptr: SpirvValue, pat: SpirvValue, size_bytes: u64) {
+    /// Returns a pointer to the `index`th element of the memset destination `ptr`, which is either
+    /// a pointer to the first element, or (if `in_array`) a pointer to a (runtime) array of them.
+    fn memset_elem_ptr(
+        &mut self,
+        ptr: SpirvValue,
+        index: SpirvValue,
+        in_array: bool,
+    ) -> SpirvValue {
+        if in_array {
+            let zero = self.constant_int(index.ty, 0);
+            self.gep(ptr, &[zero, index])
+        } else {
+            self.gep(ptr, &[index])
+        }
+    }
+
+    fn memset_constant_size(
+        &mut self,
+        ptr: SpirvValue,
+        pat: SpirvValue,
+        size_bytes: u64,
+        in_array: bool,
+        align: Align,
+        flags: MemFlags,
+    ) {
         let size_elem = self
             .lookup_type(pat.ty)
             .sizeof(self)
             .expect("Memset on unsized values not supported");
+        if size_elem.bytes() == 0 {
+            return;
+        }
         let count = size_bytes / size_elem.bytes();
-        if count == 1 {
-            self.store(pat, ptr, Align::from_bytes(0).unwrap());
+        if count == 1 && !in_array {
+            self.store_with_flags(pat, ptr, align, flags);
         } else {
+            let elem_align = align.restrict_for_offset(size_elem);
             for index in 0..count {
                 let const_index = self.constant_u32(self.span(), index as u32);
-                let gep_ptr = self.gep(ptr, &[const_index]);
-                self.store(pat, gep_ptr, Align::from_bytes(0).unwrap());
+                let gep_ptr = self.memset_elem_ptr(ptr, const_index, in_array);
+                self.store_with_flags(pat, gep_ptr, elem_align, flags);
             }
         }
     }
 
-    // TODO: Test this is correct
-    fn memset_dynamic_size(&mut self, ptr: SpirvValue, pat: SpirvValue, size_bytes: SpirvValue) {
+    fn memset_dynamic_size(
+        &mut self,
+        ptr: SpirvValue,
+        pat: SpirvValue,
+        size_bytes: SpirvValue,
+        in_array: bool,
+        align: Align,
+        flags: MemFlags,
+    ) {
         let size_elem = self
             .lookup_type(pat.ty)
             .sizeof(self)
             .expect("Unable to memset a dynamic sized object");
+        if size_elem.bytes() == 0 {
+            return;
+        }
         let size_elem_const = self.constant_int(size_bytes.ty, size_elem.bytes());
         let zero = self.constant_int(size_bytes.ty, 0);
         let one = self.constant_int(size_bytes.ty, 1);
@@ -334,8 +656,8 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         let cond = header.icmp(IntPredicate::IntULT, current_index, count);
         header.cond_br(cond, body.llbb(), exit.llbb());
 
-        let gep_ptr = body.gep(ptr, &[current_index]);
-        body.store(pat, gep_ptr, zero_align);
+        let gep_ptr = body.memset_elem_ptr(ptr, current_index, in_array);
+        body.store_with_flags(pat, gep_ptr, align.restrict_for_offset(size_elem), flags);
         let current_index_plus_1 = body.add(current_index, one);
         body.store(current_index_plus_1, index, zero_align);
         body.br(header.llbb());
@@ -343,6 +665,36 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         *self = exit;
     }
 
+    /// Lowers an ordered (i.e. not `IntEQ`/`IntNE`) comparison of two `bool`s.
+    fn bool_ordered_cmp(&mut self, op: IntPredicate, lhs: SpirvValue, rhs: SpirvValue) -> Word {
+        use IntPredicate::*;
+        // As a signed 1-bit integer, `true` is `-1`, so the signed comparisons are the
+        // unsigned ones with the operands swapped. Every comparison is then `x > y` or
+        // `x >= y`, with `(x, y)` being either `(lhs, rhs)` or `(rhs, lhs)`.
+        let (x, y, or_equal) = match op {
+            IntUGT | IntSLT => (lhs, rhs, false),
+            IntUGE | IntSLE => (lhs, rhs, true),
+            IntULT | IntSGT => (rhs, lhs, false),
+            IntULE | IntSGE => (rhs, lhs, true),
+            IntEQ | IntNE => bug!("bool_ordered_cmp called with {:?}", op),
+        };
+        let b = SpirvType::Bool.def(self.span(), self);
+        // intel-compute-runtime doesn't like OpLogicalNot
+        let true_ = self.constant_bool(self.span(), true);
+        let not_y = self
+            .emit()
+            .logical_not_equal(b, None, y.def(self), true_.def(self))
+            .unwrap();
+        if or_equal {
+            // x >= y  =>  x || !y
+            self.emit().logical_or(b, None, x.def(self), not_y)
+        } else {
+            // x > y  =>  x && !y
+            self.emit().logical_and(b, None, x.def(self), not_y)
+        }
+        .unwrap()
+    }
+
     fn zombie_convert_ptr_to_u(&self, def: Word) {
         if !self.builder.has_capability(Capability::Addresses)
             && !self
@@ -433,6 +785,12 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                     indices.push((offset.bytes() / stride.bytes()).try_into().ok()?);
                     offset = Size::from_bytes(offset.bytes() % stride.bytes());
                 }
+                SpirvType::InterfaceBlock { inner_type } => {
+                    ty = inner_type;
+                    ty_kind = self.lookup_type(ty);
+
+                    indices.push(0);
+                }
                 _ => return None,
             }
 
@@ -544,73 +902,45 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
         else_llbb: Self::BasicBlock,
         cases: impl ExactSizeIterator<Item = (u128, Self::BasicBlock)>,
     ) {
-        fn construct_8(self_: &Builder<'_, '_>, signed: bool, v: u128) -> Operand {
-            if v > u8::MAX as u128 {
-                self_.fatal(&format!(
-                    "Switches to values above u8::MAX not supported: {:?}",
-                    v
-                ))
-            } else if signed {
-                // this cast chain can probably be collapsed, but, whatever, be safe
-                Operand::LiteralInt32(v as u8 as i8 as i32 as u32)
-            } else {
-                Operand::LiteralInt32(v as u8 as u32)
-            }
-        }
-        fn construct_16(self_: &Builder<'_, '_>, signed: bool, v: u128) -> Operand {
-            if v > u16::MAX as u128 {
+        // `v` holds the bits of the case value, which only need to fit the selector's width
+        // (sign-extended values are accepted too, for signed selectors). Literals narrower than
+        // 32 bits are sign-/zero-extended to a 32-bit word, as required for `OpSwitch`, and 64-bit
+        // selectors take a 64-bit literal.
+        fn construct_case(self_: &Builder<'_, '_>, signed: bool, width: u32, v: u128) -> Operand {
+            let mask = u128::MAX >> (128 - width);
+            if v & !mask != 0 && !(signed && v | mask == u128::MAX) {
                 self_.fatal(&format!(
-                    "Switches to values above u16::MAX not supported: {:?}",
-                    v
+                    "switch case value {:?} does not fit in the {}-bit selector",
+                    v, width
                 ))
-            } else if signed {
-                Operand::LiteralInt32(v as u16 as i16 as i32 as u32)
-            } else {
-                Operand::LiteralInt32(v as u16 as u32)
             }
-        }
-        fn construct_32(self_: &Builder<'_, '_>, _signed: bool, v: u128) -> Operand {
-            if v > u32::MAX as u128 {
-                self_.fatal(&format!(
-                    "Switches to values above u32::MAX not supported: {:?}",
-                    v
-                ))
+            let bits = (v & mask) as u64;
+            let sign_extended = if signed {
+                ((bits << (64 - width)) as i64 >> (64 - width)) as u64
             } else {
-                Operand::LiteralInt32(v as u32)
-            }
-        }
-        fn construct_64(self_: &Builder<'_, '_>, _signed: bool, v: u128) -> Operand {
-            if v > u64::MAX as u128 {
-                self_.fatal(&format!(
-                    "Switches to values above u64::MAX not supported: {:?}",
-                    v
-                ))
+                bits
+            };
+            if width == 64 {
+                Operand::LiteralInt64(bits)
             } else {
-                Operand::LiteralInt64(v as u64)
+                Operand::LiteralInt32(sign_extended as u32)
             }
         }
-        // pass in signed into the closure to be able to unify closure types
-        let (signed, construct_case) = match self.lookup_type(v.ty) {
-            SpirvType::Integer(width, signed) => {
-                let construct_case = match width {
-                    8 => construct_8,
-                    16 => construct_16,
-                    32 => construct_32,
-                    64 => construct_64,
-                    other => self.fatal(&format!(
-                        "switch selector cannot have width {} (only 8, 16, 32, and 64 bits allowed)",
-                        other
-                    )),
-                };
-                (signed, construct_case)
-            }
+        let (signed, width) = match self.lookup_type(v.ty) {
+            SpirvType::Integer(width, signed) => match width {
+                8 | 16 | 32 | 64 => (signed, width),
+                other => self.fatal(&format!(
+                    "switch selector cannot have width {} (only 8, 16, 32, and 64 bits allowed)",
+                    other
+                )),
+            },
             other => self.fatal(&format!(
                 "switch selector cannot have non-integer type {}",
                 other.debug(v.ty, self)
             )),
         };
         let cases = cases
-            .map(|(i, b)| (construct_case(self, signed, i), b))
+            .map(|(i, b)| (construct_case(self, signed, width, i), b))
             .collect::<Vec<_>>();
         self.emit().switch(v.def(self), else_llbb, cases).unwrap()
     }
@@ -753,25 +1083,98 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
     fn checked_binop(
         &mut self,
         oop: OverflowOp,
-        _ty: Ty<'_>,
+        ty: Ty<'_>,
         lhs: Self::Value,
         rhs: Self::Value,
     ) -> (Self::Value, Self::Value) {
-        let fals = self.constant_bool(self.span(), false);
-        let result = match oop {
-            OverflowOp::Add => (self.add(lhs, rhs), fals),
-            OverflowOp::Sub => (self.sub(lhs, rhs), fals),
-            OverflowOp::Mul => (self.mul(lhs, rhs), fals),
+        let signed = match ty.kind() {
+            TyKind::Int(_) => true,
+            TyKind::Uint(_) => false,
+            other => self.fatal(&format!(
+                "Unexpected {} type: {:#?}",
+                match oop {
+                    OverflowOp::Add => "checked add",
+                    OverflowOp::Sub => "checked sub",
+                    OverflowOp::Mul => "checked mul",
+                },
+                other
+            )),
         };
-        self.zombie(
-            result.0.def(self),
-            match oop {
-                OverflowOp::Add => "checked add is not supported yet",
-                OverflowOp::Sub => "checked sub is not supported yet",
-                OverflowOp::Mul => "checked mul is not supported yet",
-            },
-        );
-        result
+        let bool = SpirvType::Bool.def(self.span(), self);
+        let zero = self.constant_int(lhs.ty, 0);
+
+        // The `OpIAddCarry`/`OpISubBorrow`/`Op*MulExtended` instructions all return a
+        // `{ T, T }` struct, of the (low) result and the carry/borrow/high half.
+        let pair_ty = self.type_struct(&[lhs.ty, lhs.ty], false);
+        let pair_op = |this: &mut Self, op: Op| {
+            let (lhs, rhs) = (lhs.def(this), rhs.def(this));
+            let pair = {
+                let mut emit = this.emit();
+                match op {
+                    Op::IAddCarry => emit.i_add_carry(pair_ty, None, lhs, rhs),
+                    Op::ISubBorrow => emit.i_sub_borrow(pair_ty, None, lhs, rhs),
+                    Op::UMulExtended => emit.u_mul_extended(pair_ty, None, lhs, rhs),
+                    Op::SMulExtended => emit.s_mul_extended(pair_ty, None, lhs, rhs),
+                    _ => bug!("unexpected checked_binop op {:?}", op),
+                }
+                .unwrap()
+                .with_type(pair_ty)
+            };
+            (this.extract_value(pair, 0), this.extract_value(pair, 1))
+        };
+
+        match (oop, signed) {
+            (OverflowOp::Add, false) | (OverflowOp::Sub, false) => {
+                let op = if let OverflowOp::Add = oop {
+                    Op::IAddCarry
+                } else {
+                    Op::ISubBorrow
+                };
+                let (result, carry) = pair_op(self, op);
+                (result, self.icmp(IntPredicate::IntNE, carry, zero))
+            }
+            (OverflowOp::Add, true) | (OverflowOp::Sub, true) => {
+                // Signed overflow happened iff the result moved from `lhs` in the opposite
+                // direction of what the sign of `rhs` says it should (for `rhs != 0`), i.e.
+                // `(rhs < 0) != (result < lhs)` for `add`, and `(rhs < 0) != (lhs < result)`
+                // for `sub`.
+                let result = if let OverflowOp::Add = oop {
+                    self.add(lhs, rhs)
+                } else {
+                    self.sub(lhs, rhs)
+                };
+                let rhs_negative = self.icmp(IntPredicate::IntSLT, rhs, zero);
+                let moved_down = if let OverflowOp::Add = oop {
+                    self.icmp(IntPredicate::IntSLT, result, lhs)
+                } else {
+                    self.icmp(IntPredicate::IntSLT, lhs, result)
+                };
+                let overflow = self
+                    .emit()
+                    .logical_not_equal(bool, None, rhs_negative.def(self), moved_down.def(self))
+                    .unwrap()
+                    .with_type(bool);
+                (result, overflow)
+            }
+            (OverflowOp::Mul, false) => {
+                let (result, high) = pair_op(self, Op::UMulExtended);
+                (result, self.icmp(IntPredicate::IntNE, high, zero))
+            }
+            (OverflowOp::Mul, true) => {
+                // The high half has to be the sign extension of the low half.
+                let (result, high) = pair_op(self, Op::SMulExtended);
+                let width = match self.lookup_type(lhs.ty) {
+                    SpirvType::Integer(width, _) => width,
+                    other => self.fatal(&format!(
+                        "checked mul on non-integer type {}",
+                        other.debug(lhs.ty, self)
+                    )),
+                };
+                let sign_shift = self.constant_int(lhs.ty, width as u64 - 1);
+                let sign = self.ashr(result, sign_shift);
+                (result, self.icmp(IntPredicate::IntNE, high, sign))
+            }
+        }
     }
 
     fn from_immediate(&mut self, val: Self::Value) -> Self::Value {
@@ -834,29 +1237,12 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
         self.fatal("array alloca not supported yet")
     }
 
-    fn load(&mut self, ptr: Self::Value, _align: Align) -> Self::Value {
-        // See comment on `SpirvValueKind::ConstantPointer`
-        if let Some(value) = ptr.const_ptr_val(self) {
-            return value;
-        }
-        let ty = match self.lookup_type(ptr.ty) {
-            SpirvType::Pointer { pointee } => pointee,
-            ty => self.fatal(&format!(
-                "load called on variable that wasn't a pointer: {:?}",
-                ty
-            )),
-        };
-        self.emit()
-            .load(ty, None, ptr.def(self), None, empty())
-            .unwrap()
-            .with_type(ty)
+    fn load(&mut self, ptr: Self::Value, align: Align) -> Self::Value {
+        self.load_with_flags(ptr, align, MemFlags::empty())
     }
 
     fn volatile_load(&mut self, ptr: Self::Value) -> Self::Value {
-        // TODO: Implement this
-        let result = self.load(ptr, Align::from_bytes(0).unwrap());
-        self.zombie(result.def(self), "volatile load is not supported yet");
-        result
+        self.load_with_flags(ptr, Align::from_bytes(0).unwrap(), MemFlags::VOLATILE)
     }
 
     fn atomic_load(&mut self, ptr: Self::Value, order: AtomicOrdering, _size: Size) -> Self::Value {
@@ -867,8 +1253,7 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
                 ty
             )),
         };
-        // TODO: Default to device scope
-        let memory = self.constant_u32(self.span(), Scope::Device as u32);
+        let memory = self.default_atomic_scope();
         let semantics = self.ordering_to_semantics_def(order);
         let result = self
             .emit()
@@ -959,7 +1344,17 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
         // ignore
     }
 
-    fn store(&mut self, val: Self::Value, ptr: Self::Value, _align: Align) -> Self::Value {
+    fn store(&mut self, val: Self::Value, ptr: Self::Value, align: Align) -> Self::Value {
+        self.store_with_flags(val, ptr, align, MemFlags::empty())
+    }
+
+    fn store_with_flags(
+        &mut self,
+        val: Self::Value,
+        ptr: Self::Value,
+        align: Align,
+        flags: MemFlags,
+    ) -> Self::Value {
         let ptr_elem_ty = match self.lookup_type(ptr.ty) {
             SpirvType::Pointer { pointee } => pointee,
             ty => self.fatal(&format!(
@@ -968,28 +1363,13 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
             )),
         };
         assert_ty_eq!(self, ptr_elem_ty, val.ty);
+        let (access, operands) = self.memory_access(align, flags, true, false);
         self.emit()
-            .store(ptr.def(self), val.def(self), None, empty())
+            .store(ptr.def(self), val.def(self), access, operands)
             .unwrap();
         val
     }
 
-    fn store_with_flags(
-        &mut self,
-        val: Self::Value,
-        ptr: Self::Value,
-        align: Align,
-        flags: MemFlags,
-    ) -> Self::Value {
-        if flags != MemFlags::empty() {
-            self.err(&format!(
-                "store_with_flags is not supported yet: {:?}",
-                flags
-            ));
-        }
-        self.store(val, ptr, align)
-    }
-
     fn atomic_store(
         &mut self,
         val: Self::Value,
@@ -1005,8 +1385,7 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
             )),
         };
         assert_ty_eq!(self, ptr_elem_ty, val.ty);
-        // TODO: Default to device scope
-        let memory = self.constant_u32(self.span(), Scope::Device as u32);
+        let memory = self.default_atomic_scope();
         let semantics = self.ordering_to_semantics_def(order);
         self.validate_atomic(val.ty, ptr.def(self));
         self.emit()
@@ -1361,7 +1740,7 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
                 other
             )),
         };
-        if val.ty == dest_ty {
+        if val.ty == dest_ty || self.is_vtable(val) {
             val
         } else if let Some(indices) =
             self.recover_access_chain_from_offset(val_pointee, dest_pointee, Size::ZERO)
@@ -1374,6 +1753,17 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
                 .access_chain(dest_ty, None, val.def(self), indices)
                 .unwrap()
                 .with_type(dest_ty)
+        } else if self.logical_addressing_model()
+            && !self.is_system_crate()
+            && matches!(self.lookup_type(dest_pointee), SpirvType::Adt { ref field_types, .. } if field_types.is_empty())
+        {
+            // Type-erased pointers (e.g. the data pointer of `&dyn Trait`) can't be used for
+            // anything other than casting them back, which the linker removes after inlining
+            // (see `linker/fn_pointers.rs`), so this doesn't need the `LogicalPtrCast` workaround.
+            self.emit()
+                .bitcast(dest_ty, None, val.def(self))
+                .unwrap()
+                .with_type(dest_ty)
         } else if self.logical_addressing_model() {
             // Defer the cast so that it has a chance to be avoided.
             SpirvValue {
@@ -1429,103 +1819,20 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
                     .s_less_than_equal(b, None, lhs.def(self), rhs.def(self)),
             },
             SpirvType::Pointer { .. } => match op {
-                IntEQ => {
-                    if self.emit().version().unwrap() > (1, 3) {
-                        self.emit().ptr_equal(b, None, lhs.def(self), rhs.def(self))
-                    } else {
-                        let int_ty = self.type_usize();
-                        let lhs = self
-                            .emit()
-                            .convert_ptr_to_u(int_ty, None, lhs.def(self))
-                            .unwrap();
-                        self.zombie_convert_ptr_to_u(lhs);
-                        let rhs = self
-                            .emit()
-                            .convert_ptr_to_u(int_ty, None, rhs.def(self))
-                            .unwrap();
-                        self.zombie_convert_ptr_to_u(rhs);
-                        self.emit().i_not_equal(b, None, lhs, rhs)
-                    }
+                IntEQ if self.emit().version().unwrap() > (1, 3) => {
+                    self.emit().ptr_equal(b, None, lhs.def(self), rhs.def(self))
                 }
-                IntNE => {
-                    if self.emit().version().unwrap() > (1, 3) {
-                        self.emit()
-                            .ptr_not_equal(b, None, lhs.def(self), rhs.def(self))
-                    } else {
-                        let int_ty = self.type_usize();
-                        let lhs = self
-                            .emit()
-                            .convert_ptr_to_u(int_ty, None, lhs.def(self))
-                            .unwrap();
-                        self.zombie_convert_ptr_to_u(lhs);
-                        let rhs = self
-                            .emit()
-                            .convert_ptr_to_u(int_ty, None, rhs.def(self))
-                            .unwrap();
-                        self.zombie_convert_ptr_to_u(rhs);
-                        self.emit().i_not_equal(b, None, lhs, rhs)
-                    }
-                }
-                IntUGT => {
-                    let int_ty = self.type_usize();
-                    let lhs = self
-                        .emit()
-                        .convert_ptr_to_u(int_ty, None, lhs.def(self))
-                        .unwrap();
-                    self.zombie_convert_ptr_to_u(lhs);
-                    let rhs = self
-                        .emit()
-                        .convert_ptr_to_u(int_ty, None, rhs.def(self))
-                        .unwrap();
-                    self.zombie_convert_ptr_to_u(rhs);
-                    self.emit().u_greater_than(b, None, lhs, rhs)
+                IntNE if self.emit().version().unwrap() > (1, 3) => {
+                    self.emit()
+                        .ptr_not_equal(b, None, lhs.def(self), rhs.def(self))
                 }
-                IntUGE => {
+                // Other comparisons (and equality before SPIR-V 1.4) compare the addresses.
+                _ => {
                     let int_ty = self.type_usize();
-                    let lhs = self
-                        .emit()
-                        .convert_ptr_to_u(int_ty, None, lhs.def(self))
-                        .unwrap();
-                    self.zombie_convert_ptr_to_u(lhs);
-                    let rhs = self
-                        .emit()
-                        .convert_ptr_to_u(int_ty, None, rhs.def(self))
-                        .unwrap();
-                    self.zombie_convert_ptr_to_u(rhs);
-                    self.emit().u_greater_than_equal(b, None, lhs, rhs)
+                    let lhs = self.ptrtoint(lhs, int_ty);
+                    let rhs = self.ptrtoint(rhs, int_ty);
+                    return self.icmp(op, lhs, rhs);
                 }
-                IntULT => {
-                    let int_ty = self.type_usize();
-                    let lhs = self
-                        .emit()
-                        .convert_ptr_to_u(int_ty, None, lhs.def(self))
-                        .unwrap();
-                    self.zombie_convert_ptr_to_u(lhs);
-                    let rhs = self
-                        .emit()
-                        .convert_ptr_to_u(int_ty, None, rhs.def(self))
-                        .unwrap();
-                    self.zombie_convert_ptr_to_u(rhs);
-                    self.emit().u_less_than(b, None, lhs, rhs)
-                }
-                IntULE => {
-                    let int_ty = self.type_usize();
-                    let lhs = self
-                        .emit()
-                        .convert_ptr_to_u(int_ty, None, lhs.def(self))
-                        .unwrap();
-                    self.zombie_convert_ptr_to_u(lhs);
-                    let rhs = self
-                        .emit()
-                        .convert_ptr_to_u(int_ty, None, rhs.def(self))
-                        .unwrap();
-                    self.zombie_convert_ptr_to_u(rhs);
-                    self.emit().u_less_than_equal(b, None, lhs, rhs)
-                }
-                IntSGT => self.fatal("TODO: pointer operator IntSGT not implemented yet"),
-                IntSGE => self.fatal("TODO: pointer operator IntSGE not implemented yet"),
-                IntSLT => self.fatal("TODO: pointer operator IntSLT not implemented yet"),
-                IntSLE => self.fatal("TODO: pointer operator IntSLE not implemented yet"),
             },
             SpirvType::Bool => match op {
                 IntEQ => self
@@ -1534,47 +1841,7 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
                 IntNE => self
                     .emit()
                     .logical_not_equal(b, None, lhs.def(self), rhs.def(self)),
-                // x > y  =>  x && !y
-                IntUGT => {
-                    // intel-compute-runtime doesn't like OpLogicalNot
-                    let true_ = self.constant_bool(self.span(), true);
-                    let rhs = self
-                        .emit()
-                        .logical_not_equal(b, None, rhs.def(self), true_.def(self))
-                        .unwrap();
-                    self.emit().logical_and(b, None, lhs.def(self), rhs)
-                }
-                // x >= y  =>  x || !y
-                IntUGE => {
-                    let true_ = self.constant_bool(self.span(), true);
-                    let rhs = self
-                        .emit()
-                        .logical_not_equal(b, None, rhs.def(self), true_.def(self))
-                        .unwrap();
-                    self.emit().logical_or(b, None, lhs.def(self), rhs)
-                }
-                // x < y  =>  !x && y
-                IntULE => {
-                    let true_ = self.constant_bool(self.span(), true);
-                    let lhs = self
-                        .emit()
-                        .logical_not_equal(b, None, lhs.def(self), true_.def(self))
-                        .unwrap();
-                    self.emit().logical_and(b, None, lhs, rhs.def(self))
-                }
-                // x <= y  =>  !x || y
-                IntULT => {
-                    let true_ = self.constant_bool(self.span(), true);
-                    let lhs = self
-                        .emit()
-                        .logical_not_equal(b, None, lhs.def(self), true_.def(self))
-                        .unwrap();
-                    self.emit().logical_or(b, None, lhs, rhs.def(self))
-                }
-                IntSGT => self.fatal("TODO: boolean operator IntSGT not implemented yet"),
-                IntSGE => self.fatal("TODO: boolean operator IntSGE not implemented yet"),
-                IntSLT => self.fatal("TODO: boolean operator IntSLT not implemented yet"),
-                IntSLE => self.fatal("TODO: boolean operator IntSLE not implemented yet"),
+                _ => Ok(self.bool_ordered_cmp(op, lhs, rhs)),
             },
             other => self.fatal(&format!(
                 "Int comparison not implemented on {}",
@@ -1639,18 +1906,13 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
     fn memcpy(
         &mut self,
         dst: Self::Value,
-        _dst_align: Align,
+        dst_align: Align,
         src: Self::Value,
-        _src_align: Align,
+        src_align: Align,
         size: Self::Value,
         flags: MemFlags,
     ) {
-        if flags != MemFlags::empty() {
-            self.err(&format!(
-                "memcpy with mem flags is not supported yet: {:?}",
-                flags
-            ));
-        }
+        let operands = self.copy_memory_access(dst_align, src_align, flags);
         let const_size = self.builder.lookup_const_u64(size);
         if const_size == Some(0) {
             // Nothing to do!
@@ -1665,10 +1927,10 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
             // See comment on `SpirvValueKind::ConstantPointer`
 
             if let Some(const_value) = src.const_ptr_val(self) {
-                self.store(const_value, dst, Align::from_bytes(0).unwrap());
+                self.store_with_flags(const_value, dst, dst_align, flags);
             } else {
                 self.emit()
-                    .copy_memory(dst.def(self), src.def(self), None, None, empty())
+                    .copy_memory(dst.def(self), src.def(self), None, None, operands)
                     .unwrap();
             }
         } else {
@@ -1679,7 +1941,7 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
                     size.def(self),
                     None,
                     None,
-                    empty(),
+                    operands,
                 )
                 .unwrap();
             if !self.builder.has_capability(Capability::Addresses) {
@@ -1708,15 +1970,9 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
         ptr: Self::Value,
         fill_byte: Self::Value,
         size: Self::Value,
-        _align: Align,
+        align: Align,
         flags: MemFlags,
     ) {
-        if flags != MemFlags::empty() {
-            self.err(&format!(
-                "memset with mem flags is not supported yet: {:?}",
-                flags
-            ));
-        }
         let elem_ty = match self.lookup_type(ptr.ty) {
             SpirvType::Pointer { pointee } => pointee,
             _ => self.fatal(&format!(
@@ -1724,6 +1980,11 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
                 self.debug_type(ptr.ty)
             )),
         };
+        // Runtime arrays can't be loaded/stored as a whole, so fill each element instead.
+        let (elem_ty, in_array) = match self.lookup_type(elem_ty) {
+            SpirvType::RuntimeArray { element } => (element, true),
+            _ => (elem_ty, false),
+        };
         let elem_ty_spv = self.lookup_type(elem_ty);
         let pat = match self.builder.lookup_const_u64(fill_byte) {
             Some(fill_byte) => self.memset_const_pattern(&elem_ty_spv, fill_byte as u8),
@@ -1731,8 +1992,8 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
         }
         .with_type(elem_ty);
         match self.builder.lookup_const_u64(size) {
-            Some(size) => self.memset_constant_size(ptr, pat, size),
-            None => self.memset_dynamic_size(ptr, pat, size),
+            Some(size) => self.memset_constant_size(ptr, pat, size, in_array, align, flags),
+            None => self.memset_dynamic_size(ptr, pat, size, in_array, align, flags),
         }
     }
 
@@ -1915,8 +2176,7 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
         assert_ty_eq!(self, dst_pointee_ty, cmp.ty);
         assert_ty_eq!(self, dst_pointee_ty, src.ty);
         self.validate_atomic(dst_pointee_ty, dst.def(self));
-        // TODO: Default to device scope
-        let memory = self.constant_u32(self.span(), Scope::Device as u32);
+        let memory = self.default_atomic_scope();
         let semantics_equal = self.ordering_to_semantics_def(order);
         let semantics_unequal = self.ordering_to_semantics_def(failure_order);
         // Note: OpAtomicCompareExchangeWeak is deprecated, and has the same semantics
@@ -1951,13 +2211,13 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
         };
         assert_ty_eq!(self, dst_pointee_ty, src.ty);
         self.validate_atomic(dst_pointee_ty, dst.def(self));
-        // TODO: Default to device scope
-        let memory = self
-            .constant_u32(self.span(), Scope::Device as u32)
-            .def(self);
+        use AtomicRmwBinOp::*;
+        if let AtomicNand = op {
+            return self.atomic_nand(dst, src, order);
+        }
+        let memory = self.default_atomic_scope().def(self);
         let semantics = self.ordering_to_semantics_def(order).def(self);
         let mut emit = self.emit();
-        use AtomicRmwBinOp::*;
         match op {
             AtomicXchg => emit.atomic_exchange(
                 src.ty,
@@ -1991,7 +2251,7 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
                 semantics,
                 src.def(self),
             ),
-            AtomicNand => self.fatal("atomic nand is not supported"),
+            AtomicNand => unreachable!(),
             AtomicOr => emit.atomic_or(
                 src.ty,
                 None,
@@ -2047,10 +2307,7 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
 
     fn atomic_fence(&mut self, order: AtomicOrdering, _scope: SynchronizationScope) {
         // Ignore sync scope (it only has "single thread" and "cross thread")
-        // TODO: Default to device scope
-        let memory = self
-            .constant_u32(self.span(), Scope::Device as u32)
-            .def(self);
+        let memory = self.default_atomic_scope().def(self);
         let semantics = self.ordering_to_semantics_def(order).def(self);
         self.emit().memory_barrier(memory, semantics).unwrap();
     }
@@ -2092,32 +2349,27 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
         // NOTE(eddyb) see the comment on `SpirvValueKind::FnAddr`, this should
         // be fixed upstream, so we never see any "function pointer" values being
         // created just to perform direct calls.
-        let (callee_val, result_type, argument_types) = match self.lookup_type(callee.ty) {
+        let (callee_val, indirect, result_type, argument_types) = match self.lookup_type(callee.ty)
+        {
             // HACK(eddyb) this seems to be needed, but it's not what `get_fn_addr`
             // produces, are these coming from inside `rustc_codegen_spirv`?
             SpirvType::Function {
                 return_type,
                 arguments,
-            } => (callee.def(self), return_type, arguments),
+            } => (callee.def(self), false, return_type, arguments),
 
             SpirvType::Pointer { pointee } => match self.lookup_type(pointee) {
                 SpirvType::Function {
                     return_type,
                     arguments,
-                } => (
-                    match callee.kind {
-                        SpirvValueKind::FnAddr { function } => function,
-
-                        // Truly indirect call.
-                        _ => {
-                            let fn_ptr_val = callee.def(self);
-                            self.zombie(fn_ptr_val, "indirect calls are not supported in SPIR-V");
-                            fn_ptr_val
-                        }
-                    },
-                    return_type,
-                    arguments,
-                ),
+                } => match callee.kind {
+                    SpirvValueKind::FnAddr { function } => {
+                        (function, false, return_type, arguments)
+                    }
+
+                    // Truly indirect call.
+                    _ => (callee.def(self), true, return_type, arguments),
+                },
                 _ => bug!(
                     "call expected `fn` pointer to point to function type, got `{}`",
                     self.debug_type(pointee)
@@ -2152,6 +2404,24 @@ impl<'a, 'tcx> BuilderMethods<'a, 'tcx> for Builder<'a, 'tcx> {
             // needing to materialize `&core::panic::Location` or `format_args!`.
             self.abort();
             self.undef(result_type)
+        } else if indirect {
+            // The linker turns this into an `OpSwitch` over all the functions whose address
+            // is taken, and which have the right type (see `linker/fn_pointers.rs`).
+            let operands = once(callee_val)
+                .chain(args.iter().map(|arg| arg.def(self)))
+                .map(Operand::IdRef)
+                .collect();
+            let mut emit = self.emit();
+            let result_id = emit.id();
+            // TODO: rspirv doesn't have a function_pointer_call_intel function
+            let inst = Instruction::new(
+                Op::FunctionPointerCallINTEL,
+                Some(result_type),
+                Some(result_id),
+                operands,
+            );
+            emit.insert_into_block(InsertPoint::End, inst).unwrap();
+            result_id.with_type(result_type)
         } else {
             let args = args.iter().map(|arg| arg.def(self)).collect::<Vec<_>>();
             self.emit()
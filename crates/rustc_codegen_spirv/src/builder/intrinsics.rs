@@ -4,15 +4,17 @@ use crate::builder_spirv::{SpirvValue, SpirvValueExt};
 use crate::codegen_cx::CodegenCx;
 use crate::spirv_type::SpirvType;
 use rspirv::spirv::{CLOp, GLOp};
+use rustc_codegen_ssa::common::IntPredicate;
 use rustc_codegen_ssa::mir::operand::OperandRef;
 use rustc_codegen_ssa::mir::place::PlaceRef;
-use rustc_codegen_ssa::traits::{BuilderMethods, IntrinsicCallMethods};
+use rustc_codegen_ssa::traits::{BuilderMethods, IntrinsicCallMethods, OverflowOp};
+use rustc_codegen_ssa::MemFlags;
 use rustc_middle::bug;
 use rustc_middle::ty::{FnDef, Instance, ParamEnv, Ty, TyKind};
 use rustc_span::source_map::Span;
 use rustc_span::sym;
 use rustc_target::abi::call::{FnAbi, PassMode};
-use rustc_target::abi::LayoutOf;
+use rustc_target::abi::{Align, LayoutOf};
 
 fn int_type_width_signed(ty: Ty<'_>, cx: &CodegenCx<'_>) -> Option<(u64, bool)> {
     match ty.kind() {
@@ -31,6 +33,48 @@ fn int_type_width_signed(ty: Ty<'_>, cx: &CodegenCx<'_>) -> Option<(u64, bool)>
 }
 
 impl Builder<'_, '_> {
+    /// Clamps the result of `checked_binop` to the range of `ty`, on overflow.
+    fn saturating_binop(
+        &mut self,
+        oop: OverflowOp,
+        ty: Ty<'_>,
+        lhs: SpirvValue,
+        rhs: SpirvValue,
+    ) -> SpirvValue {
+        let (width, signed) = int_type_width_signed(ty, self).unwrap_or_else(|| {
+            self.fatal(&format!(
+                "Unimplemented saturating_{} intrinsic type: {:#?}",
+                if let OverflowOp::Add = oop {
+                    "add"
+                } else {
+                    "sub"
+                },
+                ty.kind()
+            ))
+        });
+        let (result, overflow) = self.checked_binop(oop, ty, lhs, rhs);
+        // `u128`/`i128` constants are zombies anyway, so just avoid overflowing the shifts.
+        let width = width.min(64);
+        let saturated = if signed {
+            // Overflow is towards `MAX` when adding a positive value (or subtracting a negative
+            // one), and towards `MIN` otherwise.
+            let min = self.constant_int(lhs.ty, 1 << (width - 1));
+            let max = self.constant_int(lhs.ty, (1 << (width - 1)) - 1);
+            let zero = self.constant_int(lhs.ty, 0);
+            let rhs_negative = self.icmp(IntPredicate::IntSLT, rhs, zero);
+            match oop {
+                OverflowOp::Add => self.select(rhs_negative, min, max),
+                _ => self.select(rhs_negative, max, min),
+            }
+        } else {
+            match oop {
+                OverflowOp::Add => self.constant_int(lhs.ty, u64::MAX >> (64 - width)),
+                _ => self.constant_int(lhs.ty, 0),
+            }
+        };
+        self.select(overflow, saturated, result)
+    }
+
     pub fn copysign(&mut self, val: SpirvValue, sign: SpirvValue) -> SpirvValue {
         let width = match self.lookup_type(val.ty) {
             SpirvType::Float(width) => width,
@@ -106,7 +150,12 @@ impl<'a, 'tcx> IntrinsicCallMethods<'tcx> for Builder<'a, 'tcx> {
                     let pointer = SpirvType::Pointer { pointee }.def(self.span(), self);
                     ptr = self.pointercast(ptr, pointer);
                 }
-                let load = self.volatile_load(ptr);
+                let load = if name == sym::unaligned_volatile_load {
+                    let flags = MemFlags::VOLATILE | MemFlags::UNALIGNED;
+                    self.load_with_flags(ptr, Align::from_bytes(0).unwrap(), flags)
+                } else {
+                    self.volatile_load(ptr)
+                };
                 self.to_immediate(load, self.layout_of(tp_ty))
             }
 
@@ -121,35 +170,21 @@ impl<'a, 'tcx> IntrinsicCallMethods<'tcx> for Builder<'a, 'tcx> {
 
             sym::saturating_add => {
                 assert_eq!(arg_tys[0], arg_tys[1]);
-                let result = match &arg_tys[0].kind() {
-                    TyKind::Int(_) | TyKind::Uint(_) => {
-                        self.add(args[0].immediate(), args[1].immediate())
-                    }
-                    TyKind::Float(_) => self.fadd(args[0].immediate(), args[1].immediate()),
-                    other => self.fatal(&format!(
-                        "Unimplemented saturating_add intrinsic type: {:#?}",
-                        other
-                    )),
-                };
-                // TODO: Implement this
-                self.zombie(result.def(self), "saturating_add is not implemented yet");
-                result
+                self.saturating_binop(
+                    OverflowOp::Add,
+                    arg_tys[0],
+                    args[0].immediate(),
+                    args[1].immediate(),
+                )
             }
             sym::saturating_sub => {
                 assert_eq!(arg_tys[0], arg_tys[1]);
-                let result = match &arg_tys[0].kind() {
-                    TyKind::Int(_) | TyKind::Uint(_) => {
-                        self.sub(args[0].immediate(), args[1].immediate())
-                    }
-                    TyKind::Float(_) => self.fsub(args[0].immediate(), args[1].immediate()),
-                    other => self.fatal(&format!(
-                        "Unimplemented saturating_sub intrinsic type: {:#?}",
-                        other
-                    )),
-                };
-                // TODO: Implement this
-                self.zombie(result.def(self), "saturating_sub is not implemented yet");
-                result
+                self.saturating_binop(
+                    OverflowOp::Sub,
+                    arg_tys[0],
+                    args[0].immediate(),
+                    args[1].immediate(),
+                )
             }
 
             // TODO: Configure these to be ocl vs. gl ext instructions, etc.
@@ -1,7 +1,11 @@
 use super::Builder;
 use crate::builder_spirv::{SpirvValue, SpirvValueExt};
-use rspirv::spirv::{GLOp, Word};
-use rustc_codegen_ssa::traits::BuilderMethods;
+use crate::spirv_type::SpirvType;
+use rspirv::spirv::{CLOp, GLOp, Word};
+use rustc_codegen_ssa::common::{IntPredicate, RealPredicate};
+use rustc_codegen_ssa::traits::{BaseTypeMethods, BuilderMethods};
+use rustc_middle::bug;
+use rustc_target::abi::Align;
 
 #[derive(Copy, Clone, Debug)]
 pub enum LibmCustomIntrinsic {
@@ -236,116 +240,822 @@ impl Builder<'_, '_> {
                 // log10(x) == (1 / ln(10)) * ln(x)
                 let mul = self.constant_float(args[0].ty, 1.0 / 10.0f64.ln());
                 let ln = self.gl_op(GLOp::Log, result_type, [args[0]]);
-                self.mul(mul, ln)
+                self.fmul(mul, ln)
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Log1p) => {
                 assert_eq!(args.len(), 1);
                 let one = self.constant_float(args[0].ty, 1.0);
-                let add = self.add(args[0], one);
+                let add = self.fadd(args[0], one);
                 self.gl_op(GLOp::Log, result_type, &[add])
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Exp10) => {
                 assert_eq!(args.len(), 1);
                 // exp10(x) == exp(x * log(10));
                 let log10 = self.constant_float(args[0].ty, 10.0f64.ln());
-                let mul = self.mul(args[0], log10);
+                let mul = self.fmul(args[0], log10);
                 self.gl_op(GLOp::Exp, result_type, [mul])
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Expm1) => {
                 let exp = self.gl_op(GLOp::Exp, args[0].ty, &[args[0]]);
                 let one = self.constant_float(exp.ty, 1.0);
-                self.sub(exp, one)
+                self.fsub(exp, one)
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Erf) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Erf not supported yet");
-                undef
+                assert_eq!(args.len(), 1);
+                if self.kernel_mode {
+                    return self.cl_op(CLOp::erf, result_type, args);
+                }
+                // erf(x) == sign(x) * (1 - erfc(|x|))
+                let x = args[0];
+                let ax = self.gl_op(GLOp::FAbs, x.ty, [x]);
+                let erfc = self.erfc_abs(ax);
+                let one = self.constant_float(x.ty, 1.0);
+                let erf = self.fsub(one, erfc);
+                self.copysign(erf, x)
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Erfc) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Erfc not supported yet");
-                undef
+                assert_eq!(args.len(), 1);
+                if self.kernel_mode {
+                    return self.cl_op(CLOp::erfc, result_type, args);
+                }
+                // erfc(x) == 2 - erfc(-x)
+                let x = args[0];
+                let ax = self.gl_op(GLOp::FAbs, x.ty, [x]);
+                let erfc = self.erfc_abs(ax);
+                let two = self.constant_float(x.ty, 2.0);
+                let reflected = self.fsub(two, erfc);
+                let zero = self.constant_float(x.ty, 0.0);
+                let negative = self.fcmp(RealPredicate::RealOLT, x, zero);
+                self.select(negative, reflected, erfc)
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Fdim) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Fdim not supported yet");
-                undef
+                assert_eq!(args.len(), 2);
+                if self.kernel_mode {
+                    return self.cl_op(CLOp::fdim, result_type, args);
+                }
+                // Ordered comparison, so that NaNs end up in the `x - y` case.
+                let (x, y) = (args[0], args[1]);
+                let diff = self.fsub(x, y);
+                let zero = self.constant_float(x.ty, 0.0);
+                let le = self.fcmp(RealPredicate::RealOLE, x, y);
+                self.select(le, zero, diff)
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Hypot) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Hypot not supported yet");
-                undef
+                assert_eq!(args.len(), 2);
+                if self.kernel_mode {
+                    return self.cl_op(CLOp::hypot, result_type, args);
+                }
+                self.hypot(args[0], args[1])
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Ilogb) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Ilogb not supported yet");
-                undef
+                assert_eq!(args.len(), 1);
+                if self.kernel_mode {
+                    return self.cl_op(CLOp::ilogb, result_type, args);
+                }
+                self.ilogb(args[0], result_type)
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::J0) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "J0 not supported yet");
-                undef
+                assert_eq!(args.len(), 1);
+                self.bessel_j0(args[0])
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Y0) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Y0 not supported yet");
-                undef
+                assert_eq!(args.len(), 1);
+                self.bessel_y0(args[0])
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::J1) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "J1 not supported yet");
-                undef
+                assert_eq!(args.len(), 1);
+                self.bessel_j1(args[0])
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Y1) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Y1 not supported yet");
-                undef
+                assert_eq!(args.len(), 1);
+                self.bessel_y1(args[0])
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Jn) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Jn not supported yet");
-                undef
+                assert_eq!(args.len(), 2);
+                let (n, x) = (args[0], args[1]);
+                let j0 = self.bessel_j0(x);
+                let j1 = self.bessel_j1(x);
+                let jn = self.bessel_recurrence(n, x, j0, j1);
+                // The recurrence divides by `x`, but `J_n(0)` is `0` for all `n != 0`.
+                let zero = self.constant_float(x.ty, 0.0);
+                let zero_n = self.constant_int(n.ty, 0);
+                let x_is_zero = self.fcmp(RealPredicate::RealOEQ, x, zero);
+                let n_is_zero = self.icmp(IntPredicate::IntEQ, n, zero_n);
+                let at_zero = self.select(n_is_zero, j0, zero);
+                self.select(x_is_zero, at_zero, jn)
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Yn) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Yn not supported yet");
-                undef
+                assert_eq!(args.len(), 2);
+                let (n, x) = (args[0], args[1]);
+                let y0 = self.bessel_y0(x);
+                let y1 = self.bessel_y1(x);
+                self.bessel_recurrence(n, x, y0, y1)
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Lgamma) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Lgamma not supported yet");
-                undef
+                assert_eq!(args.len(), 1);
+                if self.kernel_mode {
+                    return self.cl_op(CLOp::lgamma, result_type, args);
+                }
+                self.lgamma(args[0]).0
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::LgammaR) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "LgammaR not supported yet");
-                undef
+                assert_eq!(args.len(), 1);
+                let (lgamma, sign) = self.lgamma(args[0]);
+                let one = self.constant_i32(self.span(), 1);
+                let minus_one = self.constant_i32(self.span(), -1);
+                let sign = self.select(sign, minus_one, one);
+                self.emit()
+                    .composite_construct(
+                        result_type,
+                        None,
+                        [lgamma.def(self), sign.def(self)].iter().copied(),
+                    )
+                    .unwrap()
+                    .with_type(result_type)
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Tgamma) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Tgamma not supported yet");
-                undef
+                assert_eq!(args.len(), 1);
+                if self.kernel_mode {
+                    return self.cl_op(CLOp::tgamma, result_type, args);
+                }
+                self.tgamma(args[0])
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::NextAfter) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "NextAfter not supported yet");
-                undef
+                assert_eq!(args.len(), 2);
+                if self.kernel_mode {
+                    return self.cl_op(CLOp::nextafter, result_type, args);
+                }
+                self.nextafter(args[0], args[1])
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Remainder) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Remainder not supported yet");
-                undef
+                assert_eq!(args.len(), 2);
+                if self.kernel_mode {
+                    return self.cl_op(CLOp::remainder, result_type, args);
+                }
+                self.remainder(args[0], args[1]).0
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::RemQuo) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "RemQuo not supported yet");
-                undef
+                assert_eq!(args.len(), 2);
+                let (remainder, quotient) = self.remainder(args[0], args[1]);
+                let quotient = self.remquo_quotient(quotient);
+                self.emit()
+                    .composite_construct(
+                        result_type,
+                        None,
+                        [remainder.def(self), quotient.def(self)].iter().copied(),
+                    )
+                    .unwrap()
+                    .with_type(result_type)
             }
             LibmIntrinsic::Custom(LibmCustomIntrinsic::Scalbn) => {
-                let undef = self.undef(result_type);
-                self.zombie(undef.def(self), "Scalbn not supported yet");
-                undef
+                assert_eq!(args.len(), 2);
+                // With `FLT_RADIX == 2`, `scalbn` is just `ldexp`.
+                self.std_op(GLOp::Ldexp, CLOp::ldexp, result_type, args)
             }
         }
     }
+
+    /// Uses the GLSL.std.450 or OpenCL.std instruction, depending on which the target supports.
+    fn std_op(
+        &mut self,
+        gl: GLOp,
+        cl: CLOp,
+        result_type: Word,
+        args: impl AsRef<[SpirvValue]>,
+    ) -> SpirvValue {
+        if self.kernel_mode {
+            self.cl_op(cl, result_type, args)
+        } else {
+            self.gl_op(gl, result_type, args)
+        }
+    }
+
+    /// Evaluates `coeffs[0] + x * (coeffs[1] + x * (coeffs[2] + ...))`.
+    fn horner(&mut self, x: SpirvValue, coeffs: &[f64]) -> SpirvValue {
+        let (&last, rest) = coeffs.split_last().unwrap();
+        let mut acc = self.constant_float(x.ty, last);
+        for &coeff in rest.iter().rev() {
+            let mul = self.fmul(acc, x);
+            let coeff = self.constant_float(x.ty, coeff);
+            acc = self.fadd(mul, coeff);
+        }
+        acc
+    }
+
+    /// Evaluates the rational function `horner(x, num) / horner(x, den)`.
+    fn rational(&mut self, x: SpirvValue, num: &[f64], den: &[f64]) -> SpirvValue {
+        let num = self.horner(x, num);
+        let den = self.horner(x, den);
+        self.fdiv(num, den)
+    }
+
+    fn is_nan(&mut self, x: SpirvValue) -> SpirvValue {
+        let bool = SpirvType::Bool.def(self.span(), self);
+        self.emit()
+            .is_nan(bool, None, x.def(self))
+            .unwrap()
+            .with_type(bool)
+    }
+
+    fn is_inf(&mut self, x: SpirvValue) -> SpirvValue {
+        let bool = SpirvType::Bool.def(self.span(), self);
+        self.emit()
+            .is_inf(bool, None, x.def(self))
+            .unwrap()
+            .with_type(bool)
+    }
+
+    /// `erfc(ax)` for `ax >= 0`. `f32` uses Abramowitz & Stegun 7.1.26 (absolute error
+    /// `<= 1.5e-7`, around the precision of `f32` itself), while `f64` uses the Chebyshev
+    /// expansion from Numerical Recipes (3rd edition, 6.2.2), with a relative error around
+    /// `1e-15` for `ax < 10`, growing to `1e-13` as the result approaches underflow.
+    fn erfc_abs(&mut self, ax: SpirvValue) -> SpirvValue {
+        if let SpirvType::Float(64) = self.lookup_type(ax.ty) {
+            return self.erfc_abs_f64(ax);
+        }
+        let p = self.constant_float(ax.ty, 0.327_591_1);
+        let one = self.constant_float(ax.ty, 1.0);
+        let px = self.fmul(p, ax);
+        let denom = self.fadd(one, px);
+        let t = self.fdiv(one, denom);
+        let poly = self.horner(
+            t,
+            &[
+                0.0,
+                0.254_829_592,
+                -0.284_496_736,
+                1.421_413_741,
+                -1.453_152_027,
+                1.061_405_429,
+            ],
+        );
+        let ax2 = self.fmul(ax, ax);
+        let neg_ax2 = self.fneg(ax2);
+        let exp = self.std_op(GLOp::Exp, CLOp::exp, ax.ty, [neg_ax2]);
+        self.fmul(poly, exp)
+    }
+
+    /// `erfc(ax)` for `ax >= 0`, as `t * exp(-ax^2 + P(ty))` where `t = 2 / (2 + ax)`,
+    /// `ty = 4 * t - 2`, and `P` is a Chebyshev series (evaluated with Clenshaw's recurrence).
+    fn erfc_abs_f64(&mut self, ax: SpirvValue) -> SpirvValue {
+        const COEFFS: [f64; 28] = [
+            -1.302_653_719_781_709_4,
+            6.419_697_923_564_902_6e-1,
+            1.947_647_320_418_583_6e-2,
+            -9.561_514_786_808_631e-3,
+            -9.465_953_444_820_36e-4,
+            3.668_394_978_527_61e-4,
+            4.252_332_480_690_7e-5,
+            -2.027_857_811_253_4e-5,
+            -1.624_290_004_647e-6,
+            1.303_655_835_58e-6,
+            1.562_644_172_2e-8,
+            -8.523_809_591_5e-8,
+            6.529_054_439e-9,
+            5.059_343_495e-9,
+            -9.913_641_56e-10,
+            -2.273_651_22e-10,
+            9.646_791_1e-11,
+            2.394_038e-12,
+            -6.886_027e-12,
+            8.944_87e-13,
+            3.130_92e-13,
+            -1.127_08e-13,
+            3.81e-16,
+            7.106e-15,
+            -1.523e-15,
+            -9.4e-17,
+            1.21e-16,
+            -2.8e-17,
+        ];
+        let two = self.constant_float(ax.ty, 2.0);
+        let four = self.constant_float(ax.ty, 4.0);
+        let half = self.constant_float(ax.ty, 0.5);
+        let denom = self.fadd(two, ax);
+        let t = self.fdiv(two, denom);
+        let four_t = self.fmul(four, t);
+        let ty = self.fsub(four_t, two);
+
+        let mut d = self.constant_float(ax.ty, 0.0);
+        let mut dd = d;
+        for &coeff in COEFFS[1..].iter().rev() {
+            // d, dd = ty * d - dd + coeff, d
+            let ty_d = self.fmul(ty, d);
+            let diff = self.fsub(ty_d, dd);
+            let coeff = self.constant_float(ax.ty, coeff);
+            dd = d;
+            d = self.fadd(diff, coeff);
+        }
+        // exponent = -ax^2 + 0.5 * (COEFFS[0] + ty * d) - dd
+        let c0 = self.constant_float(ax.ty, COEFFS[0]);
+        let ty_d = self.fmul(ty, d);
+        let sum = self.fadd(c0, ty_d);
+        let half_sum = self.fmul(half, sum);
+        let ax2 = self.fmul(ax, ax);
+        let exponent = self.fsub(half_sum, ax2);
+        let exponent = self.fsub(exponent, dd);
+        let exp = self.exp_f64(exponent);
+        self.fmul(t, exp)
+    }
+
+    /// `exp(x)` for `f64`, which GLSL.std.450's `Exp` doesn't support: `x` is reduced to
+    /// `r = x - k * ln(2)`, with `|r| <= ln(2) / 2`, then `exp(x) == 2^k * exp(r)`, with `exp(r)`
+    /// from its Taylor series (relative error around `2e-16`).
+    fn exp_f64(&mut self, x: SpirvValue) -> SpirvValue {
+        // `ln(2)` split in two (as in fdlibm), so that `k * LN2_HI` is exact.
+        const LN2_HI: f64 = 6.931_471_803_691_238e-1;
+        const LN2_LO: f64 = 1.908_214_929_270_587_7e-10;
+        // Beyond these, the result is `0` or infinity anyway, and `k` stays small.
+        let min = self.constant_float(x.ty, -746.0);
+        let max = self.constant_float(x.ty, 710.0);
+        let x = self.gl_op(GLOp::FClamp, x.ty, [x, min, max]);
+        let log2_e = self.constant_float(x.ty, std::f64::consts::LOG2_E);
+        let scaled = self.fmul(x, log2_e);
+        let k = self.gl_op(GLOp::RoundEven, x.ty, [scaled]);
+        let ln2_hi = self.constant_float(x.ty, LN2_HI);
+        let ln2_lo = self.constant_float(x.ty, LN2_LO);
+        let k_hi = self.fmul(k, ln2_hi);
+        let k_lo = self.fmul(k, ln2_lo);
+        let r = self.fsub(x, k_hi);
+        let r = self.fsub(r, k_lo);
+
+        let mut factorial = 1.0;
+        let mut coeffs = vec![1.0];
+        for n in 1..14 {
+            factorial *= f64::from(n);
+            coeffs.push(1.0 / factorial);
+        }
+        let exp_r = self.horner(r, &coeffs);
+        let i32_ty = SpirvType::Integer(32, true).def(self.span(), self);
+        let k = self.fptosi(k, i32_ty);
+        self.gl_op(GLOp::Ldexp, x.ty, [exp_r, k])
+    }
+
+    /// `hypot(x, y)`, computed as `max * sqrt(1 + (min / max)^2)` to avoid overflow/underflow,
+    /// with the same special cases as `libm` for zeros and infinities.
+    fn hypot(&mut self, x: SpirvValue, y: SpirvValue) -> SpirvValue {
+        let ax = self.gl_op(GLOp::FAbs, x.ty, [x]);
+        let ay = self.gl_op(GLOp::FAbs, x.ty, [y]);
+        let max = self.gl_op(GLOp::FMax, x.ty, [ax, ay]);
+        let min = self.gl_op(GLOp::FMin, x.ty, [ax, ay]);
+        let ratio = self.fdiv(min, max);
+        let ratio2 = self.fmul(ratio, ratio);
+        let one = self.constant_float(x.ty, 1.0);
+        let sum = self.fadd(one, ratio2);
+        let sqrt = self.gl_op(GLOp::Sqrt, x.ty, [sum]);
+        let result = self.fmul(max, sqrt);
+
+        let zero = self.constant_float(x.ty, 0.0);
+        let max_is_zero = self.fcmp(RealPredicate::RealOEQ, max, zero);
+        let result = self.select(max_is_zero, zero, result);
+        let max_is_inf = self.is_inf(max);
+        let inf = self.constant_float(x.ty, f64::INFINITY);
+        self.select(max_is_inf, inf, result)
+    }
+
+    /// `ilogb(x)`, from the exponent `FrexpStruct` returns (which is one higher, as it normalizes
+    /// the mantissa to `[0.5, 1)`), with `libm`'s results for zero, NaN and infinity.
+    fn ilogb(&mut self, x: SpirvValue, result_type: Word) -> SpirvValue {
+        let frexp_ty = self.type_struct(&[x.ty, result_type], false);
+        let frexp = self.gl_op(GLOp::FrexpStruct, frexp_ty, [x]);
+        let exp = self.extract_value(frexp, 1);
+        let one = self.constant_int(result_type, 1);
+        let result = self.sub(exp, one);
+
+        let min = self.constant_i32(self.span(), i32::MIN);
+        let max = self.constant_i32(self.span(), i32::MAX);
+        let zero = self.constant_float(x.ty, 0.0);
+        let is_zero = self.fcmp(RealPredicate::RealOEQ, x, zero);
+        let is_nan = self.is_nan(x);
+        let is_zero_or_nan = self.or(is_zero, is_nan);
+        let result = self.select(is_zero_or_nan, min, result);
+        let is_inf = self.is_inf(x);
+        self.select(is_inf, max, result)
+    }
+
+    /// `nextafter(x, y)`, by stepping the (sign-magnitude) bit pattern of `x` towards `y`.
+    fn nextafter(&mut self, x: SpirvValue, y: SpirvValue) -> SpirvValue {
+        let width = match self.lookup_type(x.ty) {
+            SpirvType::Float(width) => width,
+            other => bug!(
+                "nextafter must have float argument, not {}",
+                other.debug(x.ty, self)
+            ),
+        };
+        let int_ty = SpirvType::Integer(width, false).def(self.span(), self);
+        let bits = self.bitcast(x, int_ty);
+        let one = self.constant_int(int_ty, 1);
+        let bits_up = self.add(bits, one);
+        let bits_down = self.sub(bits, one);
+
+        // Moving towards `y` increases the magnitude iff `y` is on the same side as `0` is not.
+        let zero = self.constant_float(x.ty, 0.0);
+        let x_lt_y = self.fcmp(RealPredicate::RealOLT, x, y);
+        let x_gt_zero = self.fcmp(RealPredicate::RealOGT, x, zero);
+        let bool = SpirvType::Bool.def(self.span(), self);
+        let magnitude_up = self
+            .emit()
+            .logical_equal(bool, None, x_lt_y.def(self), x_gt_zero.def(self))
+            .unwrap()
+            .with_type(bool);
+        let next_bits = self.select(magnitude_up, bits_up, bits_down);
+        let next = self.bitcast(next_bits, x.ty);
+
+        // From either zero, the next value is the smallest subnormal, with the sign of `y`.
+        let smallest = match width {
+            32 => f64::from(f32::from_bits(1)),
+            64 => f64::from_bits(1),
+            _ => self.fatal(&format!(
+                "nextafter is only supported for f32 and f64, not f{}",
+                width
+            )),
+        };
+        let smallest = self.constant_float(x.ty, smallest);
+        let smallest = self.copysign(smallest, y);
+        let x_is_zero = self.fcmp(RealPredicate::RealOEQ, x, zero);
+        let next = self.select(x_is_zero, smallest, next);
+
+        let x_eq_y = self.fcmp(RealPredicate::RealOEQ, x, y);
+        let next = self.select(x_eq_y, y, next);
+        let any_nan = self.fcmp(RealPredicate::RealUNO, x, y);
+        let nan = self.fadd(x, y);
+        self.select(any_nan, nan, next)
+    }
+
+    /// `remainder(x, y)` and the quotient it used, i.e. `x - n * y` where `n` is `x / y` rounded
+    /// to the nearest integer (ties to even).
+    ///
+    /// NOTE: `x / y` is rounded before `n` is computed, so the result can be off by a multiple of
+    /// `y` when the quotient isn't exactly representable (i.e. `|x / y| >= 2^24` for `f32`).
+    fn remainder(&mut self, x: SpirvValue, y: SpirvValue) -> (SpirvValue, SpirvValue) {
+        let quotient = self.fdiv(x, y);
+        let n = self.std_op(GLOp::RoundEven, CLOp::rint, x.ty, [quotient]);
+        let ny = self.fmul(n, y);
+        (self.fsub(x, ny), n)
+    }
+
+    /// The `i32` quotient `remquo` returns, from the (integral) quotient `remainder` used: like
+    /// `libm`, its magnitude is reduced modulo `2^31` (keeping its sign), which is done before
+    /// converting it, so that large quotients don't overflow. Non-finite quotients become `0`.
+    fn remquo_quotient(&mut self, n: SpirvValue) -> SpirvValue {
+        // n - trunc(n / 2^31) * 2^31, which is exact, as `n` is an integer.
+        let modulus = self.constant_float(n.ty, 2147483648.0);
+        let div = self.fdiv(n, modulus);
+        let trunc = self.std_op(GLOp::Trunc, CLOp::trunc, n.ty, [div]);
+        let multiple = self.fmul(trunc, modulus);
+        let reduced = self.fsub(n, multiple);
+
+        let i32_ty = SpirvType::Integer(32, true).def(self.span(), self);
+        let quotient = self.fptosi(reduced, i32_ty);
+        let is_nan = self.is_nan(n);
+        let is_inf = self.is_inf(n);
+        let non_finite = self.or(is_nan, is_inf);
+        let zero = self.constant_i32(self.span(), 0);
+        self.select(non_finite, zero, quotient)
+    }
+
+    /// `lgamma(x)` and whether `gamma(x)` is negative, using the Lanczos approximation (`g = 7`,
+    /// `n = 9`, relative error around `1e-15`) for `x >= 0.5`, and the reflection formula
+    /// `gamma(x) * gamma(1 - x) == pi / sin(pi * x)` otherwise.
+    fn lgamma(&mut self, x: SpirvValue) -> (SpirvValue, SpirvValue) {
+        let (reflect, sin_pi_x, lgamma) = self.lanczos_lgamma(x);
+        // lgamma(x) == ln(pi / |sin(pi * x)|) - lgamma(1 - x)
+        let pi = self.constant_float(x.ty, std::f64::consts::PI);
+        let abs_sin = self.std_op(GLOp::FAbs, CLOp::fabs, x.ty, [sin_pi_x]);
+        let ratio = self.fdiv(pi, abs_sin);
+        let ln = self.std_op(GLOp::Log, CLOp::log, x.ty, [ratio]);
+        let reflected = self.fsub(ln, lgamma);
+        let result = self.select(reflect, reflected, lgamma);
+
+        let zero = self.constant_float(x.ty, 0.0);
+        let sin_negative = self.fcmp(RealPredicate::RealOLT, sin_pi_x, zero);
+        let negative = self.and(reflect, sin_negative);
+        (result, negative)
+    }
+
+    /// `tgamma(x)`, see `lgamma` for the approximation used.
+    fn tgamma(&mut self, x: SpirvValue) -> SpirvValue {
+        let (reflect, sin_pi_x, lgamma) = self.lanczos_lgamma(x);
+        let gamma = self.std_op(GLOp::Exp, CLOp::exp, x.ty, [lgamma]);
+        // gamma(x) == pi / (sin(pi * x) * gamma(1 - x))
+        let pi = self.constant_float(x.ty, std::f64::consts::PI);
+        let denom = self.fmul(sin_pi_x, gamma);
+        let reflected = self.fdiv(pi, denom);
+        self.select(reflect, reflected, gamma)
+    }
+
+    /// Returns whether `x < 0.5` (i.e. the reflection formula has to be used), `sin(pi * x)`, and
+    /// `lgamma(z)` for `z = if x < 0.5 { 1 - x } else { x }`.
+    fn lanczos_lgamma(&mut self, x: SpirvValue) -> (SpirvValue, SpirvValue, SpirvValue) {
+        const G: f64 = 7.0;
+        const COEFFS: [f64; 9] = [
+            0.999_999_999_999_809_9,
+            676.520_368_121_885_1,
+            -1_259.139_216_722_402_8,
+            771.323_428_777_653_1,
+            -176.615_029_162_140_6,
+            12.507_343_278_686_905,
+            -0.138_571_095_265_720_12,
+            9.984_369_578_019_572e-6,
+            1.505_632_735_149_311_6e-7,
+        ];
+
+        let half = self.constant_float(x.ty, 0.5);
+        let one = self.constant_float(x.ty, 1.0);
+        let reflect = self.fcmp(RealPredicate::RealOLT, x, half);
+        let one_minus_x = self.fsub(one, x);
+        let z = self.select(reflect, one_minus_x, x);
+
+        // lgamma(z) == ln(sqrt(2 * pi)) + (z - 0.5) * ln(t) - t + ln(sum), t = z - 0.5 + g
+        let z = self.fsub(z, one);
+        let mut sum = self.constant_float(x.ty, COEFFS[0]);
+        for (i, &coeff) in COEFFS.iter().enumerate().skip(1) {
+            let i = self.constant_float(x.ty, i as f64);
+            let coeff = self.constant_float(x.ty, coeff);
+            let denom = self.fadd(z, i);
+            let term = self.fdiv(coeff, denom);
+            sum = self.fadd(sum, term);
+        }
+        let g_half = self.constant_float(x.ty, G + 0.5);
+        let t = self.fadd(z, g_half);
+        let z_half = self.fadd(z, half);
+        let ln_t = self.std_op(GLOp::Log, CLOp::log, x.ty, [t]);
+        let ln_sum = self.std_op(GLOp::Log, CLOp::log, x.ty, [sum]);
+        let ln_sqrt_2pi = self.constant_float(x.ty, (2.0 * std::f64::consts::PI).sqrt().ln());
+        let power = self.fmul(z_half, ln_t);
+        let lgamma = self.fadd(ln_sqrt_2pi, power);
+        let lgamma = self.fsub(lgamma, t);
+        let lgamma = self.fadd(lgamma, ln_sum);
+
+        let pi = self.constant_float(x.ty, std::f64::consts::PI);
+        let pi_x = self.fmul(pi, x);
+        let sin_pi_x = self.std_op(GLOp::Sin, CLOp::sin, x.ty, [pi_x]);
+        (reflect, sin_pi_x, lgamma)
+    }
+
+    // The Bessel function approximations below are the rational/asymptotic approximations from
+    // Numerical Recipes (`bessj0`, `bessy0`, `bessj1` and `bessy1`), with an absolute error of
+    // around `1e-8`, which means they're less precise than `libm` for `f64`.
+
+    /// The asymptotic approximation for `|x| >= 8`, returning the `J` and `Y` results.
+    fn bessel_asymptotic(
+        &mut self,
+        ax: SpirvValue,
+        phase: f64,
+        p: &[f64],
+        q: &[f64],
+    ) -> (SpirvValue, SpirvValue) {
+        let eight = self.constant_float(ax.ty, 8.0);
+        let z = self.fdiv(eight, ax);
+        let y = self.fmul(z, z);
+        let phase = self.constant_float(ax.ty, phase);
+        let xx = self.fsub(ax, phase);
+        let p = self.horner(y, p);
+        let q = self.horner(y, q);
+        let zq = self.fmul(z, q);
+        let two_over_pi = self.constant_float(ax.ty, std::f64::consts::FRAC_2_PI);
+        let scale = self.fdiv(two_over_pi, ax);
+        let scale = self.std_op(GLOp::Sqrt, CLOp::sqrt, ax.ty, [scale]);
+        let cos = self.std_op(GLOp::Cos, CLOp::cos, ax.ty, [xx]);
+        let sin = self.std_op(GLOp::Sin, CLOp::sin, ax.ty, [xx]);
+
+        // j == scale * (cos * p - sin * z * q)
+        let cos_p = self.fmul(cos, p);
+        let sin_zq = self.fmul(sin, zq);
+        let j = self.fsub(cos_p, sin_zq);
+        let j = self.fmul(scale, j);
+        // y == scale * (sin * p + cos * z * q)
+        let sin_p = self.fmul(sin, p);
+        let cos_zq = self.fmul(cos, zq);
+        let y = self.fadd(sin_p, cos_zq);
+        let y = self.fmul(scale, y);
+        (j, y)
+    }
+
+    const BESSEL_P0: [f64; 5] = [
+        1.0,
+        -0.109_862_862_7e-2,
+        0.273_451_040_7e-4,
+        -0.207_337_063_9e-5,
+        0.209_388_721_1e-6,
+    ];
+    const BESSEL_Q0: [f64; 5] = [
+        -0.156_249_999_5e-1,
+        0.143_048_876_5e-3,
+        -0.691_114_765_1e-5,
+        0.762_109_516_1e-6,
+        -0.934_935_152e-7,
+    ];
+    const BESSEL_P1: [f64; 5] = [
+        1.0,
+        0.183_105e-2,
+        -0.351_639_649_6e-4,
+        0.245_752_017_4e-5,
+        -0.240_337_019e-6,
+    ];
+    const BESSEL_Q1: [f64; 5] = [
+        0.046_874_999_95,
+        -0.200_269_087_3e-3,
+        0.844_919_909_6e-5,
+        -0.882_289_87e-6,
+        0.105_787_412e-6,
+    ];
+
+    fn bessel_j0(&mut self, x: SpirvValue) -> SpirvValue {
+        let ax = self.std_op(GLOp::FAbs, CLOp::fabs, x.ty, [x]);
+        let x2 = self.fmul(x, x);
+        let small = self.rational(
+            x2,
+            &[
+                57_568_490_574.0,
+                -13_362_590_354.0,
+                651_619_640.7,
+                -11_214_424.18,
+                77_392.330_17,
+                -184.905_245_6,
+            ],
+            &[
+                57_568_490_411.0,
+                1_029_532_985.0,
+                9_494_680.718,
+                59_272.648_53,
+                267.853_271_2,
+                1.0,
+            ],
+        );
+        let (large, _) =
+            self.bessel_asymptotic(ax, 0.785_398_164, &Self::BESSEL_P0, &Self::BESSEL_Q0);
+        let eight = self.constant_float(x.ty, 8.0);
+        let is_small = self.fcmp(RealPredicate::RealOLT, ax, eight);
+        self.select(is_small, small, large)
+    }
+
+    fn bessel_y0(&mut self, x: SpirvValue) -> SpirvValue {
+        let x2 = self.fmul(x, x);
+        let rational = self.rational(
+            x2,
+            &[
+                -2_957_821_389.0,
+                7_062_834_065.0,
+                -512_359_803.6,
+                10_879_881.29,
+                -86_327.927_57,
+                228.462_273_3,
+            ],
+            &[
+                40_076_544_269.0,
+                745_249_964.8,
+                7_189_466.438,
+                47_447.264_70,
+                226.103_024_4,
+                1.0,
+            ],
+        );
+        // small == rational + (2 / pi) * j0(x) * ln(x)
+        let j0 = self.bessel_j0(x);
+        let ln = self.std_op(GLOp::Log, CLOp::log, x.ty, [x]);
+        let two_over_pi = self.constant_float(x.ty, std::f64::consts::FRAC_2_PI);
+        let j0_ln = self.fmul(j0, ln);
+        let term = self.fmul(two_over_pi, j0_ln);
+        let small = self.fadd(rational, term);
+        let (_, large) =
+            self.bessel_asymptotic(x, 0.785_398_164, &Self::BESSEL_P0, &Self::BESSEL_Q0);
+        let eight = self.constant_float(x.ty, 8.0);
+        let is_small = self.fcmp(RealPredicate::RealOLT, x, eight);
+        self.select(is_small, small, large)
+    }
+
+    fn bessel_j1(&mut self, x: SpirvValue) -> SpirvValue {
+        let ax = self.std_op(GLOp::FAbs, CLOp::fabs, x.ty, [x]);
+        let x2 = self.fmul(x, x);
+        let rational = self.rational(
+            x2,
+            &[
+                72_362_614_232.0,
+                -7_895_059_235.0,
+                242_396_853.1,
+                -2_972_611.439,
+                15_704.482_60,
+                -30.160_366_06,
+            ],
+            &[
+                144_725_228_442.0,
+                2_300_535_178.0,
+                18_583_304.74,
+                99_447.433_94,
+                376.999_139_7,
+                1.0,
+            ],
+        );
+        let small = self.fmul(x, rational);
+        // J1 is odd, so the asymptotic approximation (of `|x|`) has to take the sign of `x`.
+        let (large, _) =
+            self.bessel_asymptotic(ax, 2.356_194_491, &Self::BESSEL_P1, &Self::BESSEL_Q1);
+        let large = self.copysign(large, x);
+        let eight = self.constant_float(x.ty, 8.0);
+        let is_small = self.fcmp(RealPredicate::RealOLT, ax, eight);
+        self.select(is_small, small, large)
+    }
+
+    fn bessel_y1(&mut self, x: SpirvValue) -> SpirvValue {
+        let x2 = self.fmul(x, x);
+        let rational = self.rational(
+            x2,
+            &[
+                -0.490_060_494_3e13,
+                0.127_527_439_0e13,
+                -0.515_343_813_9e11,
+                0.734_926_455_1e9,
+                -0.423_792_272_6e7,
+                0.851_193_793_5e4,
+            ],
+            &[
+                0.249_958_057_0e14,
+                0.424_441_966_4e12,
+                0.373_365_036_7e10,
+                0.224_590_400_2e8,
+                0.102_042_605_0e6,
+                0.354_963_288_5e3,
+                1.0,
+            ],
+        );
+        // small == x * rational + (2 / pi) * (j1(x) * ln(x) - 1 / x)
+        let x_rational = self.fmul(x, rational);
+        let j1 = self.bessel_j1(x);
+        let ln = self.std_op(GLOp::Log, CLOp::log, x.ty, [x]);
+        let j1_ln = self.fmul(j1, ln);
+        let one = self.constant_float(x.ty, 1.0);
+        let inv_x = self.fdiv(one, x);
+        let diff = self.fsub(j1_ln, inv_x);
+        let two_over_pi = self.constant_float(x.ty, std::f64::consts::FRAC_2_PI);
+        let term = self.fmul(two_over_pi, diff);
+        let small = self.fadd(x_rational, term);
+        let (_, large) =
+            self.bessel_asymptotic(x, 2.356_194_491, &Self::BESSEL_P1, &Self::BESSEL_Q1);
+        let eight = self.constant_float(x.ty, 8.0);
+        let is_small = self.fcmp(RealPredicate::RealOLT, x, eight);
+        self.select(is_small, small, large)
+    }
+
+    /// Computes `f_n(x)` from `f_0(x)` and `f_1(x)` with the forward recurrence
+    /// `f_{k+1}(x) == (2k / x) * f_k(x) - f_{k-1}(x)`, shared by `J_n` and `Y_n`, and extends it
+    /// to negative `n` with `f_{-n}(x) == (-1)^n * f_n(x)`.
+    ///
+    /// NOTE: the forward recurrence is stable for `Y_n`, but for `J_n` it loses precision when
+    /// `|n| > |x|` (where `libm` switches to a backwards recurrence).
+    fn bessel_recurrence(
+        &mut self,
+        n: SpirvValue,
+        x: SpirvValue,
+        f0: SpirvValue,
+        f1: SpirvValue,
+    ) -> SpirvValue {
+        let zero_align = Align::from_bytes(0).unwrap();
+        let zero_n = self.constant_int(n.ty, 0);
+        let one_n = self.constant_int(n.ty, 1);
+        let n_negative = self.icmp(IntPredicate::IntSLT, n, zero_n);
+        let neg_n = self.neg(n);
+        let abs_n = self.select(n_negative, neg_n, n);
+
+        let prev = self.alloca(x.ty, zero_align);
+        let cur = self.alloca(x.ty, zero_align);
+        let index = self.alloca(n.ty, zero_align);
+        self.store(f0, prev, zero_align);
+        self.store(f1, cur, zero_align);
+        self.store(one_n, index, zero_align);
+
+        let mut header = self.build_sibling_block("bessel_header");
+        let mut body = self.build_sibling_block("bessel_body");
+        let mut exit = self.build_sibling_block("bessel_exit");
+        self.br(header.llbb());
+
+        let k = header.load(index, zero_align);
+        let cond = header.icmp(IntPredicate::IntSLT, k, abs_n);
+        header.cond_br(cond, body.llbb(), exit.llbb());
+
+        let f_prev = body.load(prev, zero_align);
+        let f_cur = body.load(cur, zero_align);
+        let k_float = body.sitofp(k, x.ty);
+        let two = body.constant_float(x.ty, 2.0);
+        let two_k = body.fmul(two, k_float);
+        let ratio = body.fdiv(two_k, x);
+        let scaled = body.fmul(ratio, f_cur);
+        let f_next = body.fsub(scaled, f_prev);
+        body.store(f_cur, prev, zero_align);
+        body.store(f_next, cur, zero_align);
+        let k_plus_1 = body.add(k, one_n);
+        body.store(k_plus_1, index, zero_align);
+        body.br(header.llbb());
+
+        let f_n = exit.load(cur, zero_align);
+        let n_is_zero = exit.icmp(IntPredicate::IntEQ, abs_n, zero_n);
+        let f_n = exit.select(n_is_zero, f0, f_n);
+        let low_bit = exit.and(n, one_n);
+        let n_odd = exit.icmp(IntPredicate::IntNE, low_bit, zero_n);
+        let flip = exit.and(n_negative, n_odd);
+        let neg_f_n = exit.fneg(f_n);
+        let result = exit.select(flip, neg_f_n, f_n);
+        *self = exit;
+        result
+    }
 }
@@ -9,10 +9,11 @@ use rustc_span::DUMMY_SP;
 pub use spirv_asm::InstructionTable;
 
 use crate::abi::ConvSpirvType;
-use crate::builder_spirv::{BuilderCursor, SpirvValue, SpirvValueExt};
+use crate::builder_spirv::{BuilderCursor, SpirvValue, SpirvValueExt, SpirvValueKind};
 use crate::codegen_cx::CodegenCx;
 use crate::spirv_type::SpirvType;
-use rspirv::spirv::Word;
+use rspirv::dr::{Instruction, Operand};
+use rspirv::spirv::{Op as SpirvOp, Word};
 use rustc_codegen_ssa::mir::operand::OperandValue;
 use rustc_codegen_ssa::mir::place::PlaceRef;
 use rustc_codegen_ssa::traits::{
@@ -103,6 +104,47 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
         // https://llvm.org/docs/GetElementPtr.html
         // "An OpAccessChain instruction is the equivalent of an LLVM getelementptr instruction where the first index element is zero."
         // https://github.com/gpuweb/gpuweb/issues/33
+        // Indexing vtables (see `CodegenCx::vtable_addr_of`), which are arrays of pointer-sized
+        // integers, through a `pointercast` to a function pointer (for dynamic dispatch).
+        if let SpirvValueKind::LogicalPtrCast {
+            original_ptr,
+            original_pointee_ty,
+            zombie_target_undef: _,
+        } = ptr.kind
+        {
+            let pointee = match self.lookup_type(ptr.ty) {
+                SpirvType::Pointer { pointee } => pointee,
+                _ => unreachable!(),
+            };
+            if let (SpirvType::Array { element, .. }, [index]) =
+                (self.lookup_type(original_pointee_ty), indices)
+            {
+                if self.is_fn_ptr_type(pointee)
+                    && matches!(self.lookup_type(element), SpirvType::Integer(..))
+                {
+                    let element_ptr_ty =
+                        SpirvType::Pointer { pointee: element }.def(self.span(), self);
+                    let element_ptr = self
+                        .emit()
+                        .in_bounds_access_chain(
+                            element_ptr_ty,
+                            None,
+                            original_ptr,
+                            [index.def(self)].iter().cloned(),
+                        )
+                        .unwrap();
+                    return SpirvValue {
+                        kind: SpirvValueKind::LogicalPtrCast {
+                            original_ptr: element_ptr,
+                            original_pointee_ty: element,
+                            zombie_target_undef: self.undef(ptr.ty).def(self),
+                        },
+                        ty: ptr.ty,
+                    };
+                }
+            }
+        }
+
         let mut result_indices = Vec::with_capacity(indices.len() - 1);
         let mut result_pointee_type = match self.lookup_type(ptr.ty) {
             SpirvType::Pointer { pointee } => pointee,
@@ -251,18 +293,46 @@ impl<'a, 'tcx> CoverageInfoBuilderMethods<'tcx> for Builder<'a, 'tcx> {
 impl<'a, 'tcx> DebugInfoBuilderMethods for Builder<'a, 'tcx> {
     fn dbg_var_addr(
         &mut self,
-        _dbg_var: Self::DIVariable,
+        dbg_var: Self::DIVariable,
         _scope_metadata: Self::DILocation,
-        _variable_alloca: Self::Value,
-        _direct_offset: Size,
+        variable_alloca: Self::Value,
+        direct_offset: Size,
         // NB: each offset implies a deref (i.e. they're steps in a pointer chain).
-        _indirect_offsets: &[Size],
+        indirect_offsets: &[Size],
     ) {
-        todo!()
+        // Only variables that are an entire `OpVariable` can be named.
+        if direct_offset != Size::ZERO || !indirect_offsets.is_empty() {
+            return;
+        }
+        if let SpirvValueKind::Def(id) = variable_alloca.kind {
+            self.emit().name(id, dbg_var.to_string());
+        }
     }
 
-    fn set_dbg_loc(&mut self, _: Self::DILocation) {
-        todo!()
+    fn set_dbg_loc(&mut self, span: Self::DILocation) {
+        let loc = self.tcx.sess.source_map().lookup_char_pos(span.lo());
+        let file = self.def_debug_file(&loc.file);
+        let line = Instruction::new(
+            SpirvOp::Line,
+            None,
+            None,
+            vec![
+                Operand::IdRef(file),
+                Operand::LiteralInt32(loc.line as u32),
+                Operand::LiteralInt32(loc.col.0 as u32 + 1),
+            ],
+        );
+        let mut emit = self.emit();
+        let (function, block) = (
+            emit.selected_function().unwrap(),
+            emit.selected_block().unwrap(),
+        );
+        let block = &mut emit.module_mut().functions[function].blocks[block];
+        // Nothing was emitted since the last `OpLine`, so replace it instead of piling them up.
+        match block.instructions.last_mut() {
+            Some(last) if last.class.opcode == SpirvOp::Line => *last = line,
+            _ => block.instructions.push(line),
+        }
     }
 
     fn insert_reference_to_gdb_debug_scripts_section_global(&mut self) {
@@ -270,7 +340,7 @@ impl<'a, 'tcx> DebugInfoBuilderMethods for Builder<'a, 'tcx> {
     }
 
     fn set_var_name(&mut self, _value: Self::Value, _name: &str) {
-        todo!()
+        // Only called for `-Z fewer-names=no`, locals get their `OpName`s from `dbg_var_addr`.
     }
 }
 
@@ -312,6 +312,10 @@ impl<'cx, 'tcx> Builder<'cx, 'tcx> {
                 image_type: inst.operands[0].unwrap_id_ref(),
             }
             .def(self.span(), self),
+            Op::TypeAccelerationStructureKHR => {
+                SpirvType::AccelerationStructureKhr.def(self.span(), self)
+            }
+            Op::TypeRayQueryKHR => SpirvType::RayQueryKhr.def(self.span(), self),
             Op::Variable if inst.operands[0].unwrap_storage_class() != StorageClass::Function => {
                 // OpVariable with Function storage class should be emitted inside the function,
                 // however, all other OpVariables should appear in the global scope instead.
@@ -319,6 +323,24 @@ impl<'cx, 'tcx> Builder<'cx, 'tcx> {
                     .insert_types_global_values(dr::InsertPoint::End, inst);
                 return;
             }
+            Op::Variable => {
+                // "All OpVariable instructions in a function must be the first instructions in the first block."
+                // (see also `alloca`, which this mirrors)
+                let mut builder = self.emit();
+                builder.select_block(Some(0)).unwrap();
+                let index = {
+                    let block = &builder.module_ref().functions
+                        [builder.selected_function().unwrap()]
+                    .blocks[builder.selected_block().unwrap()];
+                    block
+                        .instructions
+                        .iter()
+                        .position(|inst| inst.class.opcode != Op::Variable)
+                        .map_or(dr::InsertPoint::End, dr::InsertPoint::FromBegin)
+                };
+                builder.insert_into_block(index, inst).unwrap();
+                return;
+            }
             _ => {
                 self.emit()
                     .insert_into_block(dr::InsertPoint::End, inst)
@@ -99,19 +99,17 @@ impl SpirvValue {
     pub fn def_with_span(self, cx: &CodegenCx<'_>, span: Span) -> Word {
         match self.kind {
             SpirvValueKind::Def(word) => word,
-            SpirvValueKind::FnAddr { .. } => {
+            SpirvValueKind::FnAddr { function } => {
                 if cx.is_system_crate() {
                     *cx.zombie_undefs_for_system_fn_addrs
                         .borrow()
                         .get(&self.ty)
                         .expect("FnAddr didn't go through proper undef registration")
                 } else {
-                    cx.tcx
-                        .sess
-                        .err("Cannot use this function pointer for anything other than calls");
-                    // Because we never get beyond compilation (into e.g. linking),
-                    // emitting an invalid ID reference here is OK.
-                    0
+                    *cx.fn_ptr_constants
+                        .borrow()
+                        .get(&function)
+                        .expect("FnAddr didn't go through proper constant registration")
                 }
             }
 
@@ -222,6 +220,7 @@ impl BuilderSpirv {
         version: Option<(u8, u8)>,
         memory_model: Option<MemoryModel>,
         kernel_mode: bool,
+        extensions: &[String],
     ) -> Self {
         let mut builder = Builder::new();
         // Default to spir-v 1.3
@@ -248,7 +247,13 @@ impl BuilderSpirv {
         builder.capability(Capability::Int8);
         builder.capability(Capability::Int16);
         builder.capability(Capability::Int64);
+        builder.capability(Capability::Int64Atomics);
         builder.capability(Capability::Float64);
+        // Declared with `+ext:...` target features (and removed by the linker if unused).
+        // Declared capabilities aren't added, they only restrict which ones may be used.
+        for extension in extensions {
+            builder.extension(&extension[..]);
+        }
         if kernel_mode {
             builder.capability(Capability::Addresses);
             builder.memory_model(AddressingModel::Physical32, MemoryModel::OpenCL);
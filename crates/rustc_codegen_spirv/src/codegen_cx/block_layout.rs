@@ -0,0 +1,156 @@
+//! Checking of the offsets and array strides of uniform, storage buffer and push constant
+//! blocks against the rules chosen with `#[spirv(block(layout = "..."))]`.
+//!
+//! The offsets themselves always come from the Rust layout of the type, so these checks only
+//! point out where the Rust layout differs from what the driver will assume (e.g. a `Vec3`
+//! after an `f32`), and any padding has to be added to the Rust type by hand.
+
+use super::CodegenCx;
+use crate::attr::BlockLayout;
+use crate::spirv_type::SpirvType;
+use rspirv::spirv::Word;
+use rustc_hir::def::DefKind;
+use rustc_span::Span;
+
+impl<'tcx> CodegenCx<'tcx> {
+    /// Reports an error for every member of `ty` (recursively) that is misplaced according to
+    /// `layout`, `path` being the name of the block, for messages.
+    pub fn check_block_layout(&self, span: Span, layout: BlockLayout, ty: Word, path: &str) {
+        match self.lookup_type(ty) {
+            SpirvType::Adt {
+                def_id,
+                field_types,
+                field_offsets,
+                field_names,
+                ..
+            } => {
+                let mut min_offset = 0;
+                for (i, (&field_ty, offset)) in field_types.iter().zip(&field_offsets).enumerate() {
+                    let offset = offset.bytes();
+                    let name = field_names
+                        .as_ref()
+                        .map_or_else(|| i.to_string(), |names| names[i].clone());
+                    let field_path = format!("{}.{}", path, name);
+                    let field_span = def_id
+                        .filter(|&def_id| self.tcx.def_kind(def_id) == DefKind::Struct)
+                        .and_then(|def_id| {
+                            self.tcx
+                                .adt_def(def_id)
+                                .non_enum_variant()
+                                .fields
+                                .iter()
+                                .find(|field| field.ident.as_str() == name)
+                                .map(|field| self.tcx.def_span(field.did))
+                        })
+                        .unwrap_or(span);
+
+                    let align = self.block_layout_align(layout, field_ty);
+                    if offset % align != 0 {
+                        self.tcx.sess.span_err(
+                            field_span,
+                            &format!(
+                                "`{}` is at offset {}, but the {} layout requires a multiple of {}",
+                                field_path,
+                                offset,
+                                layout.name(),
+                                align
+                            ),
+                        );
+                    } else if offset < min_offset {
+                        self.tcx.sess.span_err(
+                            field_span,
+                            &format!(
+                                "`{}` is at offset {}, but the {} layout requires it to be at \
+                                 least at {}, after the padding of the previous struct or array",
+                                field_path,
+                                offset,
+                                layout.name(),
+                                min_offset
+                            ),
+                        );
+                    }
+
+                    // Nothing can be placed in the padding after a struct or array, up to its
+                    // alignment (except with the scalar layout).
+                    let field_spirv_type = self.lookup_type(field_ty);
+                    min_offset = match (layout, &field_spirv_type) {
+                        (
+                            BlockLayout::Std140 | BlockLayout::Std430,
+                            SpirvType::Adt { .. } | SpirvType::Array { .. },
+                        ) => field_spirv_type
+                            .sizeof(self)
+                            .map_or(0, |size| round_up(offset + size.bytes(), align)),
+                        _ => 0,
+                    };
+
+                    self.check_block_layout(field_span, layout, field_ty, &field_path);
+                }
+            }
+            SpirvType::Array { element, .. } | SpirvType::RuntimeArray { element } => {
+                // See `SpirvType::def` for the `ArrayStride`.
+                let stride = self
+                    .lookup_type(element)
+                    .sizeof(self)
+                    .map_or(0, |size| size.bytes());
+                let align = std140_round_up(layout, self.block_layout_align(layout, element));
+                if stride % align != 0 {
+                    self.tcx.sess.span_err(
+                        span,
+                        &format!(
+                            "`{}` has an array stride of {}, but the {} layout requires a \
+                             multiple of {}",
+                            path,
+                            stride,
+                            layout.name(),
+                            align
+                        ),
+                    );
+                }
+                self.check_block_layout(span, layout, element, &format!("{}[..]", path));
+            }
+            _ => {}
+        }
+    }
+
+    /// The alignment `layout` requires of `ty`, in bytes.
+    fn block_layout_align(&self, layout: BlockLayout, ty: Word) -> u64 {
+        let spirv_type = self.lookup_type(ty);
+        let align = match spirv_type {
+            SpirvType::Vector { element, count } => {
+                let element_align = self.block_layout_align(layout, element);
+                match (layout, count) {
+                    (BlockLayout::Scalar, _) => element_align,
+                    (_, 2) => element_align * 2,
+                    _ => element_align * 4,
+                }
+            }
+            SpirvType::Array { element, .. } | SpirvType::RuntimeArray { element } => {
+                // Structs and arrays are aligned to at least 16 bytes in the std140 layout.
+                std140_round_up(layout, self.block_layout_align(layout, element))
+            }
+            SpirvType::Adt { field_types, .. } => std140_round_up(
+                layout,
+                field_types
+                    .iter()
+                    .map(|&field_ty| self.block_layout_align(layout, field_ty))
+                    .max()
+                    .unwrap_or(1),
+            ),
+            SpirvType::InterfaceBlock { inner_type } => self.block_layout_align(layout, inner_type),
+            _ => spirv_type.sizeof(self).map_or(1, |size| size.bytes()),
+        };
+        align.max(1)
+    }
+}
+
+fn std140_round_up(layout: BlockLayout, align: u64) -> u64 {
+    if layout == BlockLayout::Std140 {
+        round_up(align, 16)
+    } else {
+        align
+    }
+}
+
+fn round_up(value: u64, align: u64) -> u64 {
+    (value + align - 1) / align * align
+}
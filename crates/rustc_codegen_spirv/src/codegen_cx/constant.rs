@@ -317,7 +317,8 @@ impl<'tcx> ConstMethods<'tcx> for CodegenCx<'tcx> {
     }
 
     fn const_ptrcast(&self, val: Self::Value, ty: Self::Type) -> Self::Value {
-        if val.ty == ty {
+        // Vtables already have the right type for `dyn Trait` metadata, see `vtable_addr_of`.
+        if val.ty == ty || self.is_vtable(val) {
             val
         } else {
             // constant ptrcast is not supported in spir-v
@@ -523,6 +524,11 @@ impl<'tcx> CodegenCx<'tcx> {
                 .tcx
                 .sess
                 .fatal("Cannot create a constant sampled image value"),
+            SpirvType::AccelerationStructureKhr => self
+                .tcx
+                .sess
+                .fatal("Cannot create a constant acceleration structure"),
+            SpirvType::RayQueryKhr => self.tcx.sess.fatal("Cannot create a constant ray query"),
             SpirvType::InterfaceBlock { .. } => self
                 .tcx
                 .sess
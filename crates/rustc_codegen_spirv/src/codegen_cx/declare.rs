@@ -177,6 +177,49 @@ impl<'tcx> CodegenCx<'tcx> {
         g
     }
 
+    /// Vtables are emitted as arrays of pointer-sized integers (with the function pointers
+    /// turned into integers by the linker, see `linker/fn_pointers.rs`), so that they can be
+    /// indexed in the `Logical` addressing model. The `dyn Trait` metadata type matches this
+    /// (see `abi::dig_scalar_pointee`), instead of the `&[usize; 3]` rustc uses.
+    fn vtable_addr_of(&self, vtable: SpirvValue) -> SpirvValue {
+        let (field_types, fields) = match (
+            self.lookup_type(vtable.ty),
+            self.builder.lookup_const(vtable),
+        ) {
+            (SpirvType::Adt { field_types, .. }, Some(SpirvConst::Composite(_, fields))) => {
+                (field_types, fields)
+            }
+            (ty, _) => bug!(
+                "vtable must be a constant struct, got {}",
+                ty.debug(vtable.ty, self)
+            ),
+        };
+        // The vtable starts with `drop_in_place`, followed by the size and alignment.
+        let usize_ty = field_types[1];
+        let elements = field_types
+            .iter()
+            .zip(fields)
+            .map(|(&ty, field)| {
+                // Methods that can't be called through `dyn Trait` have null pointers.
+                if self.builder.lookup_const(field.with_type(ty)) == Some(SpirvConst::Null(ty)) {
+                    self.constant_int(usize_ty, 0).def_cx(self)
+                } else {
+                    field
+                }
+            })
+            .collect::<Vec<_>>();
+        let array_ty = SpirvType::Array {
+            element: usize_ty,
+            count: self.constant_u32(DUMMY_SP, elements.len() as u32),
+        }
+        .def(DUMMY_SP, self);
+        let initializer = self.constant_composite(array_ty, elements).def_cx(self);
+        let ptr_ty = SpirvType::Pointer { pointee: array_ty }.def(DUMMY_SP, self);
+        self.emit_global()
+            .variable(ptr_ty, None, StorageClass::Private, Some(initializer))
+            .with_type(ptr_ty)
+    }
+
     fn declare_global(&self, span: Span, ty: Word) -> SpirvValue {
         let ptr_ty = SpirvType::Pointer { pointee: ty }.def(span, self);
         // FIXME(eddyb) figure out what the correct storage class is.
@@ -241,8 +284,12 @@ impl<'tcx> PreDefineMethods<'tcx> for CodegenCx<'tcx> {
 }
 
 impl<'tcx> StaticMethods for CodegenCx<'tcx> {
-    fn static_addr_of(&self, cv: Self::Value, _align: Align, _kind: Option<&str>) -> Self::Value {
-        self.make_constant_pointer(DUMMY_SP, cv)
+    fn static_addr_of(&self, cv: Self::Value, _align: Align, kind: Option<&str>) -> Self::Value {
+        if kind == Some("vtable") {
+            self.vtable_addr_of(cv)
+        } else {
+            self.make_constant_pointer(DUMMY_SP, cv)
+        }
     }
 
     fn codegen_static(&self, def_id: DefId, _is_mutable: bool) {
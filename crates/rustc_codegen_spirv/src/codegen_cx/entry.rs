@@ -1,11 +1,20 @@
 use super::CodegenCx;
-use crate::abi::ConvSpirvType;
-use crate::attr::{AggregatedSpirvAttributes, Entry};
+use crate::abi::{
+    lower_interface_type, ConvSpirvType, InterfaceLowering, InterfaceLoweringKind, OptionTag,
+};
+use crate::attr::{
+    AggregatedSpirvAttributes, BlockLayout, Entry, ExecutionModeExtra, Spanned, SpecConstant,
+};
 use crate::builder::Builder;
 use crate::builder_spirv::{SpirvValue, SpirvValueExt};
+use crate::decorations::{AtomicScopeDecoration, BlockLayoutDecoration};
 use crate::spirv_type::SpirvType;
-use rspirv::dr::Operand;
-use rspirv::spirv::{Decoration, ExecutionModel, FunctionControl, StorageClass, Word};
+use rspirv::dr::{Instruction, Operand};
+use rspirv::spirv::{
+    Capability, Decoration, ExecutionMode, ExecutionModel, FunctionControl, Op, StorageClass, Word,
+};
+use rustc_ast::LitKind;
+use rustc_codegen_ssa::common::IntPredicate;
 use rustc_codegen_ssa::traits::{BaseTypeMethods, BuilderMethods};
 use rustc_data_structures::fx::FxHashMap;
 use rustc_hir as hir;
@@ -14,8 +23,9 @@ use rustc_middle::ty::{Instance, Ty, TyKind};
 use rustc_span::Span;
 use rustc_target::abi::{
     call::{ArgAbi, ArgAttribute, ArgAttributes, FnAbi, PassMode},
-    LayoutOf, Size,
+    Align, LayoutOf, Size,
 };
+use std::convert::TryFrom;
 
 impl<'tcx> CodegenCx<'tcx> {
     // Entry points declare their "interface" (all uniforms, inputs, outputs, etc.) as parameters.
@@ -83,6 +93,15 @@ impl<'tcx> CodegenCx<'tcx> {
             )
         }
         let execution_model = entry.execution_model;
+        if matches!(
+            execution_model,
+            ExecutionModel::TaskNV | ExecutionModel::MeshNV
+        ) {
+            self.check_mesh_shading_execution_modes(span, &entry);
+            let mut emit = self.emit_global();
+            emit.extension("SPV_NV_mesh_shader");
+            emit.capability(Capability::MeshShadingNV);
+        }
         let fn_id = if execution_model == ExecutionModel::Kernel {
             self.kernel_entry_stub(entry_func, name, execution_model)
         } else {
@@ -95,13 +114,123 @@ impl<'tcx> CodegenCx<'tcx> {
                 execution_model,
             )
         };
-        let mut emit = self.emit_global();
-        entry
+        for (execution_mode, execution_mode_extra) in &entry.execution_modes {
+            if *execution_mode == ExecutionMode::LocalSizeId {
+                self.declare_local_size_id(span, fn_id, execution_mode_extra, entry.local_size_id);
+            } else {
+                self.emit_global()
+                    .execution_mode(fn_id, *execution_mode, execution_mode_extra);
+            }
+        }
+        if let Some(scope) = entry.atomic_scope {
+            self.atomic_scope_decorations.borrow_mut().insert(
+                fn_id,
+                AtomicScopeDecoration {
+                    scope: scope as u32,
+                },
+            );
+        }
+    }
+
+    /// `LocalSizeId` takes constants instead of literals, so that the `threads`
+    /// dimensions with a `local_size_id_*` attribute can be specialized.
+    fn declare_local_size_id(
+        &self,
+        span: Span,
+        fn_id: Word,
+        local_size: &ExecutionModeExtra,
+        spec_ids: [Option<u32>; 3],
+    ) {
+        if self.emit_global().version().unwrap() < (1, 2) {
+            self.tcx.sess.span_err(
+                span,
+                "`local_size_id_*` requires `LocalSizeId`, which needs SPIR-V 1.2 or later",
+            );
+        }
+        let u32 = SpirvType::Integer(32, false).def(span, self);
+        let mut operands = vec![
+            Operand::IdRef(fn_id),
+            Operand::ExecutionMode(ExecutionMode::LocalSizeId),
+        ];
+        for (&size, spec_id) in local_size.as_ref().iter().zip(&spec_ids) {
+            let size = match *spec_id {
+                Some(spec_id) => self.def_spec_constant(
+                    u32,
+                    Op::SpecConstant,
+                    vec![Operand::LiteralInt32(size)],
+                    spec_id,
+                ),
+                None => self.constant_u32(span, size).def_cx(self),
+            };
+            operands.push(Operand::IdRef(size));
+        }
+        self.emit_global()
+            .module_mut()
             .execution_modes
-            .iter()
-            .for_each(|(execution_mode, execution_mode_extra)| {
-                emit.execution_mode(fn_id, *execution_mode, execution_mode_extra);
-            });
+            .push(Instruction::new(Op::ExecutionModeId, None, None, operands));
+    }
+
+    /// Emits an `OpSpecConstant*` decorated with `SpecId`.
+    fn def_spec_constant(
+        &self,
+        ty: Word,
+        opcode: Op,
+        operands: Vec<Operand>,
+        spec_id: u32,
+    ) -> Word {
+        let mut emit = self.emit_global();
+        let id = emit.id();
+        emit.module_mut().types_global_values.push(Instruction::new(
+            opcode,
+            Some(ty),
+            Some(id),
+            operands,
+        ));
+        emit.decorate(
+            id,
+            Decoration::SpecId,
+            std::iter::once(Operand::LiteralInt32(spec_id)),
+        );
+        id
+    }
+
+    /// Mesh shaders can't be used without declaring their maximum output sizes
+    /// and their output topology, so point out the attributes for any missing.
+    fn check_mesh_shading_execution_modes(&self, span: Span, entry: &Entry) {
+        if entry.execution_model != ExecutionModel::MeshNV {
+            return;
+        }
+        let has_mode = |modes: &[ExecutionMode]| {
+            entry
+                .execution_modes
+                .iter()
+                .any(|(mode, _)| modes.contains(mode))
+        };
+        if !has_mode(&[ExecutionMode::OutputVertices]) {
+            self.tcx.sess.span_err(
+                span,
+                "mesh shaders must specify the maximum number of vertices they output, \
+                 e.g. `#[spirv(mesh_nv(output_vertices = 64))]`",
+            );
+        }
+        if !has_mode(&[ExecutionMode::OutputPrimitivesNV]) {
+            self.tcx.sess.span_err(
+                span,
+                "mesh shaders must specify the maximum number of primitives they output, \
+                 e.g. `#[spirv(mesh_nv(output_primitives_nv = 126))]`",
+            );
+        }
+        if !has_mode(&[
+            ExecutionMode::OutputPoints,
+            ExecutionMode::OutputLinesNV,
+            ExecutionMode::OutputTrianglesNV,
+        ]) {
+            self.tcx.sess.span_err(
+                span,
+                "mesh shaders must specify their output topology, one of \
+                 `output_points`, `output_lines_nv` or `output_triangles_nv`",
+            );
+        }
     }
 
     fn shader_entry_stub(
@@ -142,6 +271,7 @@ impl<'tcx> CodegenCx<'tcx> {
                 &mut bx,
                 &mut call_args,
                 &mut decoration_locations,
+                execution_model,
             )
         }
         bx.set_span(span);
@@ -175,10 +305,18 @@ impl<'tcx> CodegenCx<'tcx> {
         };
         let spirv_ty = self.layout_of(value_ty).spirv_type(hir_param.ty_span, self);
         // Some types automatically specify a storage class. Compute that here.
+        // This includes (runtime-sized) arrays of such types, i.e. descriptor arrays.
         let inferred_storage_class_from_ty = match self.lookup_type(spirv_ty) {
-            SpirvType::Image { .. } | SpirvType::Sampler | SpirvType::SampledImage { .. } => {
-                Some(StorageClass::UniformConstant)
+            SpirvType::Array { element, .. } | SpirvType::RuntimeArray { element } => {
+                self.lookup_type(element)
             }
+            spirv_ty => spirv_ty,
+        };
+        let inferred_storage_class_from_ty = match inferred_storage_class_from_ty {
+            SpirvType::Image { .. }
+            | SpirvType::Sampler
+            | SpirvType::SampledImage { .. }
+            | SpirvType::AccelerationStructureKhr => Some(StorageClass::UniformConstant),
             _ => None,
         };
         // Storage classes can be specified via attribute. Compute that here, and emit diagnostics.
@@ -255,6 +393,7 @@ impl<'tcx> CodegenCx<'tcx> {
         (spirv_ty, storage_class)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn declare_shader_interface_for_param(
         &self,
         entry_arg_abi: &ArgAbi<'tcx, Ty<'tcx>>,
@@ -263,9 +402,22 @@ impl<'tcx> CodegenCx<'tcx> {
         bx: &mut Builder<'_, 'tcx>,
         call_args: &mut Vec<SpirvValue>,
         decoration_locations: &mut FxHashMap<StorageClass, u32>,
+        execution_model: ExecutionModel,
     ) {
         let attrs = AggregatedSpirvAttributes::parse(self, self.tcx.hir().attrs(hir_param.hir_id));
 
+        // Specialization constants aren't part of the interface, they're passed
+        // to the entry-point `fn` directly.
+        if let Some(spec_constant) = &attrs.spec_constant {
+            call_args.push(self.declare_spec_constant_param(
+                entry_arg_abi,
+                hir_param,
+                &attrs,
+                spec_constant,
+            ));
+            return;
+        }
+
         // Pre-allocate the module-scoped `OpVariable`'s *Result* ID.
         let var = self.emit_global().id();
 
@@ -276,21 +428,109 @@ impl<'tcx> CodegenCx<'tcx> {
         // which we represent with `SpirvType::InterfaceBlock` (see its doc comment).
         // This "interface block" construct is also required for "runtime arrays".
         let is_unsized = self.lookup_type(value_spirv_type).sizeof(self).is_none();
+        // Arrays of descriptors (images, samplers or `TypedBuffer`s) are descriptor
+        // arrays, which are bound directly, instead of being wrapped in a block.
+        let is_descriptor_array = match self.lookup_type(value_spirv_type) {
+            SpirvType::Array { element, .. } | SpirvType::RuntimeArray { element } => matches!(
+                self.lookup_type(element),
+                SpirvType::Image { .. }
+                    | SpirvType::Sampler
+                    | SpirvType::SampledImage { .. }
+                    | SpirvType::AccelerationStructureKhr
+                    | SpirvType::InterfaceBlock { .. }
+            ),
+            _ => false,
+        };
         let var_ptr_spirv_type;
         let (value_ptr, value_len) = match storage_class {
+            StorageClass::UniformConstant | StorageClass::Uniform | StorageClass::StorageBuffer
+                if is_descriptor_array =>
+            {
+                var_ptr_spirv_type = self.type_ptr_to(value_spirv_type);
+
+                // The length of a runtime descriptor array is only known to the host, and
+                // can't be queried (`OpArrayLength` only works on runtime arrays in blocks),
+                // so the slice's length is a placeholder, the largest possible `usize`. This
+                // means `len()` is meaningless and indexing isn't bounds checked: nothing may
+                // rely on this length for size or bounds reasoning.
+                let value_len = if is_unsized {
+                    self.emit_global()
+                        .capability(Capability::RuntimeDescriptorArray);
+                    let len_spirv_type = self.type_isize();
+                    Some(self.constant_int(len_spirv_type, u64::from(u32::MAX)))
+                } else {
+                    None
+                };
+
+                (var.with_type(var_ptr_spirv_type), value_len)
+            }
             StorageClass::PushConstant | StorageClass::Uniform | StorageClass::StorageBuffer => {
-                var_ptr_spirv_type = self.type_ptr_to(
-                    SpirvType::InterfaceBlock {
-                        inner_type: value_spirv_type,
+                // Values that can't be stored in blocks as-is (e.g. `bool`s) are stored
+                // differently, and copied to/from a variable of their original type.
+                let mut lowering_failed = false;
+                let lowering = match *entry_arg_abi.layout.ty.kind() {
+                    TyKind::Ref(_, value_ty, mutbl) => {
+                        let path = match hir_param.pat.kind {
+                            hir::PatKind::Binding(_, _, ident, _) => ident.to_string(),
+                            _ => format!("{}", value_ty),
+                        };
+                        let value_layout = self.layout_of(value_ty);
+                        match lower_interface_type(self, hir_param.ty_span, value_layout, &path) {
+                            // Writes would have to be copied back to the whole buffer once the
+                            // entry point returns, clobbering those of other invocations.
+                            Ok(Some(_)) if mutbl == hir::Mutability::Mut => {
+                                self.tcx.sess.span_err(
+                                    hir_param.ty_span,
+                                    &format!(
+                                        "`{}` has to be stored differently in an interface block \
+                                         (e.g. because it contains `bool`s, `enum`s or `Option`s), \
+                                         which is only supported for `&` parameters, not `&mut`",
+                                        value_ty
+                                    ),
+                                );
+                                lowering_failed = true;
+                                None
+                            }
+                            Ok(lowering) => {
+                                lowering.map(|lowering| (lowering, value_layout.align.abi))
+                            }
+                            Err(message) => {
+                                self.tcx.sess.span_err(hir_param.ty_span, &message);
+                                lowering_failed = true;
+                                None
+                            }
+                        }
                     }
-                    .def(hir_param.span, self),
+                    _ => None,
+                };
+
+                let inner_type = lowering
+                    .as_ref()
+                    .map_or(value_spirv_type, |(lowering, _)| lowering.ty);
+                self.check_param_block_layout(
+                    entry_arg_abi.layout.ty,
+                    hir_param,
+                    storage_class,
+                    var,
+                    inner_type,
                 );
 
-                let value_ptr = bx.struct_gep(var.with_type(var_ptr_spirv_type), 0);
+                var_ptr_spirv_type = self.type_ptr_to(
+                    SpirvType::InterfaceBlock { inner_type }.def(hir_param.span, self),
+                );
+
+                let mut value_ptr = bx.struct_gep(var.with_type(var_ptr_spirv_type), 0);
+                if let Some((lowering, align)) = lowering {
+                    let copy = bx.alloca(value_spirv_type, align);
+                    copy_from_interface(bx, &lowering, value_ptr, copy);
+                    value_ptr = copy;
+                }
 
                 let value_len = if is_unsized {
                     match self.lookup_type(value_spirv_type) {
                         SpirvType::RuntimeArray { .. } => {}
+                        // Already reported by `lower_interface_type`.
+                        _ if lowering_failed => {}
                         _ => self.tcx.sess.span_err(
                             hir_param.ty_span,
                             "only plain slices are supported as unsized types",
@@ -386,6 +626,13 @@ impl<'tcx> CodegenCx<'tcx> {
             );
             decoration_supersedes_location = true;
         }
+        if let Some(index) = attrs.input_attachment_index.map(|attr| attr.value) {
+            self.emit_global().decorate(
+                var,
+                Decoration::InputAttachmentIndex,
+                std::iter::once(Operand::LiteralInt32(index)),
+            );
+        }
         if attrs.flat.is_some() {
             self.emit_global()
                 .decorate(var, Decoration::Flat, std::iter::empty());
@@ -400,6 +647,25 @@ impl<'tcx> CodegenCx<'tcx> {
                 );
             }
         }
+        if let Some(per_primitive_nv) = attrs.per_primitive_nv {
+            let mut emit = self.emit_global();
+            emit.decorate(var, Decoration::PerPrimitiveNV, std::iter::empty());
+            emit.extension("SPV_NV_mesh_shader");
+            emit.capability(Capability::MeshShadingNV);
+            drop(emit);
+            let valid = match execution_model {
+                ExecutionModel::MeshNV => storage_class == StorageClass::Output,
+                ExecutionModel::Fragment => storage_class == StorageClass::Input,
+                _ => false,
+            };
+            if !valid {
+                self.tcx.sess.span_err(
+                    per_primitive_nv.span,
+                    "#[spirv(per_primitive_nv)] is only valid on mesh shader Output \
+                     and fragment shader Input variables",
+                );
+            }
+        }
 
         // Assign locations from left to right, incrementing each storage class
         // individually.
@@ -439,6 +705,142 @@ impl<'tcx> CodegenCx<'tcx> {
         }
     }
 
+    /// Checks the layout of a block parameter against its type's
+    /// `#[spirv(block(layout = "..."))]`, if any, and records which layout
+    /// rules `spirv-val` has to be told about.
+    fn check_param_block_layout(
+        &self,
+        param_ty: Ty<'tcx>,
+        hir_param: &hir::Param<'tcx>,
+        storage_class: StorageClass,
+        var: Word,
+        inner_type: Word,
+    ) {
+        let mut value_ty = match *param_ty.kind() {
+            TyKind::Ref(_, value_ty, _) => value_ty,
+            _ => return,
+        };
+        // The layout of the elements of e.g. `&[T]` and `&[T; N]` is chosen by `T`.
+        while let TyKind::Slice(element) | TyKind::Array(element, _) = *value_ty.kind() {
+            value_ty = element;
+        }
+        let adt_def = match *value_ty.kind() {
+            TyKind::Adt(adt_def, _) => adt_def,
+            _ => return,
+        };
+        let layout = match AggregatedSpirvAttributes::parse(self, self.tcx.get_attrs(adt_def.did))
+            .block_layout
+        {
+            Some(layout) => layout.value,
+            None => return,
+        };
+        let path = match hir_param.pat.kind {
+            hir::PatKind::Binding(_, _, ident, _) => ident.to_string(),
+            _ => self.tcx.def_path_str(adt_def.did),
+        };
+        self.check_block_layout(hir_param.ty_span, layout, inner_type, &path);
+        self.block_layout_decorations.borrow_mut().insert(
+            var,
+            BlockLayoutDecoration {
+                uniform_buffer_standard_layout: layout == BlockLayout::Std430
+                    && storage_class == StorageClass::Uniform,
+                scalar_block_layout: layout == BlockLayout::Scalar,
+            },
+        );
+    }
+
+    fn declare_spec_constant_param(
+        &self,
+        entry_arg_abi: &ArgAbi<'tcx, Ty<'tcx>>,
+        hir_param: &hir::Param<'tcx>,
+        attrs: &AggregatedSpirvAttributes,
+        spec_constant: &Spanned<SpecConstant>,
+    ) -> SpirvValue {
+        let interface_attr_span = attrs
+            .storage_class
+            .map(|attr| attr.span)
+            .or_else(|| attrs.builtin.map(|attr| attr.span))
+            .or_else(|| attrs.descriptor_set.map(|attr| attr.span))
+            .or_else(|| attrs.binding.map(|attr| attr.span))
+            .or_else(|| attrs.input_attachment_index.map(|attr| attr.span))
+            .or_else(|| attrs.flat.map(|attr| attr.span))
+            .or_else(|| attrs.invariant.map(|attr| attr.span))
+            .or_else(|| attrs.per_primitive_nv.map(|attr| attr.span));
+        if let Some(span) = interface_attr_span {
+            self.tcx.sess.span_err(
+                span,
+                "#[spirv(spec_constant)] parameters are not part of the interface, \
+                 and can't have interface attributes",
+            );
+        }
+
+        let Spanned {
+            value: SpecConstant { id, ref default },
+            span,
+        } = *spec_constant;
+        let ty = entry_arg_abi
+            .layout
+            .spirv_type_immediate(hir_param.ty_span, self);
+        let invalid_default = || -> ! {
+            self.tcx.sess.span_fatal(
+                span,
+                &format!(
+                    "spec_constant default value doesn't fit in `{}`",
+                    entry_arg_abi.layout.ty
+                ),
+            )
+        };
+        // Negative defaults have to be given as strings (e.g. `default = "-1"`), as `-1` isn't
+        // a literal, and so not valid in attributes.
+        let (opcode, operands) = match self.lookup_type(ty) {
+            SpirvType::Integer(32, signed) => {
+                let value = match default {
+                    None => Some(0),
+                    Some(LitKind::Int(value, _)) if signed => {
+                        i32::try_from(*value).ok().map(|value| value as u32)
+                    }
+                    Some(LitKind::Int(value, _)) => u32::try_from(*value).ok(),
+                    Some(LitKind::Str(value, _)) if signed => {
+                        value.as_str().parse::<i32>().ok().map(|value| value as u32)
+                    }
+                    Some(LitKind::Str(value, _)) => value.as_str().parse().ok(),
+                    Some(_) => None,
+                };
+                let value = value.unwrap_or_else(|| invalid_default());
+                (Op::SpecConstant, vec![Operand::LiteralInt32(value)])
+            }
+            SpirvType::Float(32) => {
+                let value = match default {
+                    None => Some(0.0),
+                    Some(LitKind::Float(value, _)) | Some(LitKind::Str(value, _)) => {
+                        value.as_str().replace('_', "").parse().ok()
+                    }
+                    Some(_) => None,
+                };
+                let value = value.unwrap_or_else(|| invalid_default());
+                (Op::SpecConstant, vec![Operand::LiteralFloat32(value)])
+            }
+            SpirvType::Bool => match default {
+                None | Some(LitKind::Bool(false)) => (Op::SpecConstantFalse, vec![]),
+                Some(LitKind::Bool(true)) => (Op::SpecConstantTrue, vec![]),
+                Some(_) => invalid_default(),
+            },
+            _ => self.tcx.sess.span_fatal(
+                hir_param.ty_span,
+                &format!(
+                    "invalid spec_constant parameter type `{}` \
+                     (expected `u32`, `i32`, `f32` or `bool`)",
+                    entry_arg_abi.layout.ty
+                ),
+            ),
+        };
+        let spec_constant = self.def_spec_constant(ty, opcode, operands, id);
+        if let hir::PatKind::Binding(_, _, ident, _) = &hir_param.pat.kind {
+            self.emit_global().name(spec_constant, ident.to_string());
+        }
+        spec_constant.with_type(ty)
+    }
+
     // Kernel mode takes its interface as function parameters(??)
     // OpEntryPoints cannot be OpLinkage, so write out a stub to call through.
     fn kernel_entry_stub(
@@ -485,3 +887,93 @@ impl<'tcx> CodegenCx<'tcx> {
         fn_id
     }
 }
+
+fn pointee(bx: &Builder<'_, '_>, ptr: SpirvValue) -> Word {
+    match bx.lookup_type(ptr.ty) {
+        SpirvType::Pointer { pointee } => pointee,
+        other => bx.fatal(&format!("expected pointer type, found {:?}", other)),
+    }
+}
+
+/// Copies a value from an interface block, where it's stored as `lowering.ty`, to `dst`, which
+/// has its original type.
+fn copy_from_interface(
+    bx: &mut Builder<'_, '_>,
+    lowering: &InterfaceLowering,
+    src: SpirvValue,
+    dst: SpirvValue,
+) {
+    let align = Align::from_bytes(0).unwrap();
+    let copy_value =
+        |bx: &mut Builder<'_, '_>, lowering: Option<&InterfaceLowering>, src, dst| match lowering {
+            Some(lowering) => copy_from_interface(bx, lowering, src, dst),
+            None => {
+                let value = bx.load(src, align);
+                bx.store(value, dst, align);
+            }
+        };
+    let is_nonzero = |bx: &mut Builder<'_, '_>, src| {
+        let value = bx.load(src, align);
+        let zero = bx.constant_int(value.ty, 0);
+        bx.icmp(IntPredicate::IntNE, value, zero)
+    };
+    match lowering.kind {
+        InterfaceLoweringKind::Bool => {
+            let is_true = is_nonzero(bx, src);
+            let ty = pointee(bx, dst);
+            let (one, zero) = (bx.constant_int(ty, 1), bx.constant_int(ty, 0));
+            let value = bx.select(is_true, one, zero);
+            bx.store(value, dst, align);
+        }
+        InterfaceLoweringKind::Discriminant { signed } => {
+            let value = bx.load(src, align);
+            let value = bx.intcast(value, pointee(bx, dst), signed);
+            bx.store(value, dst, align);
+        }
+        InterfaceLoweringKind::Option { ref tag, ref value } => {
+            let is_some_src = bx.struct_gep(src, 0);
+            let is_some = is_nonzero(bx, is_some_src);
+            let value_src = bx.struct_gep(src, 1);
+            match *tag {
+                OptionTag::Direct {
+                    tag_member,
+                    value_member,
+                    none,
+                    some,
+                } => {
+                    let tag_dst = bx.struct_gep(dst, tag_member);
+                    let ty = pointee(bx, tag_dst);
+                    let (some, none) = (
+                        bx.constant_int(ty, some as u64),
+                        bx.constant_int(ty, none as u64),
+                    );
+                    let tag = bx.select(is_some, some, none);
+                    bx.store(tag, tag_dst, align);
+                    let value_dst = bx.struct_gep(dst, value_member);
+                    copy_value(bx, value.as_deref(), value_src, value_dst);
+                }
+                OptionTag::Niche { none } => {
+                    copy_value(bx, value.as_deref(), value_src, dst);
+                    let some = bx.load(dst, align);
+                    let none = bx.constant_int(some.ty, none as u64);
+                    let value = bx.select(is_some, some, none);
+                    bx.store(value, dst, align);
+                }
+            }
+        }
+        InterfaceLoweringKind::Struct(ref fields) => {
+            for (i, (member, field)) in fields.iter().enumerate() {
+                let field_src = bx.struct_gep(src, i as u64);
+                let field_dst = bx.struct_gep(dst, *member);
+                copy_value(bx, field.as_ref(), field_src, field_dst);
+            }
+        }
+        InterfaceLoweringKind::Array { count, ref element } => {
+            for i in 0..count {
+                let element_src = bx.struct_gep(src, i);
+                let element_dst = bx.struct_gep(dst, i);
+                copy_from_interface(bx, element, element_src, element_dst);
+            }
+        }
+    }
+}
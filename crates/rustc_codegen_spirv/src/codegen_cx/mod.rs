@@ -1,18 +1,28 @@
+#[cfg(test)]
+mod test;
+
+mod block_layout;
 mod constant;
 mod declare;
 mod entry;
 mod type_;
 
 use crate::builder::{ExtInst, InstructionTable};
-use crate::builder_spirv::{BuilderCursor, BuilderSpirv, SpirvValue, SpirvValueKind};
+use crate::builder_spirv::{
+    BuilderCursor, BuilderSpirv, SpirvValue, SpirvValueExt, SpirvValueKind,
+};
 use crate::decorations::{
-    CustomDecoration, SerializedSpan, UnrollLoopsDecoration, ZombieDecoration,
+    AtomicScopeDecoration, BlockLayoutDecoration, CustomDecoration, DefaultAtomicScopeDecoration,
+    SerializedSpan, UnrollLoopsDecoration, ZombieDecoration,
 };
 use crate::spirv_type::{SpirvType, SpirvTypePrinter, TypeCache};
-use crate::symbols::Symbols;
-use rspirv::dr::{Module, Operand};
-use rspirv::spirv::{AddressingModel, Decoration, LinkageType, MemoryModel, StorageClass, Word};
-use rustc_codegen_ssa::mir::debuginfo::{FunctionDebugContext, VariableKind};
+use crate::symbols::{parse_scope, Symbols};
+use rspirv::dr::{Instruction, Module, Operand};
+use rspirv::spirv::{
+    AddressingModel, Capability, Decoration, LinkageType, MemoryModel, MemorySemantics, Op, Scope,
+    SourceLanguage, StorageClass, Word,
+};
+use rustc_codegen_ssa::mir::debuginfo::{DebugScope, FunctionDebugContext, VariableKind};
 use rustc_codegen_ssa::traits::{
     AsmMethods, BackendTypes, CoverageInfoMethods, DebugInfoMethods, MiscMethods,
 };
@@ -22,15 +32,17 @@ use rustc_middle::mir::mono::CodegenUnit;
 use rustc_middle::mir::Body;
 use rustc_middle::ty::layout::{HasParamEnv, HasTyCtxt};
 use rustc_middle::ty::{Instance, ParamEnv, PolyExistentialTraitRef, Ty, TyCtxt, TyS};
+use rustc_session::config::DebugInfo;
 use rustc_session::Session;
 use rustc_span::def_id::LOCAL_CRATE;
 use rustc_span::symbol::{sym, Symbol};
-use rustc_span::{SourceFile, Span, DUMMY_SP};
+use rustc_span::{BytePos, SourceFile, Span, DUMMY_SP};
 use rustc_target::abi::call::FnAbi;
 use rustc_target::abi::{HasDataLayout, TargetDataLayout};
 use rustc_target::spec::{HasTargetSpec, Target};
 use std::cell::{Cell, RefCell};
 use std::iter::once;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::str::FromStr;
 
@@ -55,12 +67,24 @@ pub struct CodegenCx<'tcx> {
     /// get `LoopControl::UNROLL` applied to all of their loops' `OpLoopMerge`
     /// instructions, during structuralization.
     unroll_loops_decorations: RefCell<FxHashMap<Word, UnrollLoopsDecoration>>,
+    /// Entry points with `#[spirv(...(atomic_scope = "..."))]`, see `AtomicScopeDecoration`.
+    pub atomic_scope_decorations: RefCell<FxHashMap<Word, AtomicScopeDecoration>>,
+    /// Interface variables of `#[spirv(block(layout = "..."))]` types, see `BlockLayoutDecoration`.
+    pub block_layout_decorations: RefCell<FxHashMap<Word, BlockLayoutDecoration>>,
+    /// The constant used as the scope of atomics, see `DefaultAtomicScopeDecoration`.
+    default_atomic_scope: Cell<Option<Word>>,
     pub kernel_mode: bool,
     /// Cache of all the builtin symbols we need
     pub sym: Rc<Symbols>,
     pub instruction_table: InstructionTable,
     pub zombie_undefs_for_system_fn_addrs: RefCell<FxHashMap<Word, Word>>,
+    /// `OpFunctionPointerINTEL`s for the functions whose address is taken (outside of system
+    /// crates), keyed by function. The linker turns these into indices, and the indirect calls
+    /// into an `OpSwitch` over them (see `linker/fn_pointers.rs`).
+    pub fn_ptr_constants: RefCell<FxHashMap<Word, Word>>,
     pub libm_intrinsics: RefCell<FxHashMap<Word, super::builder::libm_intrinsics::LibmIntrinsic>>,
+    /// Map from source file name to the `OpString` referenced by its `OpSource` and `OpLine`s
+    debug_file_names: RefCell<FxHashMap<String, Word>>,
 
     /// Simple `panic!("...")` and builtin panics (from MIR `Assert`s) call `#[lang = "panic"]`.
     pub panic_fn_id: Cell<Option<Word>>,
@@ -80,7 +104,9 @@ impl<'tcx> CodegenCx<'tcx> {
         let mut spirv_version = None;
         let mut memory_model = None;
         let mut kernel_mode = false;
+        let mut extensions = Vec::new();
         for &feature in &tcx.sess.target_features {
+            let feature_str = feature.as_str();
             if feature == sym.kernel {
                 kernel_mode = true;
             } else if feature == sym.spirv10 {
@@ -101,6 +127,13 @@ impl<'tcx> CodegenCx<'tcx> {
                 memory_model = Some(MemoryModel::Vulkan);
             } else if feature == sym.glsl450 {
                 memory_model = Some(MemoryModel::GLSL450);
+            } else if let Some(capability) = parse_capability_feature(&feature_str) {
+                // Only checked by the linker, see `linker::Options::declared_capabilities`.
+                if let Err(err) = capability {
+                    tcx.sess.err(&err);
+                }
+            } else if let Some(extension) = feature_str.strip_prefix("ext:") {
+                extensions.push(extension.to_string());
             } else {
                 tcx.sess.err(&format!("Unknown feature {}", feature));
             }
@@ -109,7 +142,7 @@ impl<'tcx> CodegenCx<'tcx> {
         Self {
             tcx,
             codegen_unit,
-            builder: BuilderSpirv::new(spirv_version, memory_model, kernel_mode),
+            builder: BuilderSpirv::new(spirv_version, memory_model, kernel_mode, &extensions),
             instances: Default::default(),
             function_parameter_values: Default::default(),
             type_cache: Default::default(),
@@ -117,11 +150,16 @@ impl<'tcx> CodegenCx<'tcx> {
             ext_inst: Default::default(),
             zombie_decorations: Default::default(),
             unroll_loops_decorations: Default::default(),
+            atomic_scope_decorations: Default::default(),
+            block_layout_decorations: Default::default(),
+            default_atomic_scope: Default::default(),
             kernel_mode,
             sym,
             instruction_table: InstructionTable::new(),
             zombie_undefs_for_system_fn_addrs: Default::default(),
+            fn_ptr_constants: Default::default(),
             libm_intrinsics: Default::default(),
+            debug_file_names: Default::default(),
             panic_fn_id: Default::default(),
             panic_bounds_check_fn_id: Default::default(),
             i8_i16_atomics_allowed: false,
@@ -185,6 +223,43 @@ impl<'tcx> CodegenCx<'tcx> {
         );
     }
 
+    /// Returns the `OpString` naming `file`, declaring it (along with an `OpSource`) the first time
+    /// it's needed.
+    pub fn def_debug_file(&self, file: &SourceFile) -> Word {
+        let name = file.name.to_string();
+        if let Some(&id) = self.debug_file_names.borrow().get(&name) {
+            return id;
+        }
+        let mut emit = self.emit_global();
+        let id = emit.id();
+        let string = Instruction::new(
+            Op::String,
+            None,
+            Some(id),
+            vec![Operand::LiteralString(name.clone())],
+        );
+        let source = Instruction::new(
+            Op::Source,
+            None,
+            None,
+            vec![
+                Operand::SourceLanguage(SourceLanguage::Unknown),
+                Operand::LiteralInt32(0),
+                Operand::IdRef(id),
+            ],
+        );
+        // `OpString`/`OpSource` have to come before any `OpName`s.
+        let debugs = &mut emit.module_mut().debugs;
+        let index = debugs
+            .iter()
+            .position(|inst| !matches!(inst.class.opcode, Op::String | Op::Source))
+            .unwrap_or_else(|| debugs.len());
+        debugs.insert(index, source);
+        debugs.insert(index, string);
+        self.debug_file_names.borrow_mut().insert(name, id);
+        id
+    }
+
     pub fn is_system_crate(&self) -> bool {
         self.tcx
             .sess
@@ -195,6 +270,23 @@ impl<'tcx> CodegenCx<'tcx> {
             || self.tcx.crate_name(LOCAL_CRATE) == self.sym.num_traits
     }
 
+    /// The scope of atomics lowered from `core::sync::atomic`, i.e. `--atomic-scope`, which the
+    /// linker can still override per entry point (see `DefaultAtomicScopeDecoration`).
+    pub fn default_atomic_scope(&self) -> SpirvValue {
+        let u32_ty = SpirvType::Integer(32, false).def(DUMMY_SP, self);
+        let id = match self.default_atomic_scope.get() {
+            Some(id) => id,
+            None => {
+                // Not deduplicated with other constants, so that the linker only replaces this one.
+                let scope = self.codegen_args.atomic_scope as u32;
+                let id = self.emit_global().constant_u32(u32_ty, scope);
+                self.default_atomic_scope.set(Some(id));
+                id
+            }
+        };
+        id.with_type(u32_ty)
+    }
+
     // FIXME(eddyb) should this just be looking at `kernel_mode`?
     pub fn logical_addressing_model(&self) -> bool {
         self.emit_global()
@@ -206,6 +298,23 @@ impl<'tcx> CodegenCx<'tcx> {
             })
     }
 
+    pub fn is_fn_ptr_type(&self, ty: Word) -> bool {
+        match self.lookup_type(ty) {
+            SpirvType::Pointer { pointee } => {
+                matches!(self.lookup_type(pointee), SpirvType::Function { .. })
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `value` is a pointer to a vtable, see `vtable_addr_of`.
+    pub fn is_vtable(&self, value: SpirvValue) -> bool {
+        self.vtables
+            .borrow()
+            .values()
+            .any(|&vtable| vtable == value)
+    }
+
     pub fn finalize_module(self) -> Module {
         let mut result = self.builder.finalize();
         result.annotations.extend(
@@ -218,6 +327,23 @@ impl<'tcx> CodegenCx<'tcx> {
                         .into_inner()
                         .into_iter()
                         .map(|(id, unroll_loops)| unroll_loops.encode(id)),
+                )
+                .chain(
+                    self.atomic_scope_decorations
+                        .into_inner()
+                        .into_iter()
+                        .map(|(id, atomic_scope)| atomic_scope.encode(id)),
+                )
+                .chain(
+                    self.block_layout_decorations
+                        .into_inner()
+                        .into_iter()
+                        .map(|(id, block_layout)| block_layout.encode(id)),
+                )
+                .chain(
+                    self.default_atomic_scope
+                        .get()
+                        .map(|id| DefaultAtomicScopeDecoration {}.encode(id)),
                 ),
         );
         result
@@ -262,8 +388,53 @@ impl<'tcx> CodegenCx<'tcx> {
     }
 }
 
+/// Parses a `cap:NAME` target feature into the capability it declares, or returns `None` if
+/// `feature` is some other target feature.
+pub fn parse_capability_feature(feature: &str) -> Option<Result<Capability, String>> {
+    let name = feature.strip_prefix("cap:")?;
+    Some(match name.parse() {
+        Ok(capability) => Ok(capability),
+        Err(_) => Err(format!(
+            "Unknown capability {} in feature {}",
+            name, feature
+        )),
+    })
+}
+
 pub struct CodegenArgs {
     pub module_output_type: ModuleOutputType,
+    /// Memory scope of atomics lowered from `core::sync::atomic` (and atomic fences).
+    pub atomic_scope: Scope,
+    /// Storage classes ordered by (non-relaxed) atomics lowered from `core::sync::atomic`,
+    /// `None` meaning all of the ones that apply to the target.
+    pub atomic_memory_semantics: Option<MemorySemantics>,
+
+    /// Whether to run dead code elimination, disabled by `--no-dce` or `NO_DCE`.
+    pub dce: bool,
+    /// Whether to compact SPIR-V IDs, disabled by `--no-compact-ids` or `NO_COMPACT_IDS`.
+    pub compact_ids: bool,
+    /// Whether to make the control flow structured, disabled by `--no-structurize` or
+    /// `NO_STRUCTURIZE`.
+    pub structurize: bool,
+    /// Whether to structurize with the new structurizer, disabled by `--old-structurizer` or
+    /// `OLD_STRUCTURIZER`.
+    pub use_new_structurizer: bool,
+    /// Whether to run `spirv-val` on the output, disabled by `--no-spirv-val` or `NO_SPIRV_VAL`.
+    pub spirv_val: bool,
+
+    /// Directory to write every module to, before linking them, from `--dump-pre-link` or
+    /// `DUMP_PRE_LINK`.
+    pub dump_pre_link: Option<PathBuf>,
+    /// File to write the merged module to, before the linker passes, from `--dump-post-merge`
+    /// or `DUMP_POST_MERGE`.
+    pub dump_post_merge: Option<PathBuf>,
+    /// File to write each module to, after splitting into one module per entry point, from
+    /// `--dump-post-split` or `DUMP_POST_SPLIT`. With `--module-output=multiple`, the entry
+    /// point name is added before the extension, e.g. `post-split.main_fs.spv`.
+    pub dump_post_split: Option<PathBuf>,
+    /// File to write each linked module to, before `spirv-opt`, from `--dump-post-link` or
+    /// `DUMP_POST_LINK`. Named per entry point like `dump_post_split`.
+    pub dump_post_link: Option<PathBuf>,
 }
 
 impl CodegenArgs {
@@ -283,11 +454,115 @@ impl CodegenArgs {
             "single output or multiple output",
             "[single|multiple]",
         );
+        opts.optopt(
+            "",
+            "atomic-scope",
+            "memory scope of atomics from core::sync::atomic",
+            "[cross_device|device|queue_family|workgroup|subgroup|invocation]",
+        );
+        opts.optopt(
+            "",
+            "atomic-memory-semantics",
+            "storage classes ordered by atomics from core::sync::atomic",
+            "uniform,workgroup,cross_workgroup,subgroup,atomic_counter,image,output",
+        );
+        opts.optflag("", "no-dce", "disable dead code elimination");
+        opts.optflag("", "no-compact-ids", "disable compaction of SPIR-V IDs");
+        opts.optflag(
+            "",
+            "no-structurize",
+            "disable making the control flow structured",
+        );
+        opts.optflag("", "old-structurizer", "use the old structurizer");
+        opts.optflag(
+            "",
+            "no-spirv-val",
+            "disable running spirv-val on the output",
+        );
+        opts.optopt(
+            "",
+            "dump-pre-link",
+            "dump all input modules, before linking, into a directory",
+            "DIR",
+        );
+        opts.optopt(
+            "",
+            "dump-post-merge",
+            "dump the merged module, before the linker passes, to a file",
+            "FILE",
+        );
+        opts.optopt(
+            "",
+            "dump-post-split",
+            "dump the modules, after splitting per entry point, to a file",
+            "FILE",
+        );
+        opts.optopt(
+            "",
+            "dump-post-link",
+            "dump the linked modules, before spirv-opt, to a file",
+            "FILE",
+        );
         let matches = opts.parse(args)?;
         let module_output_type =
             matches.opt_get_default("module-output", ModuleOutputType::Single)?;
-        Ok(Self { module_output_type })
+        let atomic_scope = match matches.opt_str("atomic-scope") {
+            Some(name) => {
+                parse_scope(&name).ok_or(rustc_session::getopts::Fail::UnrecognizedOption(name))?
+            }
+            None => Scope::Device,
+        };
+        let atomic_memory_semantics = matches
+            .opt_str("atomic-memory-semantics")
+            .map(|names| parse_memory_semantics(&names))
+            .transpose()?;
+        let enabled = |flag: &str, env_var: &str| {
+            !matches.opt_present(flag) && std::env::var_os(env_var).is_none()
+        };
+        let dump_path = |opt: &str, env_var: &str| {
+            matches
+                .opt_str(opt)
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os(env_var).map(PathBuf::from))
+        };
+        Ok(Self {
+            module_output_type,
+            atomic_scope,
+            atomic_memory_semantics,
+
+            dce: enabled("no-dce", "NO_DCE"),
+            compact_ids: enabled("no-compact-ids", "NO_COMPACT_IDS"),
+            structurize: enabled("no-structurize", "NO_STRUCTURIZE"),
+            use_new_structurizer: enabled("old-structurizer", "OLD_STRUCTURIZER"),
+            spirv_val: enabled("no-spirv-val", "NO_SPIRV_VAL"),
+
+            dump_pre_link: dump_path("dump-pre-link", "DUMP_PRE_LINK"),
+            dump_post_merge: dump_path("dump-post-merge", "DUMP_POST_MERGE"),
+            dump_post_split: dump_path("dump-post-split", "DUMP_POST_SPLIT"),
+            dump_post_link: dump_path("dump-post-link", "DUMP_POST_LINK"),
+        })
+    }
+}
+
+fn parse_memory_semantics(names: &str) -> Result<MemorySemantics, rustc_session::getopts::Fail> {
+    let mut semantics = MemorySemantics::NONE;
+    for name in names.split(',').filter(|name| !name.is_empty()) {
+        semantics |= match name {
+            "uniform" => MemorySemantics::UNIFORM_MEMORY,
+            "subgroup" => MemorySemantics::SUBGROUP_MEMORY,
+            "workgroup" => MemorySemantics::WORKGROUP_MEMORY,
+            "cross_workgroup" => MemorySemantics::CROSS_WORKGROUP_MEMORY,
+            "atomic_counter" => MemorySemantics::ATOMIC_COUNTER_MEMORY,
+            "image" => MemorySemantics::IMAGE_MEMORY,
+            "output" => MemorySemantics::OUTPUT_MEMORY,
+            _ => {
+                return Err(rustc_session::getopts::Fail::UnrecognizedOption(
+                    name.to_string(),
+                ))
+            }
+        };
     }
+    Ok(semantics)
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -318,8 +593,8 @@ impl<'tcx> BackendTypes for CodegenCx<'tcx> {
     type Funclet = ();
 
     type DIScope = ();
-    type DILocation = ();
-    type DIVariable = ();
+    type DILocation = Span;
+    type DIVariable = Symbol;
 }
 
 impl<'tcx> HasTyCtxt<'tcx> for CodegenCx<'tcx> {
@@ -374,6 +649,7 @@ impl<'tcx> MiscMethods<'tcx> for CodegenCx<'tcx> {
         }
         .def(span, self);
 
+        let function = function.def_cx(self);
         if self.is_system_crate() {
             // Create these undefs up front instead of on demand in SpirvValue::def because
             // SpirvValue::def can't use cx.emit()
@@ -384,12 +660,27 @@ impl<'tcx> MiscMethods<'tcx> for CodegenCx<'tcx> {
                     // We want a unique ID for these undefs, so don't use the caching system.
                     self.emit_global().undef(ty, None)
                 });
+        } else {
+            // Same as above, but these are real values, see `fn_ptr_constants`.
+            self.fn_ptr_constants
+                .borrow_mut()
+                .entry(function)
+                .or_insert_with(|| {
+                    let mut emit = self.emit_global();
+                    let id = emit.id();
+                    // TODO: rspirv doesn't have a function_pointer_intel function
+                    emit.module_mut().types_global_values.push(Instruction::new(
+                        Op::FunctionPointerINTEL,
+                        Some(ty),
+                        Some(id),
+                        vec![Operand::IdRef(function)],
+                    ));
+                    id
+                });
         }
 
         SpirvValue {
-            kind: SpirvValueKind::FnAddr {
-                function: function.def_cx(self),
-            },
+            kind: SpirvValueKind::FnAddr { function },
             ty,
         }
     }
@@ -438,11 +729,16 @@ impl<'tcx> DebugInfoMethods<'tcx> for CodegenCx<'tcx> {
         _: &FnAbi<'tcx, &'tcx TyS<'tcx>>,
         _: Option<Self::Function>,
     ) -> Self::DIScope {
-        todo!()
     }
 
-    fn dbg_loc(&self, _: Self::DIScope, _: Option<Self::DILocation>, _: Span) -> Self::DILocation {
-        todo!()
+    fn dbg_loc(
+        &self,
+        _: Self::DIScope,
+        _: Option<Self::DILocation>,
+        span: Span,
+    ) -> Self::DILocation {
+        // SPIR-V has no notion of scopes (or inlining), `OpLine` only needs the span itself.
+        span
     }
 
     fn create_function_debug_context(
@@ -450,10 +746,22 @@ impl<'tcx> DebugInfoMethods<'tcx> for CodegenCx<'tcx> {
         _instance: Instance<'tcx>,
         _fn_abi: &FnAbi<'tcx, Ty<'tcx>>,
         _llfn: Self::Function,
-        _mir: &Body<'_>,
+        mir: &Body<'_>,
     ) -> Option<FunctionDebugContext<Self::DIScope, Self::DILocation>> {
-        // TODO: This is ignored. Do we want to implement this at some point?
-        None
+        if self.tcx.sess.opts.debuginfo == DebugInfo::None {
+            return None;
+        }
+        // All scopes are the same (and cover every file), as only `OpLine`s and `OpName`s are
+        // emitted, see `dbg_loc` and `dbg_var_addr`.
+        let scope = DebugScope {
+            dbg_scope: Some(()),
+            inlined_at: None,
+            file_start_pos: BytePos(0),
+            file_end_pos: BytePos(u32::MAX),
+        };
+        Some(FunctionDebugContext {
+            scopes: mir.source_scopes.iter().map(|_| scope).collect(),
+        })
     }
 
     fn extend_scope_to_file(
@@ -461,22 +769,19 @@ impl<'tcx> DebugInfoMethods<'tcx> for CodegenCx<'tcx> {
         _scope_metadata: Self::DIScope,
         _file: &SourceFile,
     ) -> Self::DIScope {
-        todo!()
     }
 
-    fn debuginfo_finalize(&self) {
-        todo!()
-    }
+    fn debuginfo_finalize(&self) {}
 
     fn create_dbg_var(
         &self,
-        _variable_name: Symbol,
+        variable_name: Symbol,
         _variable_type: Ty<'tcx>,
         _scope_metadata: Self::DIScope,
         _variable_kind: VariableKind,
         _span: Span,
     ) -> Self::DIVariable {
-        todo!()
+        variable_name
     }
 }
 
@@ -0,0 +1,70 @@
+use super::{CodegenArgs, ModuleOutputType};
+use rspirv::spirv::Scope;
+use std::path::PathBuf;
+
+fn parse(args: &[&str]) -> CodegenArgs {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    CodegenArgs::parse(&args).expect("Failed to parse args")
+}
+
+#[test]
+fn flags_present() {
+    let args = parse(&[
+        "--module-output=multiple",
+        "--atomic-scope=workgroup",
+        "--no-dce",
+        "--no-compact-ids",
+        "--no-structurize",
+        "--old-structurizer",
+        "--no-spirv-val",
+        "--dump-post-link=dumps/post link.spv",
+    ]);
+    assert_eq!(args.module_output_type, ModuleOutputType::Multiple);
+    assert_eq!(args.atomic_scope, Scope::Workgroup);
+    assert!(!args.dce);
+    assert!(!args.compact_ids);
+    assert!(!args.structurize);
+    assert!(!args.use_new_structurizer);
+    assert!(!args.spirv_val);
+    assert_eq!(
+        args.dump_post_link,
+        Some(PathBuf::from("dumps/post link.spv"))
+    );
+}
+
+#[test]
+fn invalid_atomic_scope() {
+    let args = vec!["--atomic-scope=global".to_string()];
+    assert!(CodegenArgs::parse(&args).is_err());
+}
+
+// Environment variables are shared between test threads, so this is the only test that sets them,
+// or relies on them being unset.
+#[test]
+fn env_var_fallback() {
+    let args = parse(&[]);
+    assert_eq!(args.module_output_type, ModuleOutputType::Single);
+    assert_eq!(args.atomic_scope, Scope::Device);
+    assert!(args.dce);
+    assert!(args.compact_ids);
+    assert!(args.structurize);
+    assert!(args.use_new_structurizer);
+    assert!(args.spirv_val);
+    assert_eq!(args.dump_pre_link, None);
+    assert_eq!(args.dump_post_link, None);
+
+    std::env::set_var("NO_DCE", "1");
+    std::env::set_var("DUMP_POST_LINK", "from-env.spv");
+    let from_env = parse(&[]);
+    let flag_wins = parse(&["--dump-post-link=from-flag.spv"]);
+    std::env::remove_var("NO_DCE");
+    std::env::remove_var("DUMP_POST_LINK");
+
+    assert!(!from_env.dce);
+    assert!(from_env.compact_ids);
+    assert_eq!(from_env.dump_post_link, Some(PathBuf::from("from-env.spv")));
+    assert_eq!(
+        flag_wins.dump_post_link,
+        Some(PathBuf::from("from-flag.spv"))
+    );
+}
@@ -109,6 +109,43 @@ impl CustomDecoration for UnrollLoopsDecoration {
     const ENCODING_PREFIX: &'static str = "U";
 }
 
+/// The `OpConstant` used as the memory `Scope` of atomics lowered from
+/// `core::sync::atomic` (with the value given by `--atomic-scope`), which
+/// should be replaced, in functions only reachable from entry points with
+/// an `AtomicScopeDecoration`, by the scope of those entry points.
+#[derive(Deserialize, Serialize)]
+pub struct DefaultAtomicScopeDecoration {}
+
+impl CustomDecoration for DefaultAtomicScopeDecoration {
+    const ENCODING_PREFIX: &'static str = "A";
+}
+
+/// An entry point `OpFunction` with `#[spirv(...(atomic_scope = "..."))]`,
+/// overriding the `Scope` of the atomics reachable from it.
+#[derive(Deserialize, Serialize)]
+pub struct AtomicScopeDecoration {
+    pub scope: u32,
+}
+
+impl CustomDecoration for AtomicScopeDecoration {
+    const ENCODING_PREFIX: &'static str = "S";
+}
+
+/// An interface variable with a `#[spirv(block(layout = "..."))]` type, using layout rules
+/// that spirv-val has to be told about. Unlike other decorations, these are only stripped
+/// after linking, by `link.rs`, which passes them on to spirv-val.
+#[derive(Deserialize, Serialize)]
+pub struct BlockLayoutDecoration {
+    /// `std430` rules used by a `Uniform` block (`VK_KHR_uniform_buffer_standard_layout`).
+    pub uniform_buffer_standard_layout: bool,
+    /// `scalar` rules (`VK_EXT_scalar_block_layout`).
+    pub scalar_block_layout: bool,
+}
+
+impl CustomDecoration for BlockLayoutDecoration {
+    const ENCODING_PREFIX: &'static str = "B";
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct ZombieDecoration {
     pub reason: String,
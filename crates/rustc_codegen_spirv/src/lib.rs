@@ -113,12 +113,14 @@ mod codegen_cx;
 mod decorations;
 mod link;
 mod linker;
+mod reflect;
 mod spirv_type;
 mod spirv_type_constraints;
 mod symbols;
 
 use builder::Builder;
-use codegen_cx::{CodegenArgs, CodegenCx, ModuleOutputType};
+use codegen_cx::{CodegenArgs, CodegenCx};
+pub use reflect::reflection_path;
 pub use rspirv;
 use rspirv::binary::Assemble;
 use rustc_ast::expand::allocator::AllocatorKind;
@@ -393,7 +395,6 @@ impl CodegenBackend for SpirvCodegenBackend {
         // TODO: Can we merge this sym with the one in symbols.rs?
         let legalize = !sess.target_features.contains(&Symbol::intern("kernel"));
         let codegen_args = CodegenArgs::from_session(sess);
-        let emit_multiple_modules = codegen_args.module_output_type == ModuleOutputType::Multiple;
 
         let timer = sess.timer("link_crate");
         link::link(
@@ -402,7 +403,7 @@ impl CodegenBackend for SpirvCodegenBackend {
             outputs,
             &codegen_results.crate_name.as_str(),
             legalize,
-            emit_multiple_modules,
+            &codegen_args,
         );
         drop(timer);
 
@@ -1,4 +1,6 @@
-use crate::{linker, SpirvCodegenBackend, SpirvModuleBuffer, SpirvThinBuffer};
+use crate::codegen_cx::{parse_capability_feature, CodegenArgs, ModuleOutputType};
+use crate::decorations::{BlockLayoutDecoration, CustomDecoration};
+use crate::{linker, reflect, SpirvCodegenBackend, SpirvModuleBuffer, SpirvThinBuffer};
 use rustc_codegen_ssa::back::lto::{LtoModuleCodegen, SerializedModule, ThinModule, ThinShared};
 use rustc_codegen_ssa::back::write::CodegenContext;
 use rustc_codegen_ssa::{CodegenResults, NativeLib};
@@ -14,10 +16,10 @@ use rustc_session::config::{CrateType, DebugInfo, Lto, OptLevel, OutputFilenames
 use rustc_session::output::{check_file_is_writeable, invalid_output_for_target, out_filename};
 use rustc_session::utils::NativeLibKind;
 use rustc_session::Session;
-use std::env;
+use spirv_tools::val;
 use std::ffi::{CString, OsStr};
 use std::fs::File;
-use std::io::{BufWriter, Read, Write};
+use std::io::{BufWriter, Read};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tar::{Archive, Builder, Header};
@@ -28,7 +30,7 @@ pub fn link<'a>(
     outputs: &OutputFilenames,
     crate_name: &str,
     legalize: bool,
-    emit_multiple_modules: bool,
+    codegen_args: &CodegenArgs,
 ) {
     let output_metadata = sess.opts.output_types.contains_key(&OutputType::Metadata);
     for &crate_type in sess.crate_types().iter() {
@@ -67,7 +69,7 @@ pub fn link<'a>(
                     &out_filename,
                     codegen_results,
                     legalize,
-                    emit_multiple_modules,
+                    codegen_args,
                 ),
                 other => sess.err(&format!("CrateType {:?} not supported yet", other)),
             }
@@ -110,7 +112,7 @@ fn link_exe(
     out_filename: &Path,
     codegen_results: &CodegenResults,
     legalize: bool,
-    emit_multiple_modules: bool,
+    codegen_args: &CodegenArgs,
 ) {
     let mut objects = Vec::new();
     let mut rlibs = Vec::new();
@@ -129,12 +131,20 @@ fn link_exe(
         codegen_results,
     );
 
-    let spv_binary = do_link(sess, &objects, &rlibs, legalize, emit_multiple_modules);
+    let spv_binary = do_link(sess, &objects, &rlibs, legalize, codegen_args);
 
     use rspirv::binary::Assemble;
     match spv_binary {
-        linker::LinkResult::SingleModule(spv_binary) => {
-            post_link_single_module(sess, spv_binary.assemble(), out_filename);
+        linker::LinkResult::SingleModule(mut spv_binary) => {
+            let val_options = take_validator_options(&mut spv_binary);
+            post_link_single_module(
+                sess,
+                codegen_args,
+                spv_binary.assemble(),
+                val_options,
+                out_filename,
+                None,
+            );
         }
         linker::LinkResult::MultipleModules(map) => {
             let mut root_file_name = out_filename.file_name().unwrap().to_owned();
@@ -144,10 +154,18 @@ fn link_exe(
                 std::fs::create_dir_all(&out_dir).unwrap();
             }
             let mut hashmap = FxHashMap::default();
-            for (name, spv_binary) in map {
+            for (name, mut spv_binary) in map {
                 let mut module_filename = out_dir.clone();
                 module_filename.push(sanitize_filename::sanitize(&name));
-                post_link_single_module(sess, spv_binary.assemble(), &module_filename);
+                let val_options = take_validator_options(&mut spv_binary);
+                post_link_single_module(
+                    sess,
+                    codegen_args,
+                    spv_binary.assemble(),
+                    val_options,
+                    &module_filename,
+                    Some(&name),
+                );
                 hashmap.insert(name, module_filename);
             }
             let file = File::create(out_filename).unwrap();
@@ -156,12 +174,35 @@ fn link_exe(
     }
 }
 
-fn post_link_single_module(sess: &Session, spv_binary: Vec<u32>, out_filename: &Path) {
-    if let Ok(ref path) = std::env::var("DUMP_POST_LINK") {
-        File::create(path)
-            .unwrap()
-            .write_all(spirv_tools::binary::from_binary(&spv_binary))
-            .unwrap();
+/// Removes the `BlockLayoutDecoration`s left in `module` by the linker, turning them into
+/// the options spirv-val needs to accept the block layouts they describe.
+fn take_validator_options(module: &mut rspirv::dr::Module) -> val::ValidatorOptions {
+    let mut options = val::ValidatorOptions::default();
+    for (_, decoration) in BlockLayoutDecoration::decode_all(module) {
+        let decoration = decoration.deserialize();
+        options.uniform_buffer_standard_layout |= decoration.uniform_buffer_standard_layout;
+        options.scalar_block_layout |= decoration.scalar_block_layout;
+    }
+    BlockLayoutDecoration::remove_all(module);
+    options
+}
+
+fn post_link_single_module(
+    sess: &Session,
+    codegen_args: &CodegenArgs,
+    spv_binary: Vec<u32>,
+    val_options: val::ValidatorOptions,
+    out_filename: &Path,
+    entry_name: Option<&str>,
+) {
+    if let Some(path) = &codegen_args.dump_post_link {
+        let path = match entry_name {
+            Some(entry_name) => linker::dump_path_for_entry(path, entry_name),
+            None => path.clone(),
+        };
+        if linker::write_dump(sess, &path, &spv_binary).is_err() {
+            return;
+        }
     }
 
     let spv_binary = if sess.opts.optimize != OptLevel::No || sess.opts.debuginfo == DebugInfo::None
@@ -172,8 +213,8 @@ fn post_link_single_module(sess: &Session, spv_binary: Vec<u32>, out_filename: &
         spv_binary
     };
 
-    if env::var("NO_SPIRV_VAL").is_err() {
-        do_spirv_val(sess, &spv_binary, out_filename);
+    if codegen_args.spirv_val {
+        do_spirv_val(sess, &spv_binary, val_options, out_filename);
     }
 
     {
@@ -188,6 +229,50 @@ fn post_link_single_module(sess: &Session, spv_binary: Vec<u32>, out_filename: &
 
         drop(save_modules_timer);
     }
+
+    {
+        let _timer = sess.timer("link_reflect");
+        // The reflection info is optional, so failing to produce it shouldn't fail the build.
+        let reflection = match rspirv::dr::load_words(&spv_binary) {
+            Ok(module) => reflect::reflect(&module),
+            Err(e) => {
+                let mut err = sess.struct_warn("failed to load spirv-binary for reflection");
+                err.note(&format!("module `{}`", out_filename.display()));
+                err.note(&format!("{:?}", e));
+                err.emit();
+                return;
+            }
+        };
+        let reflection = match reflection {
+            Ok(reflection) => reflection,
+            Err(e) => {
+                let mut err = sess.struct_warn("failed to reflect spirv-binary");
+                err.note(&format!("module `{}`", out_filename.display()));
+                err.note(&e);
+                err.emit();
+                return;
+            }
+        };
+        let reflection_path = match reflect::reflection_path(out_filename) {
+            Some(reflection_path) => reflection_path,
+            None => {
+                sess.warn(&format!(
+                    "output file `{}` has no file name to write reflection info next to",
+                    out_filename.display()
+                ));
+                return;
+            }
+        };
+        let result = File::create(&reflection_path).and_then(|file| {
+            serde_json::to_writer(BufWriter::new(file), &reflection).map_err(Into::into)
+        });
+        if let Err(e) = result {
+            let mut err = sess.struct_warn("failed to write reflection info to disk");
+            err.note(&format!("file `{}`", reflection_path.display()));
+            err.note(&format!("I/O error: {:#}", e));
+            err.emit();
+        }
+    }
 }
 
 fn do_spirv_opt(sess: &Session, spv_binary: Vec<u32>, filename: &Path) -> Vec<u32> {
@@ -248,12 +333,17 @@ fn do_spirv_opt(sess: &Session, spv_binary: Vec<u32>, filename: &Path) -> Vec<u3
     }
 }
 
-fn do_spirv_val(sess: &Session, spv_binary: &[u32], filename: &Path) {
-    use spirv_tools::val::{self, Validator};
+fn do_spirv_val(
+    sess: &Session,
+    spv_binary: &[u32],
+    options: val::ValidatorOptions,
+    filename: &Path,
+) {
+    use spirv_tools::val::Validator;
 
     let validator = val::create(sess.target.options.env.parse().ok());
 
-    if let Err(e) = validator.validate(spv_binary, None) {
+    if let Err(e) = validator.validate(spv_binary, Some(options)) {
         let mut err = sess.struct_err(&e.to_string());
         err.note("spirv-val failed");
         err.note(&format!("module `{}`", filename.display()));
@@ -409,6 +499,32 @@ pub fn read_metadata(rlib: &Path) -> Result<MetadataRef, String> {
     }
 }
 
+/// Writes every module to `path`, which is (re)created as a directory, for `--dump-pre-link`.
+fn dump_pre_link(
+    sess: &Session,
+    path: &Path,
+    modules: &[rspirv::dr::Module],
+) -> linker::Result<()> {
+    use rspirv::binary::Assemble;
+    let dir_result = if path.is_file() {
+        std::fs::remove_file(path)
+    } else {
+        Ok(())
+    };
+    if let Err(e) = dir_result.and_then(|()| std::fs::create_dir_all(path)) {
+        sess.err(&format!("failed to write {}: {}", path.display(), e));
+        return Err(rustc_errors::ErrorReported);
+    }
+    for (num, module) in modules.iter().enumerate() {
+        linker::write_dump(
+            sess,
+            &path.join(format!("mod_{}.spv", num)),
+            &module.assemble(),
+        )?;
+    }
+    Ok(())
+}
+
 /// This is the actual guts of linking: the rest of the link-related functions are just digging through rustc's
 /// shenanigans to collect all the object files we need to link.
 fn do_link(
@@ -416,7 +532,7 @@ fn do_link(
     objects: &[PathBuf],
     rlibs: &[PathBuf],
     legalize: bool,
-    emit_multiple_modules: bool,
+    codegen_args: &CodegenArgs,
 ) -> linker::LinkResult {
     fn load(bytes: &[u8]) -> rspirv::dr::Module {
         let mut loader = rspirv::dr::Loader::new();
@@ -445,31 +561,30 @@ fn do_link(
         }
     }
 
-    if let Ok(ref path) = env::var("DUMP_PRE_LINK") {
-        use rspirv::binary::Assemble;
-        let path = Path::new(path);
-        if path.is_file() {
-            std::fs::remove_file(path).unwrap();
-        }
-        std::fs::create_dir_all(path).unwrap();
-        for (num, module) in modules.iter().enumerate() {
-            File::create(path.join(format!("mod_{}.spv", num)))
-                .unwrap()
-                .write_all(spirv_tools::binary::from_binary(&module.assemble()))
-                .unwrap();
+    if let Some(path) = &codegen_args.dump_pre_link {
+        if dump_pre_link(sess, path, &modules).is_err() {
+            sess.abort_if_errors();
         }
     }
     drop(load_modules_timer);
 
     // Do the link...
     let options = linker::Options {
-        dce: env::var("NO_DCE").is_err(),
-        compact_ids: env::var("NO_COMPACT_IDS").is_err(),
+        dce: codegen_args.dce,
+        compact_ids: codegen_args.compact_ids,
         inline: legalize,
         mem2reg: legalize,
-        structurize: env::var("NO_STRUCTURIZE").is_err(),
-        use_new_structurizer: env::var("OLD_STRUCTURIZER").is_err(),
-        emit_multiple_modules,
+        structurize: codegen_args.structurize,
+        use_new_structurizer: codegen_args.use_new_structurizer,
+        emit_multiple_modules: codegen_args.module_output_type == ModuleOutputType::Multiple,
+        dump_post_merge: codegen_args.dump_post_merge.clone(),
+        dump_post_split: codegen_args.dump_post_split.clone(),
+        declared_capabilities: sess
+            .target_features
+            .iter()
+            // Unknown capabilities were already reported by `CodegenCx::new`.
+            .filter_map(|feature| parse_capability_feature(&feature.as_str())?.ok())
+            .collect(),
     };
 
     let link_result = linker::link(sess, modules, &options);
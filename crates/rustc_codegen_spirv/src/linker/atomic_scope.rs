@@ -0,0 +1,131 @@
+//! Applies the `#[spirv(...(atomic_scope = "..."))]` of entry points to the atomics reachable
+//! from them.
+//!
+//! Atomics lowered from `core::sync::atomic` all use the same constant as their scope (see
+//! `DefaultAtomicScopeDecoration`). Functions only reachable from entry points with an
+//! `AtomicScopeDecoration` get that constant replaced with the entry points' scope. Functions
+//! reachable from entry points with different scopes would have to be duplicated, so those are
+//! an error instead, if they use the constant at all.
+
+use super::{apply_rewrite_rules, id, Result};
+use crate::decorations::{AtomicScopeDecoration, CustomDecoration, DefaultAtomicScopeDecoration};
+use rspirv::dr::{Function, Instruction, Module, Operand};
+use rspirv::spirv::{Op, Word};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::ErrorReported;
+use rustc_session::Session;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Reached {
+    Unreached,
+    /// Only reachable from entry points with this scope (`None` being the default scope).
+    Scope(Option<u32>),
+    /// Reachable from entry points with different scopes.
+    Conflict,
+}
+
+pub fn apply_atomic_scopes(sess: &Session, module: &mut Module) -> Result<()> {
+    let defaults: FxHashSet<Word> = DefaultAtomicScopeDecoration::decode_all(module)
+        .map(|(id, _)| id)
+        .collect();
+    let entry_scopes: FxHashMap<Word, u32> = AtomicScopeDecoration::decode_all(module)
+        .map(|(id, atomic_scope)| (id, atomic_scope.deserialize().scope))
+        .collect();
+    DefaultAtomicScopeDecoration::remove_all(module);
+    AtomicScopeDecoration::remove_all(module);
+    if defaults.is_empty() || entry_scopes.is_empty() {
+        return Ok(());
+    }
+
+    let func_indices: FxHashMap<Word, usize> = module
+        .functions
+        .iter()
+        .enumerate()
+        .map(|(i, func)| (func.def_id().unwrap(), i))
+        .collect();
+    let callees: Vec<Vec<usize>> = module
+        .functions
+        .iter()
+        .map(|func| {
+            func.all_inst_iter()
+                .filter(|inst| inst.class.opcode == Op::FunctionCall)
+                .filter_map(|inst| func_indices.get(&inst.operands[0].unwrap_id_ref()))
+                .copied()
+                .collect()
+        })
+        .collect();
+
+    let mut reached = vec![Reached::Unreached; module.functions.len()];
+    for entry in &module.entry_points {
+        let entry_id = entry.operands[1].unwrap_id_ref();
+        let scope = entry_scopes.get(&entry_id).copied();
+        let mut stack: Vec<usize> = func_indices.get(&entry_id).copied().into_iter().collect();
+        while let Some(func) = stack.pop() {
+            reached[func] = match reached[func] {
+                Reached::Unreached => Reached::Scope(scope),
+                Reached::Scope(s) if s != scope => Reached::Conflict,
+                Reached::Scope(_) | Reached::Conflict => continue,
+            };
+            stack.extend(&callees[func]);
+        }
+    }
+
+    let uses_default = |func: &Function| {
+        func.all_inst_iter().any(|inst| {
+            inst.operands
+                .iter()
+                .any(|op| matches!(op, Operand::IdRef(id) if defaults.contains(id)))
+        })
+    };
+    let u32_ty = module
+        .types_global_values
+        .iter()
+        .find(|inst| inst.result_id.map_or(false, |id| defaults.contains(&id)))
+        .and_then(|inst| inst.result_type)
+        .unwrap();
+    let mut scope_constants = FxHashMap::default();
+    let mut has_errors = false;
+    for (i, &reached) in reached.iter().enumerate() {
+        match reached {
+            Reached::Scope(Some(scope)) => {
+                let constant = *scope_constants.entry(scope).or_insert_with(|| {
+                    let constant = id(module.header.as_mut().unwrap());
+                    module.types_global_values.push(Instruction::new(
+                        Op::Constant,
+                        Some(u32_ty),
+                        Some(constant),
+                        vec![Operand::LiteralInt32(scope)],
+                    ));
+                    constant
+                });
+                let rewrite_rules = defaults.iter().map(|&id| (id, constant)).collect();
+                apply_rewrite_rules(&rewrite_rules, &mut module.functions[i].blocks);
+            }
+            Reached::Conflict if uses_default(&module.functions[i]) => {
+                let func_id = module.functions[i].def_id().unwrap();
+                let name = module
+                    .debugs
+                    .iter()
+                    .find(|inst| {
+                        inst.class.opcode == Op::Name && inst.operands[0].unwrap_id_ref() == func_id
+                    })
+                    .map_or_else(
+                        || format!("%{}", func_id),
+                        |inst| inst.operands[1].unwrap_literal_string().to_string(),
+                    );
+                sess.err(&format!(
+                    "`{}` uses atomics, but is reachable from entry points with different \
+                     `atomic_scope`s",
+                    name
+                ));
+                has_errors = true;
+            }
+            _ => {}
+        }
+    }
+    if has_errors {
+        Err(ErrorReported)
+    } else {
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
-use rspirv::dr::{Instruction, Module};
+use super::Result;
+use crate::spirv_type::image_type_capabilities;
+use rspirv::dr::{Instruction, Module, Operand};
 use rspirv::spirv::{Capability, Op};
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::ErrorReported;
+use rustc_session::Session;
 
 pub fn remove_extra_capabilities(module: &mut Module) {
     let used_capabilities = used_capabilities(module);
@@ -8,11 +12,32 @@ pub fn remove_extra_capabilities(module: &mut Module) {
         Capability::Int8,
         Capability::Int16,
         Capability::Int64,
+        Capability::Int64Atomics,
         Capability::Float16,
         Capability::Float64,
         Capability::IntegerFunctions2INTEL,
         Capability::DemoteToHelperInvocationEXT,
         Capability::DerivativeControl,
+        Capability::AtomicFloat32AddEXT,
+        Capability::AtomicFloat64AddEXT,
+        Capability::GroupNonUniform,
+        Capability::GroupNonUniformVote,
+        Capability::GroupNonUniformArithmetic,
+        Capability::GroupNonUniformBallot,
+        Capability::GroupNonUniformShuffle,
+        Capability::GroupNonUniformShuffleRelative,
+        Capability::GroupNonUniformQuad,
+        Capability::Image1D,
+        Capability::Sampled1D,
+        Capability::ImageBuffer,
+        Capability::SampledBuffer,
+        Capability::ImageCubeArray,
+        Capability::SampledCubeArray,
+        Capability::ImageRect,
+        Capability::SampledRect,
+        Capability::InputAttachment,
+        Capability::StorageImageMultisample,
+        Capability::ImageMSArray,
     ]
     .iter()
     .copied()
@@ -25,9 +50,45 @@ pub fn remove_extra_capabilities(module: &mut Module) {
 }
 
 fn used_capabilities(module: &Module) -> FxHashSet<Capability> {
+    let mut int_widths = FxHashMap::default();
+    let mut float_widths = FxHashMap::default();
+    let mut pointees = FxHashMap::default();
+    let mut value_types = FxHashMap::default();
+    for inst in module.all_inst_iter() {
+        match inst.class.opcode {
+            Op::TypeInt => {
+                int_widths.insert(
+                    inst.result_id.unwrap(),
+                    inst.operands[0].unwrap_literal_int32(),
+                );
+            }
+            Op::TypeFloat => {
+                float_widths.insert(
+                    inst.result_id.unwrap(),
+                    inst.operands[0].unwrap_literal_int32(),
+                );
+            }
+            Op::TypePointer => {
+                pointees.insert(inst.result_id.unwrap(), inst.operands[1].unwrap_id_ref());
+            }
+            _ => {}
+        }
+        if let (Some(result_id), Some(result_type)) = (inst.result_id, inst.result_type) {
+            value_types.insert(result_id, result_type);
+        }
+    }
+    // The type of the value an atomic instruction operates on: either its result
+    // type, or for `OpAtomicStore`, the pointee type of its pointer operand.
+    let atomic_value_type = |inst: &Instruction| match inst.result_type {
+        Some(ty) => Some(ty),
+        None => {
+            let pointer_type = value_types.get(&inst.operands[0].unwrap_id_ref())?;
+            pointees.get(pointer_type).copied()
+        }
+    };
+
     let mut set = FxHashSet::default();
     for inst in module.all_inst_iter() {
-        set.extend(inst.class.capabilities);
         match inst.class.opcode {
             Op::TypeInt => match inst.operands[0].unwrap_literal_int32() {
                 8 => {
@@ -50,12 +111,103 @@ fn used_capabilities(module: &Module) -> FxHashSet<Capability> {
                 }
                 _ => {}
             },
+            Op::TypeImage => {
+                set.extend(image_type_capabilities(
+                    inst.operands[1].unwrap_dim(),
+                    inst.operands[3].unwrap_literal_int32(),
+                    inst.operands[4].unwrap_literal_int32(),
+                    inst.operands[5].unwrap_literal_int32(),
+                ));
+            }
+            // rspirv lists every `AtomicFloat*AddEXT` capability for this
+            // instruction, so only keep the one matching the operand width.
+            Op::AtomicFAddEXT => {
+                match atomic_value_type(inst).and_then(|ty| float_widths.get(&ty)) {
+                    Some(32) => {
+                        set.insert(Capability::AtomicFloat32AddEXT);
+                    }
+                    Some(64) => {
+                        set.insert(Capability::AtomicFloat64AddEXT);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            Op::AtomicLoad
+            | Op::AtomicStore
+            | Op::AtomicExchange
+            | Op::AtomicCompareExchange
+            | Op::AtomicCompareExchangeWeak
+            | Op::AtomicIIncrement
+            | Op::AtomicIDecrement
+            | Op::AtomicIAdd
+            | Op::AtomicISub
+            | Op::AtomicSMin
+            | Op::AtomicUMin
+            | Op::AtomicSMax
+            | Op::AtomicUMax
+            | Op::AtomicAnd
+            | Op::AtomicOr
+            | Op::AtomicXor => {
+                if atomic_value_type(inst).and_then(|ty| int_widths.get(&ty)) == Some(&64) {
+                    set.insert(Capability::Int64Atomics);
+                }
+            }
             _ => {}
         }
+        set.extend(inst.class.capabilities);
     }
     set
 }
 
+/// Capabilities the compiler declares depending on the target, which don't need to be declared
+/// with `+cap:...` target features.
+const IMPLICIT_CAPABILITIES: &[Capability] = &[
+    Capability::Shader,
+    Capability::Kernel,
+    Capability::Addresses,
+    Capability::Linkage,
+    Capability::VulkanMemoryModel,
+    Capability::VariablePointers,
+];
+
+/// Reports an error for every capability left in `module` that isn't in `declared` (or implied
+/// by one that is), unless no capabilities were declared at all.
+pub fn check_declared_capabilities(
+    sess: &Session,
+    module: &Module,
+    declared: &FxHashSet<Capability>,
+) -> Result<()> {
+    if declared.is_empty() {
+        return Ok(());
+    }
+    let mut allowed: FxHashSet<Capability> = IMPLICIT_CAPABILITIES.iter().copied().collect();
+    let mut to_visit: Vec<Capability> = declared.iter().copied().collect();
+    while let Some(capability) = to_visit.pop() {
+        if allowed.insert(capability) {
+            to_visit.extend(Operand::Capability(capability).required_capabilities());
+        }
+    }
+
+    let mut result = Ok(());
+    for inst in &module.capabilities {
+        let capability = inst.operands[0].unwrap_capability();
+        if !allowed.contains(&capability) {
+            sess.struct_err(&format!(
+                "the `{:?}` capability is required, but was not declared",
+                capability
+            ))
+            .note(&format!(
+                "declare it with the `+cap:{:?}` target feature (`SpirvBuilder::capability`)",
+                capability
+            ))
+            .emit();
+            result = Err(ErrorReported);
+        }
+    }
+    result
+}
+
 fn remove_capabilities(module: &mut Module, set: &FxHashSet<Capability>) {
     module.capabilities.retain(|inst| {
         inst.class.opcode != Op::Capability || !set.contains(&inst.operands[0].unwrap_capability())
@@ -76,7 +228,17 @@ fn additional_extensions(module: &Module, inst: &Instruction) -> &'static [&'sta
         let version = module.header.as_ref().unwrap().version();
         match inst.operands[0].unwrap_capability() {
             Capability::VulkanMemoryModel if version < (1, 5) => &["SPV_KHR_vulkan_memory_model"],
-            Capability::RuntimeDescriptorArray if version < (1, 5) => {
+            Capability::RuntimeDescriptorArray
+            | Capability::ShaderNonUniform
+            | Capability::UniformBufferArrayNonUniformIndexing
+            | Capability::SampledImageArrayNonUniformIndexing
+            | Capability::StorageBufferArrayNonUniformIndexing
+            | Capability::StorageImageArrayNonUniformIndexing
+            | Capability::InputAttachmentArrayNonUniformIndexing
+            | Capability::UniformTexelBufferArrayNonUniformIndexing
+            | Capability::StorageTexelBufferArrayNonUniformIndexing
+                if version < (1, 5) =>
+            {
                 &["SPV_EXT_descriptor_indexing"]
             }
             _ => &[],
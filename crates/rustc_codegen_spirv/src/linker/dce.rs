@@ -34,6 +34,13 @@ fn spread_roots(module: &Module, rooted: &mut FxHashSet<Word>) -> bool {
             }
         }
     }
+    // `OpExecutionModeId` (e.g. `LocalSizeId`) refers to constants, which are only
+    // used by the execution mode itself.
+    for inst in &module.execution_modes {
+        if rooted.contains(&inst.operands[0].unwrap_id_ref()) {
+            any |= root(inst, rooted);
+        }
+    }
     for func in &module.functions {
         if rooted.contains(&func.def_id().unwrap()) {
             for inst in func.all_inst_iter() {
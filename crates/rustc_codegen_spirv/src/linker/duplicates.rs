@@ -94,7 +94,7 @@ fn gather_annotations(annotations: &[Instruction]) -> FxHashMap<Word, Vec<u32>>
         .collect()
 }
 
-fn gather_names(debugs: &[Instruction]) -> FxHashMap<Word, String> {
+pub fn gather_names(debugs: &[Instruction]) -> FxHashMap<Word, String> {
     debugs
         .iter()
         .filter(|inst| inst.class.opcode == Op::Name)
@@ -0,0 +1,545 @@
+//! SPIR-V has no function pointers, but they can be emulated whenever all of the functions that
+//! could be called are known, which is the case once the whole program is linked.
+//!
+//! Codegen emits `OpFunctionPointerINTEL` for every function whose address is taken (including
+//! the ones in vtables), and `OpFunctionPointerCallINTEL` for every indirect call. This pass
+//! numbers the functions whose address is used, turns function pointers into those numbers, and
+//! every indirect call into an `OpSwitch` over them, with a direct call to each function of the
+//! right type.
+//!
+//! `dyn Trait` methods take their receiver as a type-erased pointer (to `()`), so calling them
+//! requires casting it back, which isn't allowed in the `Logical` addressing model. Functions
+//! taking such pointers get inlined, and `remove_erased_pointer_casts` later (after inlining and
+//! mem2reg) pairs up the casts with the ones that erased the pointer in the first place.
+
+use super::duplicates::{gather_names, remove_duplicate_types};
+use super::{apply_rewrite_rules, id, Result};
+use rspirv::dr::{Block, Instruction, Module, Operand};
+use rspirv::spirv::{AddressingModel, FunctionControl, Op, Word};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::ErrorReported;
+use rustc_session::Session;
+
+pub fn lower_fn_pointers(module: &mut Module) {
+    let fn_ptr_constants: FxHashMap<Word, Word> = module
+        .types_global_values
+        .iter()
+        .filter(|inst| inst.class.opcode == Op::FunctionPointerINTEL)
+        .map(|inst| (inst.result_id.unwrap(), inst.operands[0].unwrap_id_ref()))
+        .collect();
+
+    let mut pointees = FxHashMap::default();
+    let mut fn_types = FxHashMap::default();
+    let mut empty_structs = FxHashSet::default();
+    let mut void = None;
+    for inst in &module.types_global_values {
+        let result_id = inst.result_id;
+        match inst.class.opcode {
+            Op::TypePointer => {
+                pointees.insert(result_id.unwrap(), inst.operands[1].unwrap_id_ref());
+            }
+            Op::TypeFunction => {
+                let signature: Vec<Word> =
+                    inst.operands.iter().map(|op| op.unwrap_id_ref()).collect();
+                fn_types.insert(result_id.unwrap(), signature);
+            }
+            Op::TypeStruct if inst.operands.is_empty() => {
+                empty_structs.insert(result_id.unwrap());
+            }
+            Op::TypeVoid => void = result_id,
+            _ => {}
+        }
+    }
+    let erased_ptr_types: FxHashSet<Word> = pointees
+        .iter()
+        .filter(|(_, pointee)| empty_structs.contains(pointee))
+        .map(|(&ptr, _)| ptr)
+        .collect();
+
+    // Make type-erased pointers passed between functions visible to `remove_erased_pointer_casts`.
+    for func in &mut module.functions {
+        if func
+            .parameters
+            .iter()
+            .any(|param| erased_ptr_types.contains(&param.result_type.unwrap()))
+        {
+            let def = func.def.as_mut().unwrap();
+            let control = def.operands[0].unwrap_function_control();
+            def.operands[0] = Operand::FunctionControl(
+                (control - FunctionControl::DONT_INLINE) | FunctionControl::INLINE,
+            );
+        }
+    }
+
+    let has_indirect_calls = module.functions.iter().any(|func| {
+        func.all_inst_iter()
+            .any(|inst| inst.class.opcode == Op::FunctionPointerCallINTEL)
+    });
+    if fn_ptr_constants.is_empty() && !has_indirect_calls {
+        return;
+    }
+
+    // Number the functions whose address is used, starting at 1 (0 being null, e.g. for
+    // methods that can't be called through `dyn Trait`, in vtables).
+    let used_fn_ptr_constants: FxHashSet<Word> = module
+        .types_global_values
+        .iter()
+        .chain(
+            module
+                .functions
+                .iter()
+                .flat_map(|func| func.all_inst_iter()),
+        )
+        .flat_map(|inst| &inst.operands)
+        .filter_map(|op| match *op {
+            Operand::IdRef(id) if fn_ptr_constants.contains_key(&id) => Some(id),
+            _ => None,
+        })
+        .collect();
+    let address_taken: FxHashSet<Word> = used_fn_ptr_constants
+        .iter()
+        .map(|constant| fn_ptr_constants[constant])
+        .collect();
+    // (index, function, signature)
+    let callees: Vec<(u32, Word, &[Word])> = module
+        .functions
+        .iter()
+        .map(|func| func.def.as_ref().unwrap())
+        .filter(|def| address_taken.contains(&def.result_id.unwrap()))
+        .zip(1..)
+        .map(|(def, index)| {
+            let fn_type = def.operands[1].unwrap_id_ref();
+            (index, def.result_id.unwrap(), &fn_types[&fn_type][..])
+        })
+        .collect();
+    let indices: FxHashMap<Word, u32> = callees
+        .iter()
+        .map(|&(index, function, _)| (function, index))
+        .collect();
+
+    let value_types: FxHashMap<Word, Word> = module
+        .all_inst_iter()
+        .filter_map(|inst| Some((inst.result_id?, inst.result_type?)))
+        .collect();
+    let header = module.header.as_mut().unwrap();
+    for func in &mut module.functions {
+        let mut i = 0;
+        while i < func.blocks.len() {
+            let call_position = func.blocks[i]
+                .instructions
+                .iter()
+                .position(|inst| inst.class.opcode == Op::FunctionPointerCallINTEL);
+            let call_position = match call_position {
+                Some(call_position) => call_position,
+                None => {
+                    i += 1;
+                    continue;
+                }
+            };
+            let mut rest = func.blocks[i].instructions.split_off(call_position);
+            let call = rest.remove(0);
+            let label = func.blocks[i].label_id().unwrap();
+            let result_type = call.result_type.unwrap();
+            let fn_ptr = call.operands[0].unwrap_id_ref();
+            let args: Vec<Word> = call.operands[1..]
+                .iter()
+                .map(|op| op.unwrap_id_ref())
+                .collect();
+
+            let merge_label = id(header);
+            let default_label = id(header);
+            let mut switch_operands = vec![Operand::IdRef(fn_ptr), Operand::IdRef(default_label)];
+            let mut phi_operands = vec![];
+            let mut new_blocks = vec![];
+            for &(index, function, signature) in &callees {
+                let (return_type, params) = signature.split_first().unwrap();
+                let compatible = *return_type == result_type
+                    && params.len() == args.len()
+                    && params
+                        .iter()
+                        .zip(&args)
+                        .enumerate()
+                        .all(|(i, (param, arg))| {
+                            let arg_type = value_types[arg];
+                            *param == arg_type
+                                || (i == 0
+                                    && erased_ptr_types.contains(&arg_type)
+                                    && pointees.contains_key(param))
+                        });
+                if !compatible {
+                    continue;
+                }
+
+                let case_label = id(header);
+                let mut instructions = vec![];
+                let mut call_operands = vec![Operand::IdRef(function)];
+                for (&param, &arg) in params.iter().zip(&args) {
+                    if param == value_types[&arg] {
+                        call_operands.push(Operand::IdRef(arg));
+                    } else {
+                        let receiver = id(header);
+                        instructions.push(Instruction::new(
+                            Op::Bitcast,
+                            Some(param),
+                            Some(receiver),
+                            vec![Operand::IdRef(arg)],
+                        ));
+                        call_operands.push(Operand::IdRef(receiver));
+                    }
+                }
+                let result = id(header);
+                instructions.push(Instruction::new(
+                    Op::FunctionCall,
+                    Some(result_type),
+                    Some(result),
+                    call_operands,
+                ));
+                instructions.push(Instruction::new(
+                    Op::Branch,
+                    None,
+                    None,
+                    vec![Operand::IdRef(merge_label)],
+                ));
+                new_blocks.push(new_block(case_label, instructions));
+
+                switch_operands.push(Operand::LiteralInt32(index));
+                switch_operands.push(Operand::IdRef(case_label));
+                phi_operands.push(Operand::IdRef(result));
+                phi_operands.push(Operand::IdRef(case_label));
+            }
+            func.blocks[i].instructions.push(Instruction::new(
+                Op::Switch,
+                None,
+                None,
+                switch_operands,
+            ));
+            new_blocks.push(new_block(
+                default_label,
+                vec![Instruction::new(Op::Unreachable, None, None, vec![])],
+            ));
+
+            // The rest of the original block, which is now where its successors come from.
+            for inst in func.blocks.iter_mut().flat_map(|b| &mut b.instructions) {
+                if inst.class.opcode == Op::Phi {
+                    for parent in inst.operands.iter_mut().skip(1).step_by(2) {
+                        if *parent == Operand::IdRef(label) {
+                            *parent = Operand::IdRef(merge_label);
+                        }
+                    }
+                }
+            }
+            if Some(result_type) != void {
+                let result = if phi_operands.is_empty() {
+                    Instruction::new(Op::Undef, Some(result_type), call.result_id, vec![])
+                } else {
+                    Instruction::new(Op::Phi, Some(result_type), call.result_id, phi_operands)
+                };
+                rest.insert(0, result);
+            }
+            new_blocks.push(new_block(merge_label, rest));
+
+            // Continue with the merge block, in case there are more calls in it.
+            let new_block_count = new_blocks.len();
+            func.blocks.splice(i + 1..i + 1, new_blocks);
+            i += new_block_count;
+        }
+    }
+
+    // Function pointers are now just indices.
+    let u32_position = module.types_global_values.iter().position(|inst| {
+        inst.class.opcode == Op::TypeInt
+            && inst.operands[0].unwrap_literal_int32() == 32
+            && inst.operands[1].unwrap_literal_int32() == 0
+    });
+    // Moved to the front, as it may now be used by types declared before it.
+    let u32_type = match u32_position {
+        Some(position) => module.types_global_values.remove(position),
+        None => Instruction::new(
+            Op::TypeInt,
+            None,
+            Some(id(module.header.as_mut().unwrap())),
+            vec![Operand::LiteralInt32(32), Operand::LiteralInt32(0)],
+        ),
+    };
+    let u32 = u32_type.result_id.unwrap();
+    module.types_global_values.insert(0, u32_type);
+
+    let mut rewrite_rules = FxHashMap::default();
+    module.types_global_values.retain(|inst| {
+        let is_fn_ptr_type = inst.class.opcode == Op::TypePointer
+            && fn_types.contains_key(&inst.operands[1].unwrap_id_ref());
+        if is_fn_ptr_type {
+            rewrite_rules.insert(inst.result_id.unwrap(), u32);
+        }
+        !is_fn_ptr_type
+    });
+    for inst in &mut module.types_global_values {
+        if inst.class.opcode == Op::FunctionPointerINTEL {
+            let function = inst.operands[0].unwrap_id_ref();
+            let index = indices.get(&function).copied().unwrap_or(0);
+            *inst = Instruction::new(
+                Op::Constant,
+                Some(u32),
+                inst.result_id,
+                vec![Operand::LiteralInt32(index)],
+            );
+        }
+    }
+    // Function pointers loaded from vtables are cast from the integers stored in them.
+    for func in &mut module.functions {
+        let mut casts = FxHashMap::default();
+        for block in &mut func.blocks {
+            block.instructions.retain(|inst| {
+                let is_fn_ptr_cast = inst.class.opcode == Op::Bitcast
+                    && rewrite_rules.contains_key(&inst.result_type.unwrap());
+                if is_fn_ptr_cast {
+                    casts.insert(inst.result_id.unwrap(), inst.operands[0].unwrap_id_ref());
+                }
+                !is_fn_ptr_cast
+            });
+        }
+        apply_rewrite_rules(&casts, &mut func.blocks);
+    }
+    for inst in module.all_inst_iter_mut() {
+        if let Some(ref mut result_type) = inst.result_type {
+            if let Some(&rewrite) = rewrite_rules.get(result_type) {
+                *result_type = rewrite;
+            }
+        }
+        for op in &mut inst.operands {
+            if let Some(id) = op.id_ref_any_mut() {
+                if let Some(&rewrite) = rewrite_rules.get(id) {
+                    *id = rewrite;
+                }
+            }
+        }
+    }
+    // E.g. `*mut fn()` is now `*mut u32`, which may already exist.
+    remove_duplicate_types(module);
+}
+
+fn new_block(label: Word, instructions: Vec<Instruction>) -> Block {
+    Block {
+        label: Some(Instruction::new(Op::Label, None, Some(label), vec![])),
+        instructions,
+    }
+}
+
+/// Removes casts of pointers back from type-erased pointers (see `lower_fn_pointers`), by using
+/// the original pointer instead. Casts to a different type than the original one can't be
+/// reached (as e.g. the vtable always matches the receiver of a `dyn Trait` method call), so
+/// those are replaced with `OpUndef`.
+pub fn remove_erased_pointer_casts(sess: &Session, module: &mut Module) -> Result<()> {
+    let logical = module.memory_model.as_ref().map_or(false, |inst| {
+        inst.operands[0].unwrap_addressing_model() == AddressingModel::Logical
+    });
+    if !logical {
+        return Ok(());
+    }
+
+    let types: FxHashMap<Word, &Instruction> = module
+        .types_global_values
+        .iter()
+        .filter_map(|inst| Some((inst.result_id?, inst)))
+        .collect();
+    let is_pointer = |ty: Word| {
+        types
+            .get(&ty)
+            .map_or(false, |inst| inst.class.opcode == Op::TypePointer)
+    };
+    let global_value_types: FxHashMap<Word, Word> = module
+        .types_global_values
+        .iter()
+        .filter_map(|inst| Some((inst.result_id?, inst.result_type?)))
+        .collect();
+
+    let mut undefs = FxHashMap::default();
+    let names = gather_names(&module.debugs);
+    let mut has_errors = false;
+    for func in &mut module.functions {
+        // result id -> (result type, operand)
+        let casts: FxHashMap<Word, (Word, Word)> = func
+            .all_inst_iter()
+            .filter(|inst| {
+                inst.class.opcode == Op::Bitcast && is_pointer(inst.result_type.unwrap())
+            })
+            .map(|inst| {
+                let operand = inst.operands[0].unwrap_id_ref();
+                (
+                    inst.result_id.unwrap(),
+                    (inst.result_type.unwrap(), operand),
+                )
+            })
+            .collect();
+        if casts.is_empty() {
+            continue;
+        }
+        let header = module.header.as_mut().unwrap();
+        for &(ty, _) in casts.values() {
+            undefs.entry(ty).or_insert_with(|| id(header));
+        }
+        let mut value_types = global_value_types.clone();
+        value_types.extend(
+            func.all_inst_iter()
+                .filter_map(|inst| Some((inst.result_id?, inst.result_type?))),
+        );
+        // The pointer `erased` was cast from, if it's now being cast back to `ty`.
+        let original = |ty: Word, erased: Word| {
+            let &(_, original) = casts.get(&erased)?;
+            let original_ty = value_types[&original];
+            Some(if original_ty == ty {
+                original
+            } else if same_type_modulo_storage_classes(&types, original_ty, ty, 0) {
+                // Can't be used in place of the cast, so leave it to be reported.
+                return None;
+            } else {
+                undefs[&ty]
+            })
+        };
+
+        let mut rewrite_rules = FxHashMap::default();
+        // Erased pointers chosen between with `OpPhi` or `OpSelect` need new ones of the
+        // original type, right after them.
+        let mut merges = FxHashMap::default();
+        // Casts, and the `OpPhi`s and `OpSelect`s replaced by new ones, to remove if unused.
+        let mut removable: FxHashSet<Word> = casts.keys().copied().collect();
+        for (&cast, &(ty, erased)) in &casts {
+            if let Some(original) = original(ty, erased) {
+                rewrite_rules.insert(cast, original);
+            } else {
+                merges.insert(erased, (cast, ty));
+            }
+        }
+        for block in &mut func.blocks {
+            let mut j = 0;
+            while j < block.instructions.len() {
+                let inst = &block.instructions[j];
+                j += 1;
+                let result_id = inst.result_id;
+                let (cast, ty) = match result_id.and_then(|id| merges.get(&id)) {
+                    Some(&merge) => merge,
+                    None => continue,
+                };
+                let operands = match inst.class.opcode {
+                    Op::Phi => inst
+                        .operands
+                        .chunks(2)
+                        .map(|pair| match pair {
+                            [Operand::IdRef(value), parent] => {
+                                Some(vec![Operand::IdRef(original(ty, *value)?), parent.clone()])
+                            }
+                            _ => None,
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .map(|pairs| pairs.concat()),
+                    Op::Select => match inst.operands[..] {
+                        [ref condition, Operand::IdRef(a), Operand::IdRef(b)] => {
+                            match (original(ty, a), original(ty, b)) {
+                                (Some(a), Some(b)) => Some(vec![
+                                    condition.clone(),
+                                    Operand::IdRef(a),
+                                    Operand::IdRef(b),
+                                ]),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if let Some(operands) = operands {
+                    let merged = id(header);
+                    let opcode = inst.class.opcode;
+                    block.instructions.insert(
+                        j,
+                        Instruction::new(opcode, Some(ty), Some(merged), operands),
+                    );
+                    j += 1;
+                    rewrite_rules.insert(cast, merged);
+                    removable.insert(result_id.unwrap());
+                }
+            }
+        }
+        apply_rewrite_rules(&rewrite_rules, &mut func.blocks);
+
+        // Remove whatever is no longer used, including the casts that erased pointers.
+        loop {
+            let used: FxHashSet<Word> = func
+                .all_inst_iter()
+                .flat_map(|inst| &inst.operands)
+                .filter_map(|op| op.id_ref_any())
+                .collect();
+            let mut changed = false;
+            for block in &mut func.blocks {
+                block.instructions.retain(|inst| {
+                    let unused = removable.contains(&inst.result_id.unwrap_or(0))
+                        && !used.contains(&inst.result_id.unwrap());
+                    changed |= unused;
+                    !unused
+                });
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        if func
+            .all_inst_iter()
+            .any(|inst| casts.contains_key(&inst.result_id.unwrap_or(0)))
+        {
+            let func_id = func.def_id().unwrap();
+            let name = names
+                .get(&func_id)
+                .cloned()
+                .unwrap_or_else(|| format!("%{}", func_id));
+            sess.err(&format!("Cannot cast between pointer types in `{}`", name));
+            has_errors = true;
+        }
+    }
+    let used: FxHashSet<Word> = module
+        .functions
+        .iter()
+        .flat_map(|func| func.all_inst_iter())
+        .flat_map(|inst| &inst.operands)
+        .filter_map(|op| op.id_ref_any())
+        .collect();
+    module.types_global_values.extend(
+        undefs
+            .into_iter()
+            .filter(|(_, undef)| used.contains(undef))
+            .map(|(ty, undef)| Instruction::new(Op::Undef, Some(ty), Some(undef), vec![])),
+    );
+    if has_errors {
+        Err(ErrorReported)
+    } else {
+        Ok(())
+    }
+}
+
+/// Whether `a` and `b` are the same type, other than the storage classes of the pointers in them
+/// (which the specializer may have inferred differently).
+fn same_type_modulo_storage_classes(
+    types: &FxHashMap<Word, &Instruction>,
+    a: Word,
+    b: Word,
+    depth: u32,
+) -> bool {
+    // Recursive types are conservatively assumed to be the same.
+    if a == b || depth > 16 {
+        return true;
+    }
+    match (types.get(&a), types.get(&b)) {
+        (Some(a), Some(b)) => {
+            a.class.opcode == b.class.opcode
+                && a.operands.len() == b.operands.len()
+                && a.operands.iter().zip(&b.operands).all(|pair| match pair {
+                    (&Operand::IdRef(a), &Operand::IdRef(b)) => {
+                        same_type_modulo_storage_classes(types, a, b, depth + 1)
+                    }
+                    (Operand::StorageClass(_), Operand::StorageClass(_)) => true,
+                    (a, b) => a == b,
+                })
+        }
+        _ => false,
+    }
+}
@@ -5,6 +5,7 @@
 //! run mem2reg (see mem2reg.rs) on the result to "unwrap" the Function pointer.
 
 use super::apply_rewrite_rules;
+use super::duplicates::gather_names;
 use super::simple_passes::outgoing_edges;
 use rspirv::dr::{Block, Function, Instruction, Module, ModuleHeader, Operand};
 use rspirv::spirv::{FunctionControl, Op, StorageClass, Word};
@@ -38,6 +39,16 @@ pub fn inline(module: &mut Module) {
             true
         }
     });
+    // Names of the callees' locals, to be copied onto their clones in the callers.
+    let debug_names = gather_names(&module.debugs);
+    // Same for decorations (e.g. `NonUniform`, see `non_uniform.rs`).
+    let mut decorations = FxHashMap::<Word, Vec<Instruction>>::default();
+    for inst in &module.annotations {
+        if inst.class.opcode == Op::Decorate {
+            let target = inst.operands[0].unwrap_id_ref();
+            decorations.entry(target).or_default().push(inst.clone());
+        }
+    }
     // Drop OpName etc. for inlined functions
     module.debugs.retain(|inst| {
         !inst.operands.iter().any(|op| {
@@ -51,11 +62,19 @@ pub fn inline(module: &mut Module) {
         void,
         functions: &functions,
         disallowed_argument_types: &disallowed_argument_types,
+        debug_names: &debug_names,
+        inlined_debug_names: Vec::new(),
+        decorations: &decorations,
+        inlined_decorations: Vec::new(),
     };
     for function in &mut module.functions {
         inliner.inline_fn(function);
         fuse_trivial_branches(function);
     }
+    let inlined_debug_names = inliner.inlined_debug_names;
+    let inlined_decorations = inliner.inlined_decorations;
+    module.debugs.extend(inlined_debug_names);
+    module.annotations.extend(inlined_decorations);
 }
 
 fn compute_disallowed_argument_types(module: &Module) -> FxHashSet<Word> {
@@ -137,6 +156,12 @@ struct Inliner<'m, 'map> {
     void: Word,
     functions: &'map FunctionMap,
     disallowed_argument_types: &'map FxHashSet<Word>,
+    debug_names: &'map FxHashMap<Word, String>,
+    /// `OpName`s for the cloned IDs of named callee instructions.
+    inlined_debug_names: Vec<Instruction>,
+    decorations: &'map FxHashMap<Word, Vec<Instruction>>,
+    /// `OpDecorate`s for the cloned IDs of decorated callee instructions.
+    inlined_decorations: Vec<Instruction>,
     // rewrite_rules: FxHashMap<Word, Word>,
 }
 
@@ -244,6 +269,13 @@ impl Inliner<'_, '_> {
         // pop off OpFunctionCall
         let call = caller.blocks[block_idx].instructions.pop().unwrap();
         assert!(call.class.opcode == Op::FunctionCall);
+        // The source location the call was made from, to restore after the inlined body.
+        let call_line = caller.blocks[block_idx]
+            .instructions
+            .iter()
+            .rev()
+            .find(|inst| inst.class.opcode == Op::Line)
+            .cloned();
 
         if let Some(call_result_type) = call_result_type {
             // Generate the storage space for the return value: Do this *after* the split above,
@@ -258,7 +290,6 @@ impl Inliner<'_, '_> {
         // Fuse the first block of the callee into the block of the caller. This is okay because
         // it's illegal to branch to the first BB in a function.
         let mut callee_header = inlined_blocks.remove(0).instructions;
-        // TODO: OpLine handling
         let num_variables = callee_header
             .iter()
             .position(|inst| inst.class.opcode != Op::Variable)
@@ -282,6 +313,11 @@ impl Inliner<'_, '_> {
                 ),
             );
         }
+        if let Some(call_line) = call_line {
+            // Splitting the block ends the scope of the caller's `OpLine`, so re-apply it to the
+            // second half (the inlined blocks in between have their own `OpLine`s).
+            post_call_block_insts.insert(0, call_line);
+        }
         // Insert the second half of the split block.
         let continue_block = Block {
             label: Some(Instruction::new(Op::Label, None, Some(return_jump), vec![])),
@@ -305,6 +341,19 @@ impl Inliner<'_, '_> {
                     let new_id = self.id();
                     let old = rewrite_rules.insert(result_id, new_id);
                     assert!(old.is_none());
+                    if let Some(name) = self.debug_names.get(&result_id) {
+                        self.inlined_debug_names.push(Instruction::new(
+                            Op::Name,
+                            None,
+                            None,
+                            vec![Operand::IdRef(new_id), Operand::LiteralString(name.clone())],
+                        ));
+                    }
+                    for decoration in self.decorations.get(&result_id).into_iter().flatten() {
+                        let mut decoration = decoration.clone();
+                        decoration.operands[0] = Operand::IdRef(new_id);
+                        self.inlined_decorations.push(decoration);
+                    }
                 }
             }
         }
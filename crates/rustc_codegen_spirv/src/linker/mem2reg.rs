@@ -19,6 +19,8 @@ use std::collections::hash_map;
 pub fn mem2reg(
     header: &mut ModuleHeader,
     types_global_values: &mut Vec<Instruction>,
+    debugs: &mut Vec<Instruction>,
+    debug_names: &FxHashMap<Word, String>,
     pointer_to_pointee: &FxHashMap<Word, Word>,
     constants: &FxHashMap<Word, u32>,
     func: &mut Function,
@@ -30,6 +32,8 @@ pub fn mem2reg(
     insert_phis_all(
         header,
         types_global_values,
+        debugs,
+        debug_names,
         pointer_to_pointee,
         constants,
         &mut func.blocks,
@@ -146,9 +150,12 @@ fn compute_dominance_frontier(
     dominance_frontier
 }
 
+#[allow(clippy::too_many_arguments)]
 fn insert_phis_all(
     header: &mut ModuleHeader,
     types_global_values: &mut Vec<Instruction>,
+    debugs: &mut Vec<Instruction>,
+    debug_names: &FxHashMap<Word, String>,
     pointer_to_pointee: &FxHashMap<Word, Word>,
     constants: &FxHashMap<Word, u32>,
     blocks: &mut [Block],
@@ -182,6 +189,19 @@ fn insert_phis_all(
             rewrite_rules: FxHashMap::default(),
         };
         renamer.rename(0, None);
+        // The variable is going away, so its name (if any) moves to the phis that replace it.
+        if let Some(name) = var_map.keys().find_map(|var| debug_names.get(var)) {
+            let mut phi_defs = renamer.phi_defs.iter().copied().collect::<Vec<_>>();
+            phi_defs.sort_unstable();
+            debugs.extend(phi_defs.into_iter().map(|phi| {
+                Instruction::new(
+                    Op::Name,
+                    None,
+                    None,
+                    vec![Operand::IdRef(phi), Operand::LiteralString(name.clone())],
+                )
+            }));
+        }
         apply_rewrite_rules(&renamer.rewrite_rules, blocks);
         remove_nops(blocks);
     }
@@ -1,13 +1,16 @@
 #[cfg(test)]
 mod test;
 
+mod atomic_scope;
 mod capability_computation;
 mod dce;
 mod duplicates;
+mod fn_pointers;
 mod import_export_link;
 mod inline;
 mod mem2reg;
 mod new_structurizer;
+mod non_uniform;
 mod simple_passes;
 mod specializer;
 mod structurizer;
@@ -16,10 +19,11 @@ mod zombies;
 use crate::decorations::{CustomDecoration, UnrollLoopsDecoration};
 use rspirv::binary::Consumer;
 use rspirv::dr::{Block, Instruction, Loader, Module, ModuleHeader, Operand};
-use rspirv::spirv::{Op, StorageClass, Word};
-use rustc_data_structures::fx::FxHashMap;
+use rspirv::spirv::{Capability, Op, StorageClass, Word};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_errors::ErrorReported;
 use rustc_session::Session;
+use std::path::{Path, PathBuf};
 
 pub type Result<T> = std::result::Result<T, ErrorReported>;
 
@@ -31,6 +35,35 @@ pub struct Options {
     pub structurize: bool,
     pub use_new_structurizer: bool,
     pub emit_multiple_modules: bool,
+    pub dump_post_merge: Option<PathBuf>,
+    pub dump_post_split: Option<PathBuf>,
+    /// Capabilities declared with `+cap:...` target features, which are then the only ones
+    /// (along with the ones the compiler always declares) the output may use. If empty, any
+    /// capability may be used.
+    pub declared_capabilities: FxHashSet<Capability>,
+}
+
+/// Writes `spv_binary` to `path`, for the `--dump-*` options, reporting (rather than
+/// panicking on) I/O errors.
+pub fn write_dump(sess: &Session, path: &Path, spv_binary: &[u32]) -> Result<()> {
+    std::fs::write(path, spirv_tools::binary::from_binary(spv_binary)).map_err(|e| {
+        sess.err(&format!("failed to write {}: {}", path.display(), e));
+        ErrorReported
+    })
+}
+
+/// The file a `--dump-*` option writes the module for `entry_name` to, when emitting one
+/// module per entry point: `path` with the entry point name added before the extension, so
+/// that the modules don't overwrite each other.
+pub fn dump_path_for_entry(path: &Path, entry_name: &str) -> PathBuf {
+    let mut file_name = path.file_stem().unwrap_or_default().to_owned();
+    file_name.push(".");
+    file_name.push(sanitize_filename::sanitize(entry_name));
+    if let Some(extension) = path.extension() {
+        file_name.push(".");
+        file_name.push(extension);
+    }
+    path.with_file_name(file_name)
 }
 
 pub enum LinkResult {
@@ -111,15 +144,10 @@ pub fn link(sess: &Session, mut inputs: Vec<Module>, opts: &Options) -> Result<L
         output
     };
 
-    if let Ok(ref path) = std::env::var("DUMP_POST_MERGE") {
+    if let Some(path) = &opts.dump_post_merge {
         use rspirv::binary::Assemble;
-        use std::fs::File;
-        use std::io::Write;
 
-        File::create(path)
-            .unwrap()
-            .write_all(spirv_tools::binary::from_binary(&output.assemble()))
-            .unwrap();
+        write_dump(sess, path, &output.assemble())?;
     }
 
     // remove duplicates (https://github.com/KhronosGroup/SPIRV-Tools/blob/e7866de4b1dc2a7e8672867caeb0bdca49f458d3/source/opt/remove_duplicates_pass.cpp)
@@ -143,6 +171,16 @@ pub fn link(sess: &Session, mut inputs: Vec<Module>, opts: &Options) -> Result<L
         zombies::remove_zombies(sess, &mut output);
     }
 
+    {
+        let _timer = sess.timer("link_lower_fn_pointers");
+        fn_pointers::lower_fn_pointers(&mut output);
+    }
+
+    {
+        let _timer = sess.timer("link_apply_atomic_scopes");
+        atomic_scope::apply_atomic_scopes(sess, &mut output)?;
+    }
+
     {
         let _timer = sess.timer("specialize_generic_storage_class");
         // HACK(eddyb) `specializer` requires functions' blocks to be in RPO order
@@ -173,6 +211,11 @@ pub fn link(sess: &Session, mut inputs: Vec<Module>, opts: &Options) -> Result<L
         );
     }
 
+    {
+        let _timer = sess.timer("link_remove_private_memory_access_bits");
+        simple_passes::remove_private_memory_access_bits(&mut output);
+    }
+
     if opts.inline {
         let _timer = sess.timer("link_inline");
         inline::inline(&mut output);
@@ -226,6 +269,11 @@ pub fn link(sess: &Session, mut inputs: Vec<Module>, opts: &Options) -> Result<L
                 }
             }
         }
+        let debug_names = if opts.mem2reg {
+            duplicates::gather_names(&output.debugs)
+        } else {
+            FxHashMap::default()
+        };
         for func in &mut output.functions {
             simple_passes::block_ordering_pass(func);
             if opts.mem2reg {
@@ -233,6 +281,8 @@ pub fn link(sess: &Session, mut inputs: Vec<Module>, opts: &Options) -> Result<L
                 mem2reg::mem2reg(
                     output.header.as_mut().unwrap(),
                     &mut output.types_global_values,
+                    &mut output.debugs,
+                    &debug_names,
                     &pointer_to_pointee,
                     &constants,
                     func,
@@ -242,6 +292,15 @@ pub fn link(sess: &Session, mut inputs: Vec<Module>, opts: &Options) -> Result<L
             }
         }
     }
+
+    {
+        let _timer = sess.timer("link_remove_erased_pointer_casts");
+        fn_pointers::remove_erased_pointer_casts(sess, &mut output)?;
+    }
+    {
+        let _timer = sess.timer("link_propagate_non_uniform");
+        non_uniform::propagate_non_uniform(&mut output);
+    }
     {
         let _timer = sess.timer("link_sort_globals");
         simple_passes::sort_globals(&mut output);
@@ -264,20 +323,21 @@ pub fn link(sess: &Session, mut inputs: Vec<Module>, opts: &Options) -> Result<L
         LinkResult::SingleModule(output)
     };
 
-    let output_module_iter: Box<dyn Iterator<Item = &mut Module>> = match output {
-        LinkResult::SingleModule(ref mut m) => Box::new(std::iter::once(m)),
-        LinkResult::MultipleModules(ref mut m) => Box::new(m.values_mut()),
+    let output_module_iter: Box<dyn Iterator<Item = (Option<&str>, &mut Module)>> = match output {
+        LinkResult::SingleModule(ref mut m) => Box::new(std::iter::once((None, m))),
+        LinkResult::MultipleModules(ref mut m) => {
+            Box::new(m.iter_mut().map(|(name, m)| (Some(&name[..]), m)))
+        }
     };
-    for output in output_module_iter {
-        if let Ok(ref path) = std::env::var("DUMP_POST_SPLIT") {
+    for (entry_name, output) in output_module_iter {
+        if let Some(path) = &opts.dump_post_split {
             use rspirv::binary::Assemble;
-            use std::fs::File;
-            use std::io::Write;
 
-            File::create(path)
-                .unwrap()
-                .write_all(spirv_tools::binary::from_binary(&output.assemble()))
-                .unwrap();
+            let path = match entry_name {
+                Some(entry_name) => dump_path_for_entry(path, entry_name),
+                None => path.clone(),
+            };
+            write_dump(sess, &path, &output.assemble())?;
         }
         // Run DCE again, even if emit_multiple_modules==false - the first DCE ran before
         // structurization and mem2reg (for perf reasons), and mem2reg may remove references to
@@ -290,6 +350,11 @@ pub fn link(sess: &Session, mut inputs: Vec<Module>, opts: &Options) -> Result<L
             let _timer = sess.timer("link_remove_extra_capabilities");
             capability_computation::remove_extra_capabilities(output);
             capability_computation::remove_extra_extensions(output);
+            capability_computation::check_declared_capabilities(
+                sess,
+                output,
+                &opts.declared_capabilities,
+            )?;
         }
 
         if opts.compact_ids {
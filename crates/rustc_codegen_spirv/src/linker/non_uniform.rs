@@ -0,0 +1,195 @@
+//! Propagates `NonUniform` decorations (added by `spirv_std::arch::non_uniform`) from the
+//! marked values to everything computed from them, and declares the capabilities they need.
+//!
+//! Vulkan requires the pointer (or the loaded image/sampler) used to access a resource through a
+//! non-uniform index to be decorated, not just the index, so after inlining and mem2reg, the
+//! decoration is copied onto index arithmetic, access chains, and loads through them.
+
+use rspirv::dr::{Instruction, Module, Operand};
+use rspirv::spirv::{Capability, Decoration, Dim, Op, StorageClass, Word};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+
+pub fn propagate_non_uniform(module: &mut Module) {
+    let decorated: FxHashSet<Word> = module
+        .annotations
+        .iter()
+        .filter(|inst| {
+            inst.class.opcode == Op::Decorate
+                && inst.operands[1].unwrap_decoration() == Decoration::NonUniform
+        })
+        .map(|inst| inst.operands[0].unwrap_id_ref())
+        .collect();
+    if decorated.is_empty() {
+        return;
+    }
+
+    // Iterate until nothing changes, as `OpPhi`s can use values defined later (in loops).
+    let mut non_uniform = decorated.clone();
+    loop {
+        let mut changed = false;
+        for inst in module
+            .functions
+            .iter()
+            .flat_map(|func| func.all_inst_iter())
+        {
+            let result_id = match inst.result_id {
+                Some(result_id) if propagates(inst.class.opcode) => result_id,
+                _ => continue,
+            };
+            if !non_uniform.contains(&result_id)
+                && inst
+                    .operands
+                    .iter()
+                    .any(|op| matches!(op, Operand::IdRef(id) if non_uniform.contains(id)))
+            {
+                non_uniform.insert(result_id);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut newly_decorated: Vec<Word> = non_uniform.difference(&decorated).copied().collect();
+    newly_decorated.sort_unstable();
+    module
+        .annotations
+        .extend(newly_decorated.into_iter().map(|id| {
+            Instruction::new(
+                Op::Decorate,
+                None,
+                None,
+                vec![
+                    Operand::IdRef(id),
+                    Operand::Decoration(Decoration::NonUniform),
+                ],
+            )
+        }));
+
+    // Indexing into descriptor arrays with non-uniform indices needs a capability per kind of
+    // descriptor.
+    let globals: FxHashMap<Word, &Instruction> = module
+        .types_global_values
+        .iter()
+        .filter_map(|inst| Some((inst.result_id?, inst)))
+        .collect();
+    let mut capabilities = vec![Capability::ShaderNonUniform];
+    for inst in module
+        .functions
+        .iter()
+        .flat_map(|func| func.all_inst_iter())
+    {
+        if !matches!(inst.class.opcode, Op::AccessChain | Op::InBoundsAccessChain)
+            || inst.operands.len() < 2
+            || !non_uniform.contains(&inst.operands[1].unwrap_id_ref())
+        {
+            continue;
+        }
+        let capability = globals
+            .get(&inst.operands[0].unwrap_id_ref())
+            .filter(|var| var.class.opcode == Op::Variable)
+            .and_then(|var| {
+                let storage_class = var.operands[0].unwrap_storage_class();
+                let ptr_ty = globals.get(&var.result_type?)?;
+                let array_ty = globals.get(&ptr_ty.operands[1].unwrap_id_ref())?;
+                if !matches!(array_ty.class.opcode, Op::TypeArray | Op::TypeRuntimeArray) {
+                    return None;
+                }
+                let element_ty = globals.get(&array_ty.operands[0].unwrap_id_ref())?;
+                descriptor_array_indexing_capability(storage_class, element_ty)
+            });
+        capabilities.extend(capability);
+    }
+
+    for capability in capabilities {
+        let declared = module
+            .capabilities
+            .iter()
+            .any(|inst| inst.operands[0].unwrap_capability() == capability);
+        if !declared {
+            module.capabilities.push(Instruction::new(
+                Op::Capability,
+                None,
+                None,
+                vec![Operand::Capability(capability)],
+            ));
+        }
+    }
+
+    // All of the above are core in SPIR-V 1.5.
+    if module.header.as_ref().unwrap().version() < (1, 5) {
+        let extension = "SPV_EXT_descriptor_indexing";
+        let declared = module
+            .extensions
+            .iter()
+            .any(|inst| inst.operands[0].unwrap_literal_string() == extension);
+        if !declared {
+            module.extensions.push(Instruction::new(
+                Op::Extension,
+                None,
+                None,
+                vec![Operand::LiteralString(extension.to_string())],
+            ));
+        }
+    }
+}
+
+/// Whether the result of an instruction with this opcode is non-uniform if any of its operands
+/// is: index arithmetic, and accesses to resources.
+fn propagates(opcode: Op) -> bool {
+    matches!(
+        opcode,
+        Op::CopyObject
+            | Op::Bitcast
+            | Op::UConvert
+            | Op::SConvert
+            | Op::IAdd
+            | Op::ISub
+            | Op::IMul
+            | Op::UDiv
+            | Op::SDiv
+            | Op::UMod
+            | Op::SMod
+            | Op::SRem
+            | Op::ShiftLeftLogical
+            | Op::ShiftRightLogical
+            | Op::ShiftRightArithmetic
+            | Op::BitwiseAnd
+            | Op::BitwiseOr
+            | Op::BitwiseXor
+            | Op::Phi
+            | Op::Select
+            | Op::AccessChain
+            | Op::InBoundsAccessChain
+            | Op::PtrAccessChain
+            | Op::InBoundsPtrAccessChain
+            | Op::Load
+            | Op::SampledImage
+            | Op::Image
+    )
+}
+
+fn descriptor_array_indexing_capability(
+    storage_class: StorageClass,
+    element_ty: &Instruction,
+) -> Option<Capability> {
+    match (storage_class, element_ty.class.opcode) {
+        (StorageClass::Uniform, _) => Some(Capability::UniformBufferArrayNonUniformIndexing),
+        (StorageClass::StorageBuffer, _) => Some(Capability::StorageBufferArrayNonUniformIndexing),
+        (StorageClass::UniformConstant, Op::TypeImage) => {
+            let storage = element_ty.operands[5].unwrap_literal_int32() == 2;
+            Some(match element_ty.operands[1].unwrap_dim() {
+                Dim::DimBuffer if storage => Capability::StorageTexelBufferArrayNonUniformIndexing,
+                Dim::DimBuffer => Capability::UniformTexelBufferArrayNonUniformIndexing,
+                Dim::DimSubpassData => Capability::InputAttachmentArrayNonUniformIndexing,
+                _ if storage => Capability::StorageImageArrayNonUniformIndexing,
+                _ => Capability::SampledImageArrayNonUniformIndexing,
+            })
+        }
+        (StorageClass::UniformConstant, Op::TypeSampler | Op::TypeSampledImage) => {
+            Some(Capability::SampledImageArrayNonUniformIndexing)
+        }
+        _ => None,
+    }
+}
@@ -1,5 +1,5 @@
-use rspirv::dr::{Block, Function, Module};
-use rspirv::spirv::{Op, Word};
+use rspirv::dr::{Block, Function, Module, Operand};
+use rspirv::spirv::{MemoryAccess, Op, StorageClass, Word};
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use std::mem::replace;
 
@@ -123,4 +123,105 @@ pub fn sort_globals(module: &mut Module) {
     // Function declarations come before definitions. TODO: Figure out if it's even possible to
     // have a function declaration without a body in a fully linked module?
     module.functions.sort_by_key(|f| !f.blocks.is_empty());
+    // Merging modules interleaves their debug instructions, but `OpString`/`OpSource*` have to come
+    // before `OpName`/`OpMemberName`, which in turn come before `OpModuleProcessed`.
+    module.debugs.sort_by_key(|inst| match inst.class.opcode {
+        Op::String | Op::SourceExtension | Op::Source | Op::SourceContinued => 0,
+        Op::ModuleProcessed => 2,
+        _ => 1,
+    });
+}
+
+/// The builder marks volatile accesses as `NonPrivatePointer` (and makes them available/visible)
+/// under the Vulkan memory model, before storage classes are known. Those bits are only valid on
+/// pointers into memory shared with other invocations, so this removes them everywhere else.
+pub fn remove_private_memory_access_bits(module: &mut Module) {
+    let pointer_storage_classes: FxHashMap<Word, StorageClass> = module
+        .types_global_values
+        .iter()
+        .filter(|inst| inst.class.opcode == Op::TypePointer)
+        .map(|inst| {
+            (
+                inst.result_id.unwrap(),
+                inst.operands[0].unwrap_storage_class(),
+            )
+        })
+        .collect();
+    let value_types: FxHashMap<Word, Word> = module
+        .all_inst_iter()
+        .filter_map(|inst| Some((inst.result_id?, inst.result_type?)))
+        .collect();
+    let is_shared = |ptr: &Operand| {
+        let storage_class = value_types
+            .get(&ptr.unwrap_id_ref())
+            .and_then(|ty| pointer_storage_classes.get(ty));
+        matches!(
+            storage_class,
+            Some(StorageClass::Uniform)
+                | Some(StorageClass::Workgroup)
+                | Some(StorageClass::CrossWorkgroup)
+                | Some(StorageClass::Generic)
+                | Some(StorageClass::Image)
+                | Some(StorageClass::StorageBuffer)
+                | Some(StorageClass::PhysicalStorageBuffer)
+        )
+    };
+    let vulkan_bits = MemoryAccess::NON_PRIVATE_POINTER
+        | MemoryAccess::MAKE_POINTER_AVAILABLE
+        | MemoryAccess::MAKE_POINTER_VISIBLE;
+    for block in module.functions.iter_mut().flat_map(|f| &mut f.blocks) {
+        for inst in &mut block.instructions {
+            // Index of the first memory operands mask, and the number of pointer operands.
+            let (mask_index, pointers) = match inst.class.opcode {
+                Op::Load => (1, 1),
+                Op::Store => (2, 1),
+                Op::CopyMemory => (2, 2),
+                Op::CopyMemorySized => (3, 2),
+                _ => continue,
+            };
+            // Each mask is followed by its extra operands, ordered by bit: `Aligned`'s literal,
+            // then the `MakePointerAvailable` scope, then the `MakePointerVisible` scope.
+            let mut masks = Vec::new();
+            let mut index = mask_index;
+            while let Some(&Operand::MemoryAccess(access)) = inst.operands.get(index) {
+                let extra = access.contains(MemoryAccess::ALIGNED) as usize
+                    + access.contains(MemoryAccess::MAKE_POINTER_AVAILABLE) as usize
+                    + access.contains(MemoryAccess::MAKE_POINTER_VISIBLE) as usize;
+                masks.push((access, inst.operands[index + 1..index + 1 + extra].to_vec()));
+                index += 1 + extra;
+            }
+            if !masks
+                .iter()
+                .any(|(access, _)| access.intersects(vulkan_bits))
+            {
+                continue;
+            }
+            // A copy with two masks has one for the target and one for the source, otherwise
+            // the only mask applies to every pointer.
+            let two_masks = masks.len() == 2;
+            let mut operands = inst.operands[..mask_index].to_vec();
+            for (i, (access, extra)) in masks.into_iter().enumerate() {
+                let shared = if two_masks {
+                    is_shared(&inst.operands[i])
+                } else {
+                    inst.operands[..pointers].iter().all(is_shared)
+                };
+                if shared || !access.intersects(vulkan_bits) {
+                    operands.push(Operand::MemoryAccess(access));
+                    operands.extend(extra);
+                } else {
+                    let aligned = access.contains(MemoryAccess::ALIGNED) as usize;
+                    operands.push(Operand::MemoryAccess(access - vulkan_bits));
+                    operands.extend(extra.into_iter().take(aligned));
+                }
+            }
+            // An empty trailing mask can be left out (but not one followed by the source's).
+            while operands.len() > mask_index
+                && operands.last() == Some(&Operand::MemoryAccess(MemoryAccess::NONE))
+            {
+                operands.pop();
+            }
+            inst.operands = operands;
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use super::{link, LinkResult, Options};
+use super::{dump_path_for_entry, link, LinkResult, Options};
 use pipe::pipe;
 use rspirv::dr::{Loader, Module};
 use rustc_driver::handle_options;
@@ -96,6 +96,9 @@ fn assemble_and_link(binaries: &[&[u8]]) -> Result<Module, String> {
                 structurize: false,
                 use_new_structurizer: false,
                 emit_multiple_modules: false,
+                dump_post_merge: None,
+                dump_post_split: None,
+                declared_capabilities: Default::default(),
             },
         );
         assert_eq!(compiler.session().has_errors(), res.is_err());
@@ -490,3 +493,15 @@ fn names_and_decorations() {
 
     without_header_eq(result, expect);
 }
+
+#[test]
+fn dump_path_per_entry() {
+    assert_eq!(
+        dump_path_for_entry(&PathBuf::from("dumps/post-split.spv"), "main_fs"),
+        PathBuf::from("dumps/post-split.main_fs.spv")
+    );
+    assert_eq!(
+        dump_path_for_entry(&PathBuf::from("post-link"), "main_vs"),
+        PathBuf::from("post-link.main_vs")
+    );
+}
@@ -0,0 +1,586 @@
+//! Reflection information about a linked module, written as JSON next to the `.spv` file, so that
+//! users of the module (e.g. through `spirv-builder`) don't have to rediscover the entry points
+//! and their bindings with a separate reflection library.
+//!
+//! NOTE: the types here are mirrored (as `Deserialize`) in `spirv-builder/src/reflect.rs`, and the
+//! two must be kept in sync.
+
+use rspirv::dr::{Instruction, Module, Operand};
+use rspirv::spirv::{Decoration, Dim, Op, StorageClass, Word};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+pub struct ModuleReflection {
+    pub entry_points: Vec<EntryPoint>,
+}
+
+#[derive(Serialize)]
+pub struct EntryPoint {
+    pub name: String,
+    pub execution_model: String,
+    pub execution_modes: Vec<ExecutionMode>,
+    pub inputs: Vec<InterfaceVariable>,
+    pub outputs: Vec<InterfaceVariable>,
+    pub descriptor_bindings: Vec<DescriptorBinding>,
+    pub push_constants: Option<PushConstantBlock>,
+}
+
+#[derive(Serialize)]
+pub struct ExecutionMode {
+    pub mode: String,
+    /// Literal operands, with `OpExecutionModeId` operands resolved to their (default) values.
+    pub operands: Vec<u32>,
+}
+
+#[derive(Serialize)]
+pub struct InterfaceVariable {
+    pub name: Option<String>,
+    pub location: Option<u32>,
+    pub builtin: Option<String>,
+    pub ty: Type,
+}
+
+#[derive(Serialize)]
+pub enum DescriptorType {
+    Sampler,
+    CombinedImageSampler,
+    SampledImage,
+    StorageImage,
+    UniformTexelBuffer,
+    StorageTexelBuffer,
+    UniformBuffer,
+    StorageBuffer,
+    InputAttachment,
+    AccelerationStructure,
+}
+
+#[derive(Serialize)]
+pub struct DescriptorBinding {
+    pub name: Option<String>,
+    pub set: u32,
+    pub binding: u32,
+    pub descriptor_type: Option<DescriptorType>,
+    /// `Some(1)` for a single descriptor, `None` for a runtime-sized (bindless) array.
+    pub array_size: Option<u32>,
+    pub ty: Type,
+}
+
+#[derive(Serialize)]
+pub struct PushConstantBlock {
+    pub name: Option<String>,
+    pub size: u32,
+    pub ty: Type,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Type {
+    Void,
+    Bool,
+    Int {
+        width: u32,
+        signed: bool,
+    },
+    Float {
+        width: u32,
+    },
+    Vector {
+        element: Box<Type>,
+        count: u32,
+    },
+    Matrix {
+        column: Box<Type>,
+        count: u32,
+    },
+    Array {
+        element: Box<Type>,
+        /// `None` for runtime arrays.
+        count: Option<u32>,
+        stride: Option<u32>,
+    },
+    Struct {
+        name: Option<String>,
+        members: Vec<StructMember>,
+    },
+    Pointer {
+        storage_class: String,
+        pointee: Box<Type>,
+    },
+    Image {
+        dim: String,
+        depth: u32,
+        arrayed: bool,
+        multisampled: bool,
+        sampled: u32,
+        format: String,
+    },
+    Sampler,
+    SampledImage {
+        image: Box<Type>,
+    },
+    AccelerationStructure,
+    RayQuery,
+    /// Any other type, or a type that recursively contains itself (through pointers).
+    Other {
+        opcode: String,
+    },
+}
+
+#[derive(Serialize)]
+pub struct StructMember {
+    pub name: Option<String>,
+    pub offset: Option<u32>,
+    pub ty: Type,
+}
+
+impl Type {
+    /// The size of this type in an explicitly laid out block, with runtime arrays counting as
+    /// zero-sized.
+    fn size(&self) -> u32 {
+        match self {
+            Type::Int { width, .. } | Type::Float { width } => width / 8,
+            Type::Vector { element, count } => element.size() * count,
+            Type::Matrix { column, count } => column.size() * count,
+            Type::Array {
+                element,
+                count,
+                stride,
+            } => stride.unwrap_or_else(|| element.size()) * count.unwrap_or(0),
+            Type::Struct { members, .. } => {
+                let mut size = 0;
+                for member in members {
+                    size = size.max(member.offset.unwrap_or(size) + member.ty.size());
+                }
+                size
+            }
+            // Physical pointers are the only pointers allowed in blocks.
+            Type::Pointer { .. } => 8,
+            _ => 0,
+        }
+    }
+}
+
+/// The reflection file for a module is written next to it, with `.json` appended to its name
+/// (`None` if `spv_path` doesn't end in a file name).
+pub fn reflection_path(spv_path: &Path) -> Option<PathBuf> {
+    let mut file_name = spv_path.file_name()?.to_owned();
+    file_name.push(".json");
+    Some(spv_path.with_file_name(file_name))
+}
+
+pub fn reflect(module: &Module) -> Result<ModuleReflection, String> {
+    let cx = ReflectCx::new(module);
+    let entry_points = module
+        .entry_points
+        .iter()
+        .map(|entry| cx.reflect_entry_point(entry))
+        .collect::<Result<_, _>>()?;
+    Ok(ModuleReflection { entry_points })
+}
+
+struct ReflectCx<'a> {
+    module: &'a Module,
+    defs: FxHashMap<Word, &'a Instruction>,
+    names: FxHashMap<Word, &'a str>,
+    member_names: FxHashMap<(Word, u32), &'a str>,
+    decorations: FxHashMap<Word, Vec<&'a Instruction>>,
+    member_decorations: FxHashMap<(Word, u32), Vec<&'a Instruction>>,
+}
+
+impl<'a> ReflectCx<'a> {
+    fn new(module: &'a Module) -> Self {
+        let defs = module
+            .types_global_values
+            .iter()
+            .filter_map(|inst| Some((inst.result_id?, inst)))
+            .collect();
+        let mut names = FxHashMap::default();
+        let mut member_names = FxHashMap::default();
+        for inst in &module.debugs {
+            match inst.class.opcode {
+                Op::Name => {
+                    names.insert(
+                        inst.operands[0].unwrap_id_ref(),
+                        inst.operands[1].unwrap_literal_string(),
+                    );
+                }
+                Op::MemberName => {
+                    member_names.insert(
+                        (
+                            inst.operands[0].unwrap_id_ref(),
+                            inst.operands[1].unwrap_literal_int32(),
+                        ),
+                        inst.operands[2].unwrap_literal_string(),
+                    );
+                }
+                _ => {}
+            }
+        }
+        let mut decorations = FxHashMap::<_, Vec<_>>::default();
+        let mut member_decorations = FxHashMap::<_, Vec<_>>::default();
+        for inst in &module.annotations {
+            match inst.class.opcode {
+                Op::Decorate => decorations
+                    .entry(inst.operands[0].unwrap_id_ref())
+                    .or_default()
+                    .push(inst),
+                Op::MemberDecorate => member_decorations
+                    .entry((
+                        inst.operands[0].unwrap_id_ref(),
+                        inst.operands[1].unwrap_literal_int32(),
+                    ))
+                    .or_default()
+                    .push(inst),
+                _ => {}
+            }
+        }
+        Self {
+            module,
+            defs,
+            names,
+            member_names,
+            decorations,
+            member_decorations,
+        }
+    }
+
+    /// Returns the operands following `decoration`, if `id` is decorated with it.
+    fn decoration(&self, id: Word, decoration: Decoration) -> Option<&'a [Operand]> {
+        self.decorations.get(&id)?.iter().find_map(|inst| {
+            if inst.operands[1].unwrap_decoration() == decoration {
+                Some(&inst.operands[2..])
+            } else {
+                None
+            }
+        })
+    }
+
+    fn decoration_u32(&self, id: Word, decoration: Decoration) -> Option<u32> {
+        Some(self.decoration(id, decoration)?[0].unwrap_literal_int32())
+    }
+
+    fn member_decoration_u32(&self, id: Word, member: u32, decoration: Decoration) -> Option<u32> {
+        self.member_decorations
+            .get(&(id, member))?
+            .iter()
+            .find_map(|inst| {
+                if inst.operands[2].unwrap_decoration() == decoration {
+                    Some(inst.operands[3].unwrap_literal_int32())
+                } else {
+                    None
+                }
+            })
+    }
+
+    fn name(&self, id: Word) -> Option<String> {
+        self.names.get(&id).map(|&name| name.to_string())
+    }
+
+    /// The value of an integer (spec) constant, using the default for spec constants.
+    fn constant_u32(&self, id: Word) -> Option<u32> {
+        let inst = self.defs.get(&id)?;
+        match inst.class.opcode {
+            Op::Constant | Op::SpecConstant => match inst.operands[0] {
+                Operand::LiteralInt32(value) => Some(value),
+                Operand::LiteralInt64(value) => Some(value as u32),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn reflect_entry_point(&self, entry: &Instruction) -> Result<EntryPoint, String> {
+        let execution_model = match entry.operands[0] {
+            Operand::ExecutionModel(model) => model,
+            ref other => return Err(format!("invalid OpEntryPoint execution model {:?}", other)),
+        };
+        let entry_id = entry.operands[1].unwrap_id_ref();
+        let name = entry.operands[2].unwrap_literal_string().to_string();
+
+        let execution_modes = self
+            .module
+            .execution_modes
+            .iter()
+            .filter(|inst| inst.operands[0].unwrap_id_ref() == entry_id)
+            .map(|inst| ExecutionMode {
+                mode: match inst.operands[1] {
+                    Operand::ExecutionMode(mode) => format!("{:?}", mode),
+                    ref other => format!("{:?}", other),
+                },
+                operands: inst.operands[2..]
+                    .iter()
+                    .filter_map(|operand| match *operand {
+                        Operand::LiteralInt32(value) => Some(value),
+                        Operand::IdRef(id) => self.constant_u32(id),
+                        _ => None,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        // Before SPIR-V 1.4, only `Input`/`Output` variables are listed in `OpEntryPoint`, so the
+        // other global variables the entry point uses have to be found by walking its call graph.
+        let mut used = self.reachable_ids(entry_id);
+        used.extend(entry.operands[3..].iter().map(|op| op.unwrap_id_ref()));
+
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut descriptor_bindings = Vec::new();
+        let mut push_constants = None;
+        for global in &self.module.types_global_values {
+            if global.class.opcode != Op::Variable {
+                continue;
+            }
+            let id = global.result_id.unwrap();
+            if !used.contains(&id) {
+                continue;
+            }
+            let storage_class = global.operands[0].unwrap_storage_class();
+            // Skip variables whose type can't be followed (e.g. a pointer to a type that's
+            // only forward-declared), rather than failing the whole reflection.
+            let pointee = match self.pointee(global.result_type.unwrap()) {
+                Some(pointee) => pointee,
+                None => continue,
+            };
+            match storage_class {
+                StorageClass::Input | StorageClass::Output => {
+                    let var = InterfaceVariable {
+                        name: self.name(id),
+                        location: self.decoration_u32(id, Decoration::Location),
+                        builtin: self
+                            .decoration(id, Decoration::BuiltIn)
+                            .and_then(|operands| match operands[0] {
+                                Operand::BuiltIn(builtin) => Some(format!("{:?}", builtin)),
+                                _ => None,
+                            }),
+                        ty: self.reflect_type(pointee, &mut FxHashSet::default()),
+                    };
+                    if storage_class == StorageClass::Input {
+                        inputs.push(var);
+                    } else {
+                        outputs.push(var);
+                    }
+                }
+                StorageClass::PushConstant => {
+                    let ty = self.reflect_type(pointee, &mut FxHashSet::default());
+                    push_constants = Some(PushConstantBlock {
+                        name: self.name(id),
+                        size: ty.size(),
+                        ty,
+                    });
+                }
+                _ => {
+                    if let (Some(set), Some(binding)) = (
+                        self.decoration_u32(id, Decoration::DescriptorSet),
+                        self.decoration_u32(id, Decoration::Binding),
+                    ) {
+                        let (element, array_size) = self.descriptor_array(pointee);
+                        descriptor_bindings.push(DescriptorBinding {
+                            name: self.name(id),
+                            set,
+                            binding,
+                            descriptor_type: self.descriptor_type(storage_class, element),
+                            array_size,
+                            ty: self.reflect_type(pointee, &mut FxHashSet::default()),
+                        });
+                    }
+                }
+            }
+        }
+        descriptor_bindings.sort_by_key(|binding| (binding.set, binding.binding));
+
+        Ok(EntryPoint {
+            name,
+            execution_model: format!("{:?}", execution_model),
+            execution_modes,
+            inputs,
+            outputs,
+            descriptor_bindings,
+            push_constants,
+        })
+    }
+
+    /// All ids referenced from `entry` or any function it (transitively) references.
+    fn reachable_ids(&self, entry: Word) -> FxHashSet<Word> {
+        let functions: FxHashMap<Word, _> = self
+            .module
+            .functions
+            .iter()
+            .map(|func| (func.def_id().unwrap(), func))
+            .collect();
+        let mut used = FxHashSet::default();
+        let mut visited = FxHashSet::default();
+        let mut stack = vec![entry];
+        while let Some(func_id) = stack.pop() {
+            if !visited.insert(func_id) {
+                continue;
+            }
+            let func = match functions.get(&func_id) {
+                Some(func) => func,
+                None => continue,
+            };
+            for inst in func.all_inst_iter() {
+                for operand in &inst.operands {
+                    if let Operand::IdRef(id) = *operand {
+                        if functions.contains_key(&id) {
+                            stack.push(id);
+                        } else {
+                            used.insert(id);
+                        }
+                    }
+                }
+            }
+        }
+        used
+    }
+
+    /// The type `pointer_ty` points to, if it's an `OpTypePointer` to a defined type.
+    fn pointee(&self, pointer_ty: Word) -> Option<Word> {
+        let inst = self.defs.get(&pointer_ty)?;
+        if inst.class.opcode != Op::TypePointer {
+            return None;
+        }
+        let pointee = inst.operands[1].unwrap_id_ref();
+        if self.defs.contains_key(&pointee) {
+            Some(pointee)
+        } else {
+            None
+        }
+    }
+
+    /// Splits an array of descriptors into its element type and size.
+    fn descriptor_array(&self, ty: Word) -> (Word, Option<u32>) {
+        let inst = match self.defs.get(&ty) {
+            Some(inst) => inst,
+            None => return (ty, Some(1)),
+        };
+        match inst.class.opcode {
+            Op::TypeArray => (
+                inst.operands[0].unwrap_id_ref(),
+                self.constant_u32(inst.operands[1].unwrap_id_ref()),
+            ),
+            Op::TypeRuntimeArray => (inst.operands[0].unwrap_id_ref(), None),
+            _ => (ty, Some(1)),
+        }
+    }
+
+    fn descriptor_type(&self, storage_class: StorageClass, ty: Word) -> Option<DescriptorType> {
+        let inst = self.defs.get(&ty)?;
+        Some(match (storage_class, inst.class.opcode) {
+            (StorageClass::UniformConstant, Op::TypeSampler) => DescriptorType::Sampler,
+            (StorageClass::UniformConstant, Op::TypeSampledImage) => {
+                DescriptorType::CombinedImageSampler
+            }
+            (StorageClass::UniformConstant, Op::TypeImage) => {
+                let dim = inst.operands[1].unwrap_dim();
+                let sampled = inst.operands[5].unwrap_literal_int32();
+                match (dim, sampled) {
+                    (Dim::DimSubpassData, _) => DescriptorType::InputAttachment,
+                    (Dim::DimBuffer, 2) => DescriptorType::StorageTexelBuffer,
+                    (Dim::DimBuffer, _) => DescriptorType::UniformTexelBuffer,
+                    (_, 2) => DescriptorType::StorageImage,
+                    _ => DescriptorType::SampledImage,
+                }
+            }
+            (StorageClass::UniformConstant, Op::TypeAccelerationStructureKHR) => {
+                DescriptorType::AccelerationStructure
+            }
+            (StorageClass::StorageBuffer, _) => DescriptorType::StorageBuffer,
+            (StorageClass::Uniform, _)
+                if self.decoration(ty, Decoration::BufferBlock).is_some() =>
+            {
+                DescriptorType::StorageBuffer
+            }
+            (StorageClass::Uniform, _) => DescriptorType::UniformBuffer,
+            _ => return None,
+        })
+    }
+
+    fn reflect_type(&self, ty: Word, visiting: &mut FxHashSet<Word>) -> Type {
+        let inst = match self.defs.get(&ty) {
+            Some(inst) => inst,
+            // e.g. a type that's only forward-declared.
+            None => {
+                return Type::Other {
+                    opcode: "Undefined".to_string(),
+                }
+            }
+        };
+        let other = || Type::Other {
+            opcode: format!("{:?}", inst.class.opcode),
+        };
+        if !visiting.insert(ty) {
+            return other();
+        }
+        let result = match inst.class.opcode {
+            Op::TypeVoid => Type::Void,
+            Op::TypeBool => Type::Bool,
+            Op::TypeInt => Type::Int {
+                width: inst.operands[0].unwrap_literal_int32(),
+                signed: inst.operands[1].unwrap_literal_int32() != 0,
+            },
+            Op::TypeFloat => Type::Float {
+                width: inst.operands[0].unwrap_literal_int32(),
+            },
+            Op::TypeVector => Type::Vector {
+                element: Box::new(self.reflect_type(inst.operands[0].unwrap_id_ref(), visiting)),
+                count: inst.operands[1].unwrap_literal_int32(),
+            },
+            Op::TypeMatrix => Type::Matrix {
+                column: Box::new(self.reflect_type(inst.operands[0].unwrap_id_ref(), visiting)),
+                count: inst.operands[1].unwrap_literal_int32(),
+            },
+            Op::TypeArray | Op::TypeRuntimeArray => Type::Array {
+                element: Box::new(self.reflect_type(inst.operands[0].unwrap_id_ref(), visiting)),
+                count: if inst.class.opcode == Op::TypeArray {
+                    self.constant_u32(inst.operands[1].unwrap_id_ref())
+                } else {
+                    None
+                },
+                stride: self.decoration_u32(ty, Decoration::ArrayStride),
+            },
+            Op::TypeStruct => Type::Struct {
+                name: self.name(ty),
+                members: inst
+                    .operands
+                    .iter()
+                    .enumerate()
+                    .map(|(index, member)| {
+                        let index = index as u32;
+                        StructMember {
+                            name: self
+                                .member_names
+                                .get(&(ty, index))
+                                .map(|&name| name.to_string()),
+                            offset: self.member_decoration_u32(ty, index, Decoration::Offset),
+                            ty: self.reflect_type(member.unwrap_id_ref(), visiting),
+                        }
+                    })
+                    .collect(),
+            },
+            Op::TypePointer => Type::Pointer {
+                storage_class: format!("{:?}", inst.operands[0].unwrap_storage_class()),
+                pointee: Box::new(self.reflect_type(inst.operands[1].unwrap_id_ref(), visiting)),
+            },
+            Op::TypeImage => Type::Image {
+                dim: format!("{:?}", inst.operands[1].unwrap_dim()),
+                depth: inst.operands[2].unwrap_literal_int32(),
+                arrayed: inst.operands[3].unwrap_literal_int32() != 0,
+                multisampled: inst.operands[4].unwrap_literal_int32() != 0,
+                sampled: inst.operands[5].unwrap_literal_int32(),
+                format: format!("{:?}", inst.operands[6].unwrap_image_format()),
+            },
+            Op::TypeSampler => Type::Sampler,
+            Op::TypeSampledImage => Type::SampledImage {
+                image: Box::new(self.reflect_type(inst.operands[0].unwrap_id_ref(), visiting)),
+            },
+            Op::TypeAccelerationStructureKHR => Type::AccelerationStructure,
+            Op::TypeRayQueryKHR => Type::RayQuery,
+            _ => other(),
+        };
+        visiting.remove(&ty);
+        result
+    }
+}
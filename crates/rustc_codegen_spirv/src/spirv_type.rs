@@ -79,6 +79,8 @@ pub enum SpirvType {
     SampledImage {
         image_type: Word,
     },
+    AccelerationStructureKhr,
+    RayQueryKhr,
 
     /// `OpTypeStruct` decorated with `Block`, required by Vulkan (and OpenGL)
     /// for `PushConstant`, `Uniform` and `StorageBuffer` interface variables.
@@ -87,6 +89,40 @@ pub enum SpirvType {
     },
 }
 
+fn is_interface_block(cx: &CodegenCx<'_>, ty: Word) -> bool {
+    matches!(cx.lookup_type(ty), SpirvType::InterfaceBlock { .. })
+}
+
+/// Some image dimensionalities (and multisampled/arrayed combinations) need
+/// additional capabilities just to declare an `OpTypeImage` with them.
+pub fn image_type_capabilities(
+    dim: Dim,
+    arrayed: u32,
+    multisampled: u32,
+    sampled: u32,
+) -> &'static [Capability] {
+    let storage = sampled == 2;
+    match dim {
+        Dim::Dim1D if storage => &[Capability::Image1D],
+        Dim::Dim1D => &[Capability::Sampled1D],
+        Dim::DimBuffer if storage => &[Capability::ImageBuffer],
+        Dim::DimBuffer => &[Capability::SampledBuffer],
+        Dim::DimCube if arrayed == 1 && storage => &[Capability::ImageCubeArray],
+        Dim::DimCube if arrayed == 1 => &[Capability::SampledCubeArray],
+        Dim::DimRect if storage => &[Capability::ImageRect],
+        Dim::DimRect => &[Capability::SampledRect],
+        Dim::DimSubpassData => &[Capability::InputAttachment],
+        // `ImageMSArray` only concerns storage images, sampled ones can always be
+        // multisampled and arrayed.
+        _ if multisampled == 1 && arrayed == 1 && storage => &[
+            Capability::StorageImageMultisample,
+            Capability::ImageMSArray,
+        ],
+        _ if multisampled == 1 && storage => &[Capability::StorageImageMultisample],
+        _ => &[],
+    }
+}
+
 impl SpirvType {
     /// Note: `Builder::type_*` should be called *nowhere else* but here, to ensure
     /// `CodegenCx::type_defs` stays up-to-date
@@ -177,7 +213,8 @@ impl SpirvType {
                     .expect("Element of sized array must be sized")
                     .bytes();
                 let result = cx.emit_global().type_array(element, count.def_cx(cx));
-                if !cx.kernel_mode {
+                // Arrays of blocks are only used for descriptor arrays, which have no layout.
+                if !cx.kernel_mode && !is_interface_block(cx, element) {
                     // TODO: kernel mode can't do this??
                     cx.emit_global().decorate(
                         result,
@@ -195,11 +232,13 @@ impl SpirvType {
                     .sizeof(cx)
                     .expect("Element of sized array must be sized")
                     .bytes();
-                cx.emit_global().decorate(
-                    result,
-                    Decoration::ArrayStride,
-                    iter::once(Operand::LiteralInt32(element_size as u32)),
-                );
+                if !is_interface_block(cx, element) {
+                    cx.emit_global().decorate(
+                        result,
+                        Decoration::ArrayStride,
+                        iter::once(Operand::LiteralInt32(element_size as u32)),
+                    );
+                }
                 if cx.kernel_mode {
                     cx.zombie_with_span(result, def_span, "RuntimeArray in kernel mode");
                 }
@@ -237,18 +276,33 @@ impl SpirvType {
                 sampled,
                 image_format,
                 access_qualifier,
-            } => cx.emit_global().type_image(
-                sampled_type,
-                dim,
-                depth,
-                arrayed,
-                multisampled,
-                sampled,
-                image_format,
-                access_qualifier,
-            ),
+            } => {
+                let mut emit = cx.emit_global();
+                for &capability in image_type_capabilities(dim, arrayed, multisampled, sampled) {
+                    emit.capability(capability);
+                }
+                emit.type_image(
+                    sampled_type,
+                    dim,
+                    depth,
+                    arrayed,
+                    multisampled,
+                    sampled,
+                    image_format,
+                    access_qualifier,
+                )
+            }
             Self::Sampler => cx.emit_global().type_sampler(),
             Self::SampledImage { image_type } => cx.emit_global().type_sampled_image(image_type),
+            Self::AccelerationStructureKhr => cx.emit_global().type_acceleration_structure_khr(),
+            Self::RayQueryKhr => {
+                // Only declare ray queries as used when a ray query type is
+                // actually defined, as they're unavailable on many devices.
+                let mut emit = cx.emit_global();
+                emit.extension("SPV_KHR_ray_query");
+                emit.capability(Capability::RayQueryKHR);
+                emit.type_ray_query_khr()
+            }
 
             Self::InterfaceBlock { inner_type } => {
                 let mut emit = cx.emit_global();
@@ -347,7 +401,11 @@ impl SpirvType {
                 cx.lookup_type(element).sizeof(cx)? * cx.builder.lookup_const_u64(count).unwrap()
             }
             Self::Pointer { .. } => cx.tcx.data_layout.pointer_size,
-            Self::Image { .. } | Self::Sampler | Self::SampledImage { .. } => Size::from_bytes(4),
+            Self::Image { .. }
+            | Self::Sampler
+            | Self::SampledImage { .. }
+            | Self::AccelerationStructureKhr
+            | Self::RayQueryKhr => Size::from_bytes(4),
 
             Self::InterfaceBlock { inner_type } => cx.lookup_type(inner_type).sizeof(cx)?,
         };
@@ -375,9 +433,11 @@ impl SpirvType {
                 cx.lookup_type(element).alignof(cx)
             }
             Self::Pointer { .. } => cx.tcx.data_layout.pointer_align.abi,
-            Self::Image { .. } | Self::Sampler | Self::SampledImage { .. } => {
-                Align::from_bytes(4).unwrap()
-            }
+            Self::Image { .. }
+            | Self::Sampler
+            | Self::SampledImage { .. }
+            | Self::AccelerationStructureKhr
+            | Self::RayQueryKhr => Align::from_bytes(4).unwrap(),
 
             Self::InterfaceBlock { inner_type } => cx.lookup_type(inner_type).alignof(cx),
         }
@@ -516,6 +576,11 @@ impl fmt::Debug for SpirvTypePrinter<'_, '_> {
                 .field("id", &self.id)
                 .field("image_type", &self.cx.debug_type(image_type))
                 .finish(),
+            SpirvType::AccelerationStructureKhr => f
+                .debug_struct("AccelerationStructureKhr")
+                .field("id", &self.id)
+                .finish(),
+            SpirvType::RayQueryKhr => f.debug_struct("RayQueryKhr").field("id", &self.id).finish(),
 
             SpirvType::InterfaceBlock { inner_type } => f
                 .debug_struct("InterfaceBlock")
@@ -671,6 +736,8 @@ impl SpirvTypePrinter<'_, '_> {
                 .debug_struct("SampledImage")
                 .field("image_type", &self.cx.debug_type(image_type))
                 .finish(),
+            SpirvType::AccelerationStructureKhr => f.write_str("AccelerationStructureKhr"),
+            SpirvType::RayQueryKhr => f.write_str("RayQueryKhr"),
 
             SpirvType::InterfaceBlock { inner_type } => {
                 f.write_str("interface block { ")?;
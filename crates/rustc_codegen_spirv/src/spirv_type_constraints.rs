@@ -427,7 +427,8 @@ pub fn instruction_signatures(op: Op) -> Option<&'static [InstSig<'static>]> {
         Op::ConvertPtrToU | Op::SatConvertSToU | Op::SatConvertUToS | Op::ConvertUToPtr => {}
         Op::PtrCastToGeneric | Op::GenericCastToPtr => sig! { (Pointer(_, T)) -> Pointer(_, T) },
         Op::GenericCastToPtrExplicit => sig! { {S} (Pointer(_, T)) -> Pointer(S, T) },
-        Op::Bitcast => {}
+        // Pointers can only be cast to pointers with the same storage class.
+        Op::Bitcast => sig! { (Pointer(S, _)) -> Pointer(S, _) | (_) -> _ },
 
         // 3.37.12. Composite Instructions
         Op::VectorExtractDynamic => sig! { (Vector(T), _) -> T },
@@ -727,7 +728,7 @@ pub fn instruction_signatures(op: Op) -> Option<&'static [InstSig<'static>]> {
         | Op::ExecuteCallableKHR
         | Op::ConvertUToAccelerationStructureKHR
         | Op::IgnoreIntersectionKHR
-        | Op::TerminateRayKHR => reserved!(SPV_KHR_ray_tracing),
+        | Op::TerminateRayKHR => {}
         // SPV_KHR_ray_query
         Op::TypeRayQueryKHR
         | Op::RayQueryInitializeKHR
@@ -735,20 +736,19 @@ pub fn instruction_signatures(op: Op) -> Option<&'static [InstSig<'static>]> {
         | Op::RayQueryGenerateIntersectionKHR
         | Op::RayQueryConfirmIntersectionKHR
         | Op::RayQueryProceedKHR
-        | Op::RayQueryGetIntersectionTypeKHR => reserved!(SPV_KHR_ray_query),
+        | Op::RayQueryGetIntersectionTypeKHR => {}
         // SPV_AMD_shader_fragment_mask
         Op::FragmentMaskFetchAMD | Op::FragmentFetchAMD => reserved!(SPV_AMD_shader_fragment_mask),
         // SPV_KHR_shader_clock
         Op::ReadClockKHR => reserved!(SPV_KHR_shader_clock),
         // SPV_NV_mesh_shader
-        Op::WritePackedPrimitiveIndices4x8NV => reserved!(SPV_NV_mesh_shader),
-        // SPV_NV_ray_tracing
-        Op::ReportIntersectionNV
-        | Op::IgnoreIntersectionNV
-        | Op::TerminateRayNV
-        | Op::TraceNV
-        | Op::TypeAccelerationStructureNV
-        | Op::ExecuteCallableNV => reserved!(SPV_NV_ray_tracing),
+        Op::WritePackedPrimitiveIndices4x8NV => {}
+        // SPV_NV_ray_tracing (`ReportIntersection` and `TypeAccelerationStructure`
+        // are shared with SPV_KHR_ray_tracing)
+        Op::ReportIntersectionNV | Op::TypeAccelerationStructureNV => {}
+        Op::IgnoreIntersectionNV | Op::TerminateRayNV | Op::TraceNV | Op::ExecuteCallableNV => {
+            reserved!(SPV_NV_ray_tracing)
+        }
         // SPV_NV_cooperative_matrix
         Op::TypeCooperativeMatrixNV
         | Op::CooperativeMatrixLoadNV
@@ -802,7 +802,7 @@ pub fn instruction_signatures(op: Op) -> Option<&'static [InstSig<'static>]> {
         | Op::RayQueryGetWorldRayDirectionKHR
         | Op::RayQueryGetWorldRayOriginKHR
         | Op::RayQueryGetIntersectionObjectToWorldKHR
-        | Op::RayQueryGetIntersectionWorldToObjectKHR => reserved!(SPV_KHR_ray_query),
+        | Op::RayQueryGetIntersectionWorldToObjectKHR => {}
 
         // Instructions not present in current SPIR-V specification
         // SPV_INTEL_function_pointers
@@ -1,6 +1,8 @@
-use crate::attr::{Entry, ExecutionModeExtra, IntrinsicType, SpirvAttribute};
+use crate::attr::{
+    BlockLayout, Entry, ExecutionModeExtra, IntrinsicType, SpecConstant, SpirvAttribute,
+};
 use crate::builder::libm_intrinsics;
-use rspirv::spirv::{BuiltIn, ExecutionMode, ExecutionModel, StorageClass};
+use rspirv::spirv::{BuiltIn, ExecutionMode, ExecutionModel, Scope, StorageClass};
 use rustc_ast::ast::{AttrKind, Attribute, Lit, LitIntType, LitKind, NestedMetaItem};
 use rustc_data_structures::fx::FxHashMap;
 use rustc_span::symbol::{Ident, Symbol};
@@ -31,8 +33,17 @@ pub struct Symbols {
     pub spirv14: Symbol,
     pub spirv15: Symbol,
     pub entry_point_name: Symbol,
+    pub atomic_scope: Symbol,
     descriptor_set: Symbol,
     binding: Symbol,
+    input_attachment_index: Symbol,
+    spec_constant: Symbol,
+    block: Symbol,
+    mesh_ext: Symbol,
+    task_ext: Symbol,
+    layout: Symbol,
+    id: Symbol,
+    default: Symbol,
     image_type: Symbol,
     dim: Symbol,
     depth: Symbol,
@@ -144,6 +155,21 @@ const BUILTINS: &[(&str, BuiltIn)] = {
         ("hit_kind_nv", HitKindNV),
         ("incoming_ray_flags_nv", IncomingRayFlagsNV),
         ("ray_geometry_index_khr", RayGeometryIndexKHR),
+        // The KHR names are aliases of the NV variants (associated consts, which `use BuiltIn::*`
+        // doesn't bring in).
+        ("launch_id_khr", LaunchIdNV),
+        ("launch_size_khr", LaunchSizeNV),
+        ("world_ray_origin_khr", WorldRayOriginNV),
+        ("world_ray_direction_khr", WorldRayDirectionNV),
+        ("object_ray_origin_khr", ObjectRayOriginNV),
+        ("object_ray_direction_khr", ObjectRayDirectionNV),
+        ("ray_tmin_khr", RayTminNV),
+        ("ray_tmax_khr", RayTmaxNV),
+        ("instance_custom_index_khr", InstanceCustomIndexNV),
+        ("object_to_world_khr", ObjectToWorldNV),
+        ("world_to_object_khr", WorldToObjectNV),
+        ("hit_kind_khr", HitKindNV),
+        ("incoming_ray_flags_khr", IncomingRayFlagsNV),
         ("warps_per_sm_nv", WarpsPerSMNV),
         ("sm_count_nv", SMCountNV),
         ("warp_id_nv", WarpIDNV),
@@ -204,6 +230,13 @@ const EXECUTION_MODELS: &[(&str, ExecutionModel)] = {
         ("closest_hit_nv", ClosestHitNV),
         ("miss_nv", MissNV),
         ("callable_nv", CallableNV),
+        // See the KHR builtins above.
+        ("ray_generation_khr", RayGenerationNV),
+        ("intersection_khr", IntersectionNV),
+        ("any_hit_khr", AnyHitNV),
+        ("closest_hit_khr", ClosestHitNV),
+        ("miss_khr", MissNV),
+        ("callable_khr", CallableNV),
     ]
 };
 
@@ -337,10 +370,23 @@ impl Symbols {
             ("block", SpirvAttribute::Block),
             ("flat", SpirvAttribute::Flat),
             ("invariant", SpirvAttribute::Invariant),
+            ("per_primitive_nv", SpirvAttribute::PerPrimitiveNv),
             (
                 "sampled_image",
                 SpirvAttribute::IntrinsicType(IntrinsicType::SampledImage),
             ),
+            (
+                "acceleration_structure",
+                SpirvAttribute::IntrinsicType(IntrinsicType::AccelerationStructureKhr),
+            ),
+            (
+                "ray_query",
+                SpirvAttribute::IntrinsicType(IntrinsicType::RayQueryKhr),
+            ),
+            (
+                "typed_buffer",
+                SpirvAttribute::IntrinsicType(IntrinsicType::TypedBuffer),
+            ),
             ("unroll_loops", SpirvAttribute::UnrollLoops),
         ]
         .iter()
@@ -372,6 +418,7 @@ impl Symbols {
             fmt_decimal: Symbol::intern("fmt_decimal"),
 
             entry_point_name: Symbol::intern("entry_point_name"),
+            atomic_scope: Symbol::intern("atomic_scope"),
             spirv: Symbol::intern("spirv"),
             spirv_std: Symbol::intern("spirv_std"),
             libm: Symbol::intern("libm"),
@@ -388,6 +435,14 @@ impl Symbols {
             spirv15: Symbol::intern("spirv1.5"),
             descriptor_set: Symbol::intern("descriptor_set"),
             binding: Symbol::intern("binding"),
+            input_attachment_index: Symbol::intern("input_attachment_index"),
+            spec_constant: Symbol::intern("spec_constant"),
+            block: Symbol::intern("block"),
+            mesh_ext: Symbol::intern("mesh_ext"),
+            task_ext: Symbol::intern("task_ext"),
+            layout: Symbol::intern("layout"),
+            id: Symbol::intern("id"),
+            default: Symbol::intern("default"),
             image_type: Symbol::intern("image_type"),
             dim: Symbol::intern("dim"),
             depth: Symbol::intern("depth"),
@@ -459,6 +514,25 @@ pub(crate) fn parse_attrs_for_checking<'a>(
                     SpirvAttribute::DescriptorSet(parse_attr_int_value(arg)?)
                 } else if arg.has_name(sym.binding) {
                     SpirvAttribute::Binding(parse_attr_int_value(arg)?)
+                } else if arg.has_name(sym.input_attachment_index) {
+                    SpirvAttribute::InputAttachmentIndex(parse_attr_int_value(arg)?)
+                } else if arg.has_name(sym.spec_constant) {
+                    SpirvAttribute::SpecConstant(parse_spec_constant_attr(sym, arg)?)
+                } else if arg.has_name(sym.block) && arg.meta_item_list().is_some() {
+                    SpirvAttribute::BlockLayout(parse_block_layout_attr(sym, arg)?)
+                } else if arg.has_name(sym.mesh_ext) || arg.has_name(sym.task_ext) {
+                    // The SPIR-V headers rspirv is built from predate SPV_EXT_mesh_shader, so
+                    // these execution models (and `OpSetMeshOutputsEXT`/`OpEmitMeshTasksEXT`)
+                    // can't be represented yet.
+                    let name = arg.name_or_empty();
+                    let nv_name = name.as_str().replace("_ext", "_nv");
+                    return Err((
+                        span,
+                        format!(
+                            "`{}` entry points (SPV_EXT_mesh_shader) are not supported yet, use `{}` instead",
+                            name, nv_name
+                        ),
+                    ));
                 } else {
                     let name = match arg.ident() {
                         Some(i) => i,
@@ -611,6 +685,87 @@ fn parse_attr_int_value(arg: &NestedMetaItem) -> Result<u32, ParseAttrError> {
     }
 }
 
+fn parse_spec_constant_attr(
+    sym: &Symbols,
+    arg: &NestedMetaItem,
+) -> Result<SpecConstant, ParseAttrError> {
+    let mut id = None;
+    let mut default = None;
+    if let Some(attrs) = arg.meta_item_list() {
+        for attr in attrs {
+            if attr.has_name(sym.id) {
+                id = Some(parse_attr_int_value(attr)?);
+            } else if attr.has_name(sym.default) {
+                default =
+                    match attr.meta_item().and_then(|attr| attr.name_value_literal()) {
+                        Some(lit)
+                            if matches!(
+                                lit.kind,
+                                LitKind::Int(..)
+                                    | LitKind::Float(..)
+                                    | LitKind::Bool(_)
+                                    | LitKind::Str(..)
+                            ) =>
+                        {
+                            Some(lit.kind.clone())
+                        }
+                        _ => return Err((
+                            attr.span(),
+                            "spec_constant default value must be an integer, float, bool or string literal"
+                                .to_string(),
+                        )),
+                    };
+            } else {
+                return Err((
+                    attr.span(),
+                    "unknown argument to spec_constant attribute".to_string(),
+                ));
+            }
+        }
+    }
+    match id {
+        Some(id) => Ok(SpecConstant { id, default }),
+        None => Err((
+            arg.span(),
+            "#[spirv(spec_constant(id = ...))] must specify an `id`".to_string(),
+        )),
+    }
+}
+
+fn parse_block_layout_attr(
+    sym: &Symbols,
+    arg: &NestedMetaItem,
+) -> Result<BlockLayout, ParseAttrError> {
+    let mut layout = None;
+    for attr in arg.meta_item_list().unwrap_or_default() {
+        if attr.has_name(sym.layout) {
+            layout = match attr.value_str().map(|s| s.as_str()) {
+                Some(s) if &*s == "std140" => Some(BlockLayout::Std140),
+                Some(s) if &*s == "std430" => Some(BlockLayout::Std430),
+                Some(s) if &*s == "scalar" => Some(BlockLayout::Scalar),
+                _ => {
+                    return Err((
+                        attr.span(),
+                        "`layout` must be one of \"std140\", \"std430\" or \"scalar\"".to_string(),
+                    ))
+                }
+            };
+        } else {
+            return Err((
+                attr.span(),
+                "unknown argument to block attribute".to_string(),
+            ));
+        }
+    }
+    match layout {
+        Some(layout) => Ok(layout),
+        None => Err((
+            arg.span(),
+            "#[spirv(block(layout = ...))] must specify a `layout`".to_string(),
+        )),
+    }
+}
+
 fn parse_local_size_attr(arg: &NestedMetaItem) -> Result<[u32; 3], ParseAttrError> {
     let arg = match arg.meta_item() {
         Some(arg) => arg,
@@ -645,6 +800,19 @@ fn parse_local_size_attr(arg: &NestedMetaItem) -> Result<[u32; 3], ParseAttrErro
     }
 }
 
+/// Parses the name of a memory `Scope`, as used by `atomic_scope = "..."` and `--atomic-scope`.
+pub fn parse_scope(name: &str) -> Option<Scope> {
+    Some(match name {
+        "cross_device" => Scope::CrossDevice,
+        "device" => Scope::Device,
+        "queue_family" => Scope::QueueFamily,
+        "workgroup" => Scope::Workgroup,
+        "subgroup" => Scope::Subgroup,
+        "invocation" => Scope::Invocation,
+        _ => return None,
+    })
+}
+
 // for a given entry, gather up the additional attributes
 // in this case ExecutionMode's, some have extra arguments
 // others are specified with x, y, or z components
@@ -661,6 +829,7 @@ fn parse_entry_attrs(
     let mut origin_mode: Option<ExecutionMode> = None;
     let mut local_size: Option<[u32; 3]> = None;
     let mut local_size_hint: Option<[u32; 3]> = None;
+    let mut local_size_id_span: Option<Span> = None;
     // Reserved
     //let mut max_workgroup_size_intel: Option<[u32; 3]> = None;
     if let Some(attrs) = arg.meta_item_list() {
@@ -708,6 +877,22 @@ fn parse_entry_attrs(
                                 _ => unreachable!(),
                             }
                         }
+                        LocalSizeId => {
+                            let val = val.unwrap();
+                            local_size_id_span.get_or_insert(attr_name.span);
+                            match extra_dim {
+                                X => {
+                                    entry.local_size_id[0] = Some(val);
+                                }
+                                Y => {
+                                    entry.local_size_id[1] = Some(val);
+                                }
+                                Z => {
+                                    entry.local_size_id[2] = Some(val);
+                                }
+                                _ => unreachable!(),
+                            }
+                        }
                         // Reserved
                         /*MaxWorkgroupSizeINTEL => {
                             let val = val.unwrap();
@@ -757,6 +942,20 @@ fn parse_entry_attrs(
                             ))
                         }
                     }
+                } else if attr_name.name == sym.atomic_scope {
+                    match attr.value_str().and_then(|s| parse_scope(&s.as_str())) {
+                        Some(scope) => {
+                            entry.atomic_scope = Some(scope);
+                        }
+                        None => {
+                            return Err((
+                                attr_name.span,
+                                "`atomic_scope` must be one of \"cross_device\", \"device\", \
+                                 \"queue_family\", \"workgroup\", \"subgroup\" or \"invocation\""
+                                    .to_string(),
+                            ))
+                        }
+                    }
                 } else {
                     return Err((
                         attr_name.span,
@@ -778,6 +977,31 @@ fn parse_entry_attrs(
             }
         }
     }
+    // `local_size_id_*` turn `threads` dimensions into specialization constants
+    // (defaulting to the `threads` value), which needs `LocalSizeId` instead.
+    let local_size_mode = if let Some(span) = local_size_id_span {
+        if !matches!(entry.execution_model, GLCompute | TaskNV | MeshNV | Kernel) {
+            return Err((
+                span,
+                format!(
+                    "`local_size_id_*` can't be used with `#[spirv({})]`, only with \
+                     `compute`, `task_nv`, `mesh_nv` and `kernel` entry points",
+                    name.name.to_ident_string()
+                ),
+            ));
+        }
+        if local_size.is_none() {
+            return Err((
+                span,
+                String::from(
+                    "`local_size_id_*` requires the `threads` argument, for the default sizes",
+                ),
+            ));
+        }
+        LocalSizeId
+    } else {
+        LocalSize
+    };
     match entry.execution_model {
         Fragment => {
             let origin_mode = origin_mode.unwrap_or(OriginUpperLeft);
@@ -789,7 +1013,7 @@ fn parse_entry_attrs(
             if let Some(local_size) = local_size {
                 entry
                     .execution_modes
-                    .push((LocalSize, ExecutionModeExtra::new(local_size)));
+                    .push((local_size_mode, ExecutionModeExtra::new(local_size)));
             } else {
                 return Err((
                     arg.span(),
@@ -799,11 +1023,26 @@ fn parse_entry_attrs(
                 ));
             }
         }
+        TaskNV | MeshNV => {
+            if let Some(local_size) = local_size {
+                entry
+                    .execution_modes
+                    .push((local_size_mode, ExecutionModeExtra::new(local_size)));
+            } else {
+                return Err((
+                    arg.span(),
+                    format!(
+                        "The `threads` argument must be specified when using `#[spirv({})]`",
+                        name.name.to_ident_string()
+                    ),
+                ));
+            }
+        }
         Kernel => {
             if let Some(local_size) = local_size {
                 entry
                     .execution_modes
-                    .push((LocalSize, ExecutionModeExtra::new(local_size)));
+                    .push((local_size_mode, ExecutionModeExtra::new(local_size)));
             }
             if let Some(local_size_hint) = local_size_hint {
                 entry
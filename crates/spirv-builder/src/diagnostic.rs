@@ -0,0 +1,128 @@
+//! Compiler diagnostics, parsed from the JSON messages printed by
+//! `cargo build --message-format=json`.
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// The severity of a [`Diagnostic`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+    Note,
+    Help,
+    FailureNote,
+    #[serde(rename = "error: internal compiler error")]
+    InternalCompilerError,
+    #[serde(other)]
+    Other,
+}
+
+/// A region of a source file a [`Diagnostic`] points at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiagnosticSpan {
+    /// The source file, made absolute where possible (rustc reports paths
+    /// relative to the workspace root of the shader crate).
+    pub file: PathBuf,
+    /// Byte offsets into the file, the end being exclusive.
+    pub byte_start: u32,
+    pub byte_end: u32,
+    /// 1-based lines, inclusive.
+    pub line_start: usize,
+    pub line_end: usize,
+    /// 1-based columns (in characters), the end being exclusive.
+    pub column_start: usize,
+    pub column_end: usize,
+}
+
+/// An error, warning etc. reported while compiling the shader crate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub level: DiagnosticLevel,
+    pub message: String,
+    /// The error code (e.g. `E0308`), if any.
+    pub code: Option<String>,
+    /// The primary span, if the diagnostic points at the source code.
+    pub span: Option<DiagnosticSpan>,
+    /// The diagnostic as rustc would print it, including notes and help.
+    pub rendered: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonTarget {
+    src_path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct JsonDiagnosticCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct JsonDiagnosticSpan {
+    file_name: PathBuf,
+    byte_start: u32,
+    byte_end: u32,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+}
+
+#[derive(Deserialize)]
+struct JsonDiagnostic {
+    message: String,
+    code: Option<JsonDiagnosticCode>,
+    level: DiagnosticLevel,
+    spans: Vec<JsonDiagnosticSpan>,
+    rendered: Option<String>,
+}
+
+/// The `"reason": "compiler-message"` lines printed by cargo.
+#[derive(Deserialize)]
+pub(crate) struct CompilerMessage {
+    target: JsonTarget,
+    message: JsonDiagnostic,
+}
+
+impl CompilerMessage {
+    pub(crate) fn into_diagnostic(self) -> Diagnostic {
+        let CompilerMessage { target, message } = self;
+        let span = message
+            .spans
+            .into_iter()
+            .find(|span| span.is_primary)
+            .map(|span| DiagnosticSpan {
+                file: resolve_file(&target.src_path, span.file_name),
+                byte_start: span.byte_start,
+                byte_end: span.byte_end,
+                line_start: span.line_start,
+                line_end: span.line_end,
+                column_start: span.column_start,
+                column_end: span.column_end,
+            });
+        Diagnostic {
+            level: message.level,
+            message: message.message,
+            code: message.code.map(|code| code.code),
+            span,
+            rendered: message.rendered,
+        }
+    }
+}
+
+/// Relative paths are relative to the workspace root, which is one of the
+/// ancestors of the (absolute) path of the crate root.
+fn resolve_file(src_path: &Path, file: PathBuf) -> PathBuf {
+    if file.is_absolute() {
+        return file;
+    }
+    src_path
+        .ancestors()
+        .skip(1)
+        .map(|dir| dir.join(&file))
+        .find(|path| path.is_file())
+        .unwrap_or(file)
+}
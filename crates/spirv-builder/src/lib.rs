@@ -57,6 +57,16 @@
 mod test;
 
 mod depfile;
+pub mod diagnostic;
+pub mod reflect;
+mod shader_modules;
+mod watch;
+
+pub use diagnostic::{Diagnostic, DiagnosticLevel, DiagnosticSpan};
+pub use reflect::ModuleReflection;
+pub use rustc_codegen_spirv::rspirv::spirv::Capability;
+use shader_modules::read_spirv_words;
+pub use watch::Watcher;
 
 use raw_string::{RawStr, RawString};
 use serde::Deserialize;
@@ -71,16 +81,73 @@ use std::process::{Command, Stdio};
 
 #[derive(Debug)]
 pub enum SpirvBuilderError {
-    BuildFailed,
+    /// `rustc_codegen_spirv` wasn't found in the library path, which is usually
+    /// because `spirv-builder` isn't being used from a build script.
+    RustcCodegenSpirvNotFound(String),
+    /// `cargo` couldn't be run.
+    CargoFailedToRun(std::io::Error),
+    /// The build failed, with the errors (and warnings etc.) reported by the compiler.
+    BuildFailed(Vec<Diagnostic>),
+    /// The build succeeded, but cargo didn't report building the shader crate.
+    NoArtifact,
+    /// The shader crate was built, but had no `.spv` output.
+    NoSpvArtifact,
+    /// The shader crate was built, but had more than one `.spv` output.
+    MultipleSpvArtifacts(Vec<PathBuf>),
+    /// The dependency file of the output (for `cargo:rerun-if-changed`) couldn't be read.
+    DepFileUnreadable(std::io::Error),
     MultiModuleWithPrintMetadata,
     MetadataFileMissing(std::io::Error),
     MetadataFileMalformed(serde_json::Error),
+    /// The path given to [`ModuleReflection::load`] doesn't end in a file name.
+    NotAModulePath(PathBuf),
+    ReflectionFileMissing(std::io::Error),
+    ReflectionFileMalformed(serde_json::Error),
+    /// A built `.spv` file couldn't be read.
+    SpirvFileUnreadable(std::io::Error),
+    /// A built `.spv` file isn't made of whole 32-bit words.
+    SpirvFileMalformed(PathBuf),
+    /// `shader_modules` was set outside of a build script.
+    ShaderModulesWithoutOutDir,
+    ShaderModulesUnwritable(std::io::Error),
+    /// The background thread of [`SpirvBuilder::watch`] couldn't be started.
+    WatchThreadUnspawnable(std::io::Error),
+    /// A path given to [`SpirvBuilder::dump_stage`] isn't UTF-8 or contains whitespace, so it
+    /// can't be passed through `RUSTFLAGS`.
+    DumpPathUnrepresentable(PathBuf),
 }
 
 impl fmt::Display for SpirvBuilderError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            SpirvBuilderError::BuildFailed => f.write_str("Build failed"),
+            SpirvBuilderError::RustcCodegenSpirvNotFound(filename) => {
+                write!(f, "Could not find {} in library path", filename)
+            }
+            SpirvBuilderError::CargoFailedToRun(_) => f.write_str("Failed to execute cargo build"),
+            SpirvBuilderError::BuildFailed(diagnostics) => {
+                let errors = diagnostics
+                    .iter()
+                    .filter(|diagnostic| {
+                        matches!(
+                            diagnostic.level,
+                            DiagnosticLevel::Error | DiagnosticLevel::InternalCompilerError
+                        )
+                    })
+                    .count();
+                match errors {
+                    0 => f.write_str("Build failed"),
+                    1 => f.write_str("Build failed with 1 error"),
+                    _ => write!(f, "Build failed with {} errors", errors),
+                }
+            }
+            SpirvBuilderError::NoArtifact => {
+                f.write_str("Did not find output file in rustc output")
+            }
+            SpirvBuilderError::NoSpvArtifact => f.write_str("Crate had no .spv artifacts"),
+            SpirvBuilderError::MultipleSpvArtifacts(_) => {
+                f.write_str("Crate had multiple .spv artifacts")
+            }
+            SpirvBuilderError::DepFileUnreadable(_) => f.write_str("Could not read dep file"),
             SpirvBuilderError::MultiModuleWithPrintMetadata => {
                 f.write_str("Multi-module build cannot be used with print_metadata = true")
             }
@@ -90,33 +157,152 @@ impl fmt::Display for SpirvBuilderError {
             SpirvBuilderError::MetadataFileMalformed(_) => {
                 f.write_str("Unable to parse multi-module metadata file")
             }
+            SpirvBuilderError::NotAModulePath(path) => {
+                write!(f, "{} is not the path of a module", path.display())
+            }
+            SpirvBuilderError::ReflectionFileMissing(_) => f.write_str("Reflection file missing"),
+            SpirvBuilderError::ReflectionFileMalformed(_) => {
+                f.write_str("Unable to parse reflection file")
+            }
+            SpirvBuilderError::SpirvFileUnreadable(_) => f.write_str("Could not read .spv file"),
+            SpirvBuilderError::SpirvFileMalformed(path) => write!(
+                f,
+                "{} is not a SPIR-V module (its length is not a multiple of 4)",
+                path.display()
+            ),
+            SpirvBuilderError::ShaderModulesWithoutOutDir => {
+                f.write_str("shader_modules = true requires OUT_DIR, set in build scripts")
+            }
+            SpirvBuilderError::ShaderModulesUnwritable(_) => {
+                f.write_str("Could not write shader modules file to OUT_DIR")
+            }
+            SpirvBuilderError::WatchThreadUnspawnable(_) => {
+                f.write_str("Could not start the watch thread")
+            }
+            SpirvBuilderError::DumpPathUnrepresentable(path) => write!(
+                f,
+                "Dump path {} must be UTF-8 and contain no whitespace",
+                path.display()
+            ),
         }
     }
 }
 
-impl Error for SpirvBuilderError {}
+impl Error for SpirvBuilderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SpirvBuilderError::CargoFailedToRun(e)
+            | SpirvBuilderError::DepFileUnreadable(e)
+            | SpirvBuilderError::MetadataFileMissing(e)
+            | SpirvBuilderError::ReflectionFileMissing(e)
+            | SpirvBuilderError::SpirvFileUnreadable(e)
+            | SpirvBuilderError::ShaderModulesUnwritable(e)
+            | SpirvBuilderError::WatchThreadUnspawnable(e) => Some(e),
+            SpirvBuilderError::MetadataFileMalformed(e)
+            | SpirvBuilderError::ReflectionFileMalformed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum MemoryModel {
     Simple,
     Vulkan,
     GLSL450,
 }
 
+/// The scope used for atomics that don't have one chosen by their entry point's `atomic_scope`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AtomicScope {
+    CrossDevice,
+    Device,
+    QueueFamily,
+    Workgroup,
+    Subgroup,
+    Invocation,
+}
+
+/// A point during linking at which the module(s) can be dumped, see
+/// [`SpirvBuilder::dump_stage`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LinkStage {
+    /// Every module being linked (from the shader crate and its dependencies), as `mod_N.spv`
+    /// files in a directory.
+    PreLink,
+    /// The merged module, before any linker passes, to a file.
+    PostMerge,
+    /// Each module after splitting it per entry point (or the whole module, if not building
+    /// one module per entry point), to a file. With [`SpirvBuilder::build_multimodule`], each
+    /// module goes to its own file, with the entry point name added before the extension.
+    PostSplit,
+    /// Each linked module, before `spirv-opt` and `spirv-val`, to a file, named per entry
+    /// point like [`LinkStage::PostSplit`].
+    PostLink,
+}
+
+/// A successfully built shader crate, with each module either as the path to the built spir-v
+/// file (`M = PathBuf`), or as its SPIR-V words (`M = Vec<u32>`).
+#[derive(Clone, Debug)]
+pub enum CompileResult<M = PathBuf> {
+    /// The only module.
+    SingleModule(M),
+    /// One module per entry point, by entry point name.
+    MultiModule(HashMap<String, M>),
+}
+
+impl CompileResult {
+    /// Reads the SPIR-V words of the built module(s).
+    pub fn read_words(&self) -> Result<CompileResult<Vec<u32>>, SpirvBuilderError> {
+        Ok(match self {
+            CompileResult::SingleModule(path) => {
+                CompileResult::SingleModule(read_spirv_words(path)?)
+            }
+            CompileResult::MultiModule(paths) => CompileResult::MultiModule(
+                paths
+                    .iter()
+                    .map(|(name, path)| Ok((name.clone(), read_spirv_words(path)?)))
+                    .collect::<Result<_, SpirvBuilderError>>()?,
+            ),
+        })
+    }
+}
+
 pub struct SpirvBuilder {
     path_to_crate: PathBuf,
     print_metadata: bool,
+    shader_modules: bool,
     release: bool,
     spirv_version: Option<(u8, u8)>,
     memory_model: Option<MemoryModel>,
+    atomic_scope: Option<AtomicScope>,
+    capabilities: Vec<Capability>,
+    extensions: Vec<String>,
+    validate: bool,
+    dce: bool,
+    compact_ids: bool,
+    structurize: bool,
+    old_structurizer: bool,
+    dumps: Vec<(LinkStage, PathBuf)>,
 }
 impl SpirvBuilder {
     pub fn new(path_to_crate: impl AsRef<Path>) -> Self {
         Self {
             path_to_crate: path_to_crate.as_ref().to_owned(),
             print_metadata: true,
+            shader_modules: false,
             release: true,
             spirv_version: None,
             memory_model: None,
+            atomic_scope: None,
+            capabilities: Vec::new(),
+            extensions: Vec::new(),
+            validate: true,
+            dce: true,
+            compact_ids: true,
+            structurize: true,
+            old_structurizer: false,
+            dumps: Vec::new(),
         }
     }
 
@@ -126,6 +312,16 @@ impl SpirvBuilder {
         self
     }
 
+    /// Whether to also write the built module(s) to a Rust file in `OUT_DIR`, named after the
+    /// shader crate (e.g. `sky_shader.rs`), for `include!`. It defines the SPIR-V words of the
+    /// module as `pub const SKY_SHADER: &[u32]` (or one `SKY_SHADER_<ENTRY_POINT>` per entry point,
+    /// for multi-module builds), and the entry point names as `&str` constants in
+    /// `pub mod sky_shader`. Defaults to false.
+    pub fn shader_modules(mut self, v: bool) -> Self {
+        self.shader_modules = v;
+        self
+    }
+
     /// Build in release. Defaults to true.
     pub fn release(mut self, v: bool) -> Self {
         self.release = v;
@@ -144,31 +340,204 @@ impl SpirvBuilder {
         self
     }
 
+    /// Sets the default scope of atomics. Defaults to `Device`.
+    pub fn atomic_scope(mut self, atomic_scope: AtomicScope) -> Self {
+        self.atomic_scope = Some(atomic_scope);
+        self
+    }
+
+    /// Declares a capability the shader may use. Once any capability is declared, using one that
+    /// wasn't (other than the ones always declared for the target, like `Shader`) is an error.
+    /// Declaring a capability doesn't add it to the module, only the ones used are kept.
+    pub fn capability(mut self, capability: Capability) -> Self {
+        self.capabilities.push(capability);
+        self
+    }
+
+    /// Declares an extension the shader may use, e.g. from `asm!`. Like every extension, it's
+    /// removed from the module again if nothing uses it.
+    pub fn extension(mut self, extension: impl Into<String>) -> Self {
+        self.extensions.push(extension.into());
+        self
+    }
+
+    /// Whether to run `spirv-val` on the built module(s). Defaults to true.
+    pub fn validate(mut self, v: bool) -> Self {
+        self.validate = v;
+        self
+    }
+
+    /// Whether to remove dead code while linking. Defaults to true.
+    pub fn dce(mut self, v: bool) -> Self {
+        self.dce = v;
+        self
+    }
+
+    /// Whether to renumber the SPIR-V IDs of the linked module(s) to be contiguous.
+    /// Defaults to true.
+    pub fn compact_ids(mut self, v: bool) -> Self {
+        self.compact_ids = v;
+        self
+    }
+
+    /// Whether to make the control flow structured, as required by shaders. Defaults to true.
+    pub fn structurize(mut self, v: bool) -> Self {
+        self.structurize = v;
+        self
+    }
+
+    /// Whether to use the old structurizer instead of the new one. Defaults to false.
+    pub fn old_structurizer(mut self, v: bool) -> Self {
+        self.old_structurizer = v;
+        self
+    }
+
+    /// Dumps the module(s) at `stage` of linking to `path`, for debugging the compiler.
+    /// See [`LinkStage`] for whether `path` is a file or a directory. As it's passed through
+    /// `RUSTFLAGS`, `path` must be UTF-8 and can't contain whitespace.
+    pub fn dump_stage(mut self, stage: LinkStage, path: impl AsRef<Path>) -> Self {
+        self.dumps.push((stage, path.as_ref().to_owned()));
+        self
+    }
+
     /// Builds the module. Returns the path to the built spir-v file. If `print_metadata` is true,
     /// you usually don't have to inspect the path, as the environment variable will already be
     /// set.
     pub fn build(self) -> Result<PathBuf, SpirvBuilderError> {
         let spirv_module = invoke_rustc(&self, false)?;
-        let env_var = spirv_module.file_name().unwrap().to_str().unwrap();
         if self.print_metadata {
-            println!("cargo:rustc-env={}={}", env_var, spirv_module.display());
+            let env_var = spirv_module.file_name().unwrap_or_default();
+            println!(
+                "cargo:rustc-env={}={}",
+                env_var.to_string_lossy(),
+                spirv_module.display()
+            );
+        }
+        if self.shader_modules {
+            let entry_points = ModuleReflection::load(&spirv_module)?.entry_points;
+            let entry_points: Vec<&str> = entry_points.iter().map(|e| &e.name[..]).collect();
+            let modules = [(None, read_spirv_words(&spirv_module)?)];
+            let crate_name = crate_name_of(&spirv_module);
+            let source = shader_modules::generate(&crate_name, &modules, &entry_points);
+            shader_modules::write_to_out_dir(&crate_name, &source)?;
         }
         Ok(spirv_module)
     }
 
+    /// Builds the module like [`build`](Self::build), returning its SPIR-V words.
+    pub fn build_to_memory(self) -> Result<Vec<u32>, SpirvBuilderError> {
+        read_spirv_words(&self.build()?)
+    }
+
+    /// Builds the module like [`build`](Self::build), and also loads the reflection information
+    /// the compiler wrote next to it.
+    pub fn build_with_reflection(self) -> Result<(PathBuf, ModuleReflection), SpirvBuilderError> {
+        let spirv_module = self.build()?;
+        let reflection = ModuleReflection::load(&spirv_module)?;
+        Ok((spirv_module, reflection))
+    }
+
     pub fn build_multimodule(self) -> Result<HashMap<String, PathBuf>, SpirvBuilderError> {
         if self.print_metadata {
             return Err(SpirvBuilderError::MultiModuleWithPrintMetadata);
         }
         let metadata_file = invoke_rustc(&self, true)?;
-        let metadata_contents =
-            File::open(metadata_file).map_err(SpirvBuilderError::MetadataFileMissing)?;
-        let metadata = serde_json::from_reader(BufReader::new(metadata_contents))
-            .map_err(SpirvBuilderError::MetadataFileMalformed)?;
-        Ok(metadata)
+        let modules = read_multimodule_metadata(&metadata_file)?;
+        if self.shader_modules {
+            let mut entry_points: Vec<&str> = modules.keys().map(|name| &name[..]).collect();
+            entry_points.sort_unstable();
+            let modules = entry_points
+                .iter()
+                .map(|&name| Ok((Some(name), read_spirv_words(&modules[name])?)))
+                .collect::<Result<Vec<_>, SpirvBuilderError>>()?;
+            let crate_name = crate_name_of(&metadata_file);
+            let source = shader_modules::generate(&crate_name, &modules, &entry_points);
+            shader_modules::write_to_out_dir(&crate_name, &source)?;
+        }
+        Ok(modules)
+    }
+
+    /// Builds one module per entry point like [`build_multimodule`](Self::build_multimodule),
+    /// returning their SPIR-V words, by entry point name.
+    pub fn build_multimodule_to_memory(
+        self,
+    ) -> Result<HashMap<String, Vec<u32>>, SpirvBuilderError> {
+        self.build_multimodule()?
+            .into_iter()
+            .map(|(name, path)| Ok((name, read_spirv_words(&path)?)))
+            .collect()
+    }
+
+    /// Builds the crate on a background thread (one module per entry point if `multimodule`
+    /// is true), and again whenever one of its source files changes, passing each result
+    /// (including build errors) to `on_build`.
+    ///
+    /// The source files are learned from the dependency file rustc writes next to the output,
+    /// and watched by polling their modification times, waiting for them to stop changing
+    /// before rebuilding. Cargo metadata is never printed. Watching stops once the returned
+    /// [`Watcher`] is dropped (after any build in progress finishes).
+    pub fn watch(
+        mut self,
+        multimodule: bool,
+        on_build: impl FnMut(Result<CompileResult, SpirvBuilderError>) + Send + 'static,
+    ) -> Result<Watcher, SpirvBuilderError> {
+        self.print_metadata = false;
+        watch::spawn(self, multimodule, on_build)
+    }
+
+    /// Watches the crate like [`watch`](Self::watch), passing the SPIR-V words of the built
+    /// module(s) to `on_build`.
+    pub fn watch_to_memory<F>(
+        self,
+        multimodule: bool,
+        mut on_build: F,
+    ) -> Result<Watcher, SpirvBuilderError>
+    where
+        F: FnMut(Result<CompileResult<Vec<u32>>, SpirvBuilderError>) + Send + 'static,
+    {
+        self.watch(multimodule, move |result| {
+            on_build(result.and_then(|result| result.read_words()))
+        })
+    }
+
+    /// Builds the crate (one module per entry point if `multimodule` is true), returning the
+    /// source files it was built from, for [`watch`](Self::watch).
+    fn build_compile_result(
+        &self,
+        multimodule: bool,
+    ) -> Result<(CompileResult, Vec<PathBuf>), SpirvBuilderError> {
+        let artifact = invoke_rustc(self, multimodule)?;
+        let sources = deps_of(&artifact)?
+            .iter()
+            .map(|source| PathBuf::from(source.to_string()))
+            .collect();
+        let result = if multimodule {
+            CompileResult::MultiModule(read_multimodule_metadata(&artifact)?)
+        } else {
+            CompileResult::SingleModule(artifact)
+        };
+        Ok((result, sources))
     }
 }
 
+/// The name of the shader crate `artifact` was built from, e.g. `sky_shader` for `sky_shader.spv`.
+fn crate_name_of(artifact: &Path) -> String {
+    artifact
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn read_multimodule_metadata(
+    metadata_file: &Path,
+) -> Result<HashMap<String, PathBuf>, SpirvBuilderError> {
+    let metadata_contents =
+        File::open(metadata_file).map_err(SpirvBuilderError::MetadataFileMissing)?;
+    serde_json::from_reader(BufReader::new(metadata_contents))
+        .map_err(SpirvBuilderError::MetadataFileMalformed)
+}
+
 // https://github.com/rust-lang/cargo/blob/1857880b5124580c4aeb4e8bc5f1198f491d61b1/src/cargo/util/paths.rs#L29-L52
 fn dylib_path_envvar() -> &'static str {
     if cfg!(windows) {
@@ -186,7 +555,7 @@ fn dylib_path() -> Vec<PathBuf> {
     }
 }
 
-fn find_rustc_codegen_spirv() -> PathBuf {
+fn find_rustc_codegen_spirv() -> Result<PathBuf, SpirvBuilderError> {
     let filename = format!(
         "{}rustc_codegen_spirv{}",
         env::consts::DLL_PREFIX,
@@ -195,10 +564,10 @@ fn find_rustc_codegen_spirv() -> PathBuf {
     for mut path in dylib_path() {
         path.push(&filename);
         if path.is_file() {
-            return path;
+            return Ok(path);
         }
     }
-    panic!("Could not find {} in library path", filename);
+    Err(SpirvBuilderError::RustcCodegenSpirvNotFound(filename))
 }
 
 // Note: in case of multimodule, returns path to the metadata json
@@ -211,7 +580,7 @@ fn invoke_rustc(builder: &SpirvBuilder, multimodule: bool) -> Result<PathBuf, Sp
     // alongside build.rs, and cargo will helpfully add it to LD_LIBRARY_PATH for us! However,
     // rustc expects a full path, instead of a filename looked up via LD_LIBRARY_PATH, so we need
     // to copy cargo's understanding of library lookup and find the library and its full path.
-    let rustc_codegen_spirv = find_rustc_codegen_spirv();
+    let rustc_codegen_spirv = find_rustc_codegen_spirv()?;
     let mut target_features = Vec::new();
     // these must match codegen_cx/mod.rs
     if let Some((major, minor)) = builder.spirv_version {
@@ -227,16 +596,65 @@ fn invoke_rustc(builder: &SpirvBuilder, multimodule: bool) -> Result<PathBuf, Sp
             .to_string(),
         );
     }
+    for capability in &builder.capabilities {
+        target_features.push(format!("+cap:{:?}", capability));
+    }
+    for extension in &builder.extensions {
+        target_features.push(format!("+ext:{}", extension));
+    }
     let feature_flag = if target_features.is_empty() {
         String::new()
     } else {
         format!(" -C target-feature={}", target_features.join(","))
     };
-    let llvm_args = if multimodule {
-        " -C llvm-args=--module-output=multiple"
-    } else {
-        ""
-    };
+    // these must match CodegenArgs::parse in codegen_cx/mod.rs
+    let mut llvm_args = Vec::new();
+    if multimodule {
+        llvm_args.push("--module-output=multiple".to_string());
+    }
+    if let Some(atomic_scope) = &builder.atomic_scope {
+        llvm_args.push(
+            match atomic_scope {
+                AtomicScope::CrossDevice => "--atomic-scope=cross_device",
+                AtomicScope::Device => "--atomic-scope=device",
+                AtomicScope::QueueFamily => "--atomic-scope=queue_family",
+                AtomicScope::Workgroup => "--atomic-scope=workgroup",
+                AtomicScope::Subgroup => "--atomic-scope=subgroup",
+                AtomicScope::Invocation => "--atomic-scope=invocation",
+            }
+            .to_string(),
+        );
+    }
+    let flags = [
+        (!builder.validate, "--no-spirv-val"),
+        (!builder.dce, "--no-dce"),
+        (!builder.compact_ids, "--no-compact-ids"),
+        (!builder.structurize, "--no-structurize"),
+        (builder.old_structurizer, "--old-structurizer"),
+    ];
+    for &(set, flag) in &flags {
+        if set {
+            llvm_args.push(flag.to_string());
+        }
+    }
+    for (stage, path) in &builder.dumps {
+        let stage = match stage {
+            LinkStage::PreLink => "pre-link",
+            LinkStage::PostMerge => "post-merge",
+            LinkStage::PostSplit => "post-split",
+            LinkStage::PostLink => "post-link",
+        };
+        // `RUSTFLAGS` is split on whitespace, and `display` would mangle non-UTF-8 paths.
+        let path_str = path
+            .to_str()
+            .filter(|path| !path.contains(char::is_whitespace))
+            .ok_or_else(|| SpirvBuilderError::DumpPathUnrepresentable(path.clone()))?;
+        llvm_args.push(format!("--dump-{}={}", stage, path_str));
+    }
+    let llvm_args: String = llvm_args
+        .iter()
+        .map(|arg| format!(" -C llvm-args={}", arg))
+        .collect();
     let rustflags = format!(
         "-Z codegen-backend={} -Z symbol-mangling-version=v0{}{}",
         rustc_codegen_spirv.display(),
@@ -246,7 +664,7 @@ fn invoke_rustc(builder: &SpirvBuilder, multimodule: bool) -> Result<PathBuf, Sp
     let mut cargo = Command::new("cargo");
     cargo.args(&[
         "build",
-        "--message-format=json-render-diagnostics",
+        "--message-format=json",
         "-Zbuild-std=core",
         "-Zbuild-std-features=compiler-builtins-mem",
         "--target",
@@ -282,21 +700,22 @@ fn invoke_rustc(builder: &SpirvBuilder, multimodule: bool) -> Result<PathBuf, Sp
         .current_dir(&builder.path_to_crate)
         .env("RUSTFLAGS", rustflags)
         .output()
-        .expect("failed to execute cargo build");
+        .map_err(SpirvBuilderError::CargoFailedToRun)?;
 
-    // `get_last_artifact` has the side-effect of printing invalid lines, so
-    // we do that even in case of an error, to let through any useful messages
-    // that ended up on stdout instead of stderr.
-    let stdout = String::from_utf8(build.stdout).unwrap();
-    let artifact = get_last_artifact(&stdout);
+    // `parse_cargo_output` has the side-effect of printing the rendered diagnostics
+    // and invalid lines, so we do that even in case of an error, to let through any
+    // useful messages that ended up on stdout instead of stderr.
+    let stdout = String::from_utf8_lossy(&build.stdout);
+    let (artifact, diagnostics) = parse_cargo_output(&stdout);
 
     if build.status.success() {
+        let artifact = get_spv_artifact(artifact)?;
         if builder.print_metadata {
-            print_deps_of(&artifact);
+            print_deps_of(&artifact)?;
         }
         Ok(artifact)
     } else {
-        Err(SpirvBuilderError::BuildFailed)
+        Err(SpirvBuilderError::BuildFailed(diagnostics))
     }
 }
 
@@ -306,48 +725,83 @@ struct RustcOutput {
     filenames: Option<Vec<String>>,
 }
 
-fn get_last_artifact(out: &str) -> PathBuf {
-    let last = out
-        .lines()
-        .filter_map(|line| match serde_json::from_str::<RustcOutput>(line) {
-            Ok(line) => Some(line),
+/// Returns the last artifact's filenames, and all the compiler diagnostics.
+fn parse_cargo_output(out: &str) -> (Option<Vec<String>>, Vec<Diagnostic>) {
+    let mut last_artifact = None;
+    let mut diagnostics = Vec::new();
+    for line in out.lines() {
+        let output = match serde_json::from_str::<RustcOutput>(line) {
+            Ok(output) => output,
             Err(_) => {
                 // Pass through invalid lines
                 println!("{}", line);
-                None
+                continue;
             }
-        })
-        .filter(|line| line.reason == "compiler-artifact")
-        .last()
-        .expect("Did not find output file in rustc output");
+        };
+        match &output.reason[..] {
+            "compiler-artifact" => last_artifact = Some(output.filenames.unwrap_or_default()),
+            "compiler-message" => {
+                if let Ok(message) = serde_json::from_str::<diagnostic::CompilerMessage>(line) {
+                    let diagnostic = message.into_diagnostic();
+                    // Print diagnostics like `--message-format=json-render-diagnostics` would.
+                    if let Some(rendered) = &diagnostic.rendered {
+                        eprint!("{}", rendered);
+                    }
+                    diagnostics.push(diagnostic);
+                }
+            }
+            _ => {}
+        }
+    }
+    (last_artifact, diagnostics)
+}
 
-    let mut filenames = last
-        .filenames
-        .unwrap()
+fn get_spv_artifact(filenames: Option<Vec<String>>) -> Result<PathBuf, SpirvBuilderError> {
+    let mut filenames: Vec<PathBuf> = filenames
+        .ok_or(SpirvBuilderError::NoArtifact)?
         .into_iter()
-        .filter(|v| v.ends_with(".spv"));
-    let filename = filenames.next().expect("Crate had no .spv artifacts");
-    assert_eq!(filenames.next(), None, "Crate had multiple .spv artifacts");
-    filename.into()
+        .filter(|v| v.ends_with(".spv"))
+        .map(PathBuf::from)
+        .collect();
+    match filenames.len() {
+        0 => Err(SpirvBuilderError::NoSpvArtifact),
+        1 => Ok(filenames.pop().unwrap()),
+        _ => Err(SpirvBuilderError::MultipleSpvArtifacts(filenames)),
+    }
+}
+
+fn print_deps_of(artifact: &Path) -> Result<(), SpirvBuilderError> {
+    for dep in deps_of(artifact)? {
+        println!("cargo:rerun-if-changed={}", dep);
+    }
+    Ok(())
 }
 
-fn print_deps_of(artifact: &Path) {
+/// Reads the dependency file of `artifact`, returning the source files it was built from.
+fn deps_of(artifact: &Path) -> Result<Vec<RawString>, SpirvBuilderError> {
     let deps_file = artifact.with_extension("d");
     let mut deps_map = HashMap::new();
     depfile::read_deps_file(&deps_file, |item, deps| {
         deps_map.insert(item, deps);
         Ok(())
     })
-    .expect("Could not read dep file");
-    fn recurse(map: &HashMap<RawString, Vec<RawString>>, artifact: &RawStr) {
+    .map_err(SpirvBuilderError::DepFileUnreadable)?;
+    fn recurse(
+        map: &HashMap<RawString, Vec<RawString>>,
+        artifact: &RawStr,
+        deps: &mut Vec<RawString>,
+    ) {
         match map.get(artifact) {
             Some(entries) => {
                 for entry in entries {
-                    recurse(map, entry)
+                    recurse(map, entry, deps)
                 }
             }
-            None => println!("cargo:rerun-if-changed={}", artifact),
+            None => deps.push(artifact.to_owned()),
         }
     }
-    recurse(&deps_map, artifact.to_str().unwrap().into());
+    let artifact = artifact.to_string_lossy();
+    let mut deps = Vec::new();
+    recurse(&deps_map, artifact[..].into(), &mut deps);
+    Ok(deps)
 }
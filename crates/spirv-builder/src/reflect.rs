@@ -0,0 +1,163 @@
+//! Reflection information written by `rustc_codegen_spirv` next to every `.spv` module it builds.
+//!
+//! NOTE: these types mirror (as `Serialize`) `rustc_codegen_spirv/src/reflect.rs`, and the two must
+//! be kept in sync.
+
+use crate::SpirvBuilderError;
+use serde::Deserialize;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// Entry points of a module, and the resources they use.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct ModuleReflection {
+    pub entry_points: Vec<EntryPoint>,
+}
+
+impl ModuleReflection {
+    /// Loads the reflection information for the module at `spv_path`, as returned by
+    /// [`SpirvBuilder::build`](crate::SpirvBuilder::build) (or one of the modules returned by
+    /// [`SpirvBuilder::build_multimodule`](crate::SpirvBuilder::build_multimodule)).
+    pub fn load(spv_path: impl AsRef<Path>) -> Result<Self, SpirvBuilderError> {
+        let file = File::open(reflection_path(spv_path.as_ref())?)
+            .map_err(SpirvBuilderError::ReflectionFileMissing)?;
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(SpirvBuilderError::ReflectionFileMalformed)
+    }
+
+    pub fn entry_point(&self, name: &str) -> Option<&EntryPoint> {
+        self.entry_points.iter().find(|entry| entry.name == name)
+    }
+}
+
+/// The reflection file for a module is written next to it, with `.json` appended to its name.
+pub(crate) fn reflection_path(spv_path: &Path) -> Result<PathBuf, SpirvBuilderError> {
+    rustc_codegen_spirv::reflection_path(spv_path)
+        .ok_or_else(|| SpirvBuilderError::NotAModulePath(spv_path.to_owned()))
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct EntryPoint {
+    pub name: String,
+    /// The name of the SPIR-V `ExecutionModel`, e.g. `"Fragment"` or `"GLCompute"`.
+    pub execution_model: String,
+    pub execution_modes: Vec<ExecutionMode>,
+    pub inputs: Vec<InterfaceVariable>,
+    pub outputs: Vec<InterfaceVariable>,
+    /// Sorted by descriptor set, then binding.
+    pub descriptor_bindings: Vec<DescriptorBinding>,
+    pub push_constants: Option<PushConstantBlock>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct ExecutionMode {
+    /// The name of the SPIR-V `ExecutionMode`, e.g. `"LocalSize"`.
+    pub mode: String,
+    /// Literal operands, with `OpExecutionModeId` operands resolved to their (default) values.
+    pub operands: Vec<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct InterfaceVariable {
+    pub name: Option<String>,
+    pub location: Option<u32>,
+    /// The name of the SPIR-V `BuiltIn`, e.g. `"Position"`.
+    pub builtin: Option<String>,
+    pub ty: Type,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum DescriptorType {
+    Sampler,
+    CombinedImageSampler,
+    SampledImage,
+    StorageImage,
+    UniformTexelBuffer,
+    StorageTexelBuffer,
+    UniformBuffer,
+    StorageBuffer,
+    InputAttachment,
+    AccelerationStructure,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct DescriptorBinding {
+    pub name: Option<String>,
+    pub set: u32,
+    pub binding: u32,
+    /// `None` if the type doesn't correspond to a Vulkan descriptor type.
+    pub descriptor_type: Option<DescriptorType>,
+    /// `Some(1)` for a single descriptor, `None` for a runtime-sized (bindless) array.
+    pub array_size: Option<u32>,
+    pub ty: Type,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct PushConstantBlock {
+    pub name: Option<String>,
+    /// Size in bytes, with any trailing runtime array counting as empty.
+    pub size: u32,
+    pub ty: Type,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Type {
+    Void,
+    Bool,
+    Int {
+        width: u32,
+        signed: bool,
+    },
+    Float {
+        width: u32,
+    },
+    Vector {
+        element: Box<Type>,
+        count: u32,
+    },
+    Matrix {
+        column: Box<Type>,
+        count: u32,
+    },
+    Array {
+        element: Box<Type>,
+        /// `None` for runtime arrays.
+        count: Option<u32>,
+        stride: Option<u32>,
+    },
+    Struct {
+        name: Option<String>,
+        members: Vec<StructMember>,
+    },
+    Pointer {
+        storage_class: String,
+        pointee: Box<Type>,
+    },
+    Image {
+        dim: String,
+        depth: u32,
+        arrayed: bool,
+        multisampled: bool,
+        sampled: u32,
+        format: String,
+    },
+    Sampler,
+    SampledImage {
+        image: Box<Type>,
+    },
+    AccelerationStructure,
+    RayQuery,
+    /// Any other type, or a type that recursively contains itself (through pointers).
+    Other {
+        opcode: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct StructMember {
+    pub name: Option<String>,
+    pub offset: Option<u32>,
+    pub ty: Type,
+}
@@ -0,0 +1,90 @@
+//! Loading built modules as SPIR-V words, and embedding them into the crate being built, as
+//! the Rust file written to `OUT_DIR` by [`SpirvBuilder::shader_modules`].
+
+use crate::SpirvBuilderError;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Reads the SPIR-V module at `path` as words (in native endianness, like the compiler writes
+/// them).
+pub(crate) fn read_spirv_words(path: &Path) -> Result<Vec<u32>, SpirvBuilderError> {
+    let bytes = fs::read(path).map_err(SpirvBuilderError::SpirvFileUnreadable)?;
+    if bytes.len() % 4 != 0 {
+        return Err(SpirvBuilderError::SpirvFileMalformed(path.to_owned()));
+    }
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|word| u32::from_ne_bytes([word[0], word[1], word[2], word[3]]))
+        .collect())
+}
+
+/// Generates the Rust source embedding the modules built from the shader crate `crate_name`:
+/// a `&[u32]` constant for every module (named after the crate, followed by the entry point
+/// for multi-module builds), and a module named after the crate, with a `&str` constant for
+/// every entry point name.
+pub(crate) fn generate(
+    crate_name: &str,
+    modules: &[(Option<&str>, Vec<u32>)],
+    entry_points: &[&str],
+) -> String {
+    let crate_ident = to_ident(crate_name);
+    let mut source = format!(
+        "// Generated by spirv-builder from the `{}` shader crate.\n",
+        crate_name
+    );
+    for (entry_point, words) in modules {
+        let const_name = match entry_point {
+            Some(entry_point) => format!("{}_{}", crate_ident, to_ident(entry_point)),
+            None => crate_ident.clone(),
+        };
+        writeln!(
+            source,
+            "\npub const {}: &[u32] = &[",
+            const_name.to_uppercase()
+        )
+        .unwrap();
+        for line in words.chunks(8) {
+            source.push_str("   ");
+            for word in line {
+                write!(source, " {:#010x},", word).unwrap();
+            }
+            source.push('\n');
+        }
+        source.push_str("];\n");
+    }
+    writeln!(source, "\npub mod {} {{", crate_ident.to_lowercase()).unwrap();
+    for entry_point in entry_points {
+        writeln!(
+            source,
+            "    pub const {}: &str = {:?};",
+            to_ident(entry_point).to_uppercase(),
+            entry_point
+        )
+        .unwrap();
+    }
+    source.push_str("}\n");
+    source
+}
+
+/// Writes `source` (see [`generate`]) to `OUT_DIR`, as `<crate_name>.rs`.
+pub(crate) fn write_to_out_dir(crate_name: &str, source: &str) -> Result<(), SpirvBuilderError> {
+    let out_dir = std::env::var_os("OUT_DIR")
+        .map(PathBuf::from)
+        .ok_or(SpirvBuilderError::ShaderModulesWithoutOutDir)?;
+    let path = out_dir.join(format!("{}.rs", crate_name));
+    fs::write(path, source).map_err(SpirvBuilderError::ShaderModulesUnwritable)
+}
+
+/// Turns an entry point (or crate) name into an identifier, replacing anything that can't
+/// appear in one with `_`.
+fn to_ident(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !ident.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        ident.insert(0, '_');
+    }
+    ident
+}
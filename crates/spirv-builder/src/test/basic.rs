@@ -1,4 +1,9 @@
-use super::{dis_entry_fn, dis_fn, dis_globals, val};
+use super::{
+    build, dis_entry_fn, dis_fn, dis_globals, global_lock, read_module, reflect, setup, val,
+};
+use crate::reflect::DescriptorType;
+use crate::{AtomicScope, Capability, SpirvBuilder};
+use rustc_codegen_spirv::rspirv::spirv::{Op, Scope};
 use std::ffi::OsStr;
 
 struct SetEnvVar<'a> {
@@ -500,3 +505,185 @@ OpUnreachable
 OpFunctionEnd"#,
     )
 }
+
+#[test]
+fn reflect_bindings() {
+    let reflection = reflect(
+        r#"
+#[derive(Copy, Clone)]
+pub struct Constants {
+    pub scale: f32,
+    pub offset: glam::Vec2,
+}
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(push_constant)] constants: &Constants,
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] uniform: &glam::Vec4,
+    #[spirv(storage_buffer, descriptor_set = 1, binding = 0)] slice: &mut [f32],
+    #[spirv(descriptor_set = 0, binding = 0)] sampler: &Sampler,
+    output: &mut f32,
+) {
+    let _ = sampler;
+    slice[0] = constants.scale + constants.offset.x + uniform.x;
+    *output = slice[0];
+}
+"#,
+    );
+    let entry = reflection.entry_point("main").unwrap();
+    assert_eq!(entry.execution_model, "Fragment");
+    assert_eq!(entry.inputs.len(), 0);
+    assert_eq!(entry.outputs.len(), 1);
+    assert_eq!(entry.outputs[0].location, Some(0));
+    let bindings = entry
+        .descriptor_bindings
+        .iter()
+        .map(|b| (b.set, b.binding, b.descriptor_type, b.array_size))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bindings,
+        [
+            (0, 0, Some(DescriptorType::Sampler), Some(1)),
+            (0, 1, Some(DescriptorType::UniformBuffer), Some(1)),
+            (1, 0, Some(DescriptorType::StorageBuffer), Some(1)),
+        ]
+    );
+    assert_eq!(entry.push_constants.as_ref().unwrap().size, 12);
+}
+
+#[test]
+fn reflect_not_a_module_path() {
+    assert!(matches!(
+        crate::ModuleReflection::load(".."),
+        Err(crate::SpirvBuilderError::NotAModulePath(_))
+    ));
+}
+
+#[test]
+fn dump_path_with_whitespace() {
+    let result = SpirvBuilder::new("../../target/test-spirv")
+        .dump_stage(crate::LinkStage::PostLink, "dumps/post link.spv")
+        .build();
+    assert!(matches!(
+        result,
+        Err(crate::SpirvBuilderError::DumpPathUnrepresentable(_))
+    ));
+}
+
+#[test]
+fn debuginfo_survives_linking() {
+    let _lock = global_lock();
+    let _var = SetEnvVar::new(&"CARGO_PROFILE_DEV_DEBUG", "2");
+    let module = read_module(&build(
+        r#"
+fn add(x: f32, y: f32) -> f32 {
+    let sum = x + y;
+    sum
+}
+
+#[spirv(fragment)]
+pub fn main(input: f32, output: &mut f32) {
+    *output = add(input, 1.0);
+}
+"#,
+    ))
+    .unwrap();
+    let lib_rs = module
+        .debugs
+        .iter()
+        .find(|inst| {
+            inst.class.opcode == Op::String
+                && inst.operands[0].unwrap_literal_string().ends_with("lib.rs")
+        })
+        .expect("no OpString for lib.rs")
+        .result_id
+        .unwrap();
+    assert!(module.debugs.iter().any(|inst| {
+        inst.class.opcode == Op::Source && inst.operands[2].unwrap_id_ref() == lib_rs
+    }));
+    assert!(module
+        .functions
+        .iter()
+        .flat_map(|func| func.all_inst_iter())
+        .any(|inst| inst.class.opcode == Op::Line && inst.operands[0].unwrap_id_ref() == lib_rs));
+}
+
+/// The `Scope` operand of the `OpAtomicIAdd` in the module built from `src`.
+fn atomic_scope(src: &str, default_scope: Option<AtomicScope>) -> u32 {
+    let _lock = global_lock();
+    let project = setup(src).expect("Failed to set up project");
+    let mut builder = SpirvBuilder::new(&project)
+        .print_metadata(false)
+        .release(false);
+    if let Some(default_scope) = default_scope {
+        builder = builder.atomic_scope(default_scope);
+    }
+    let module = read_module(&builder.build().expect("Failed to build test")).unwrap();
+    let atomic = module
+        .functions
+        .iter()
+        .flat_map(|func| func.all_inst_iter())
+        .find(|inst| inst.class.opcode == Op::AtomicIAdd)
+        .expect("no OpAtomicIAdd");
+    let scope = atomic.operands[1].unwrap_id_ref();
+    module
+        .types_global_values
+        .iter()
+        .find(|inst| inst.result_id == Some(scope))
+        .unwrap()
+        .operands[0]
+        .unwrap_literal_int32()
+}
+
+#[test]
+fn atomic_scopes() {
+    let src = |attr: &str| {
+        format!(
+            r#"
+use core::sync::atomic::{{AtomicU32, Ordering}};
+
+#[spirv(compute(threads(64){}))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [u32; 2]) {{
+    let atomic = unsafe {{ &*(&mut buffer[0] as *mut u32 as *const AtomicU32) }};
+    buffer[1] = atomic.fetch_add(1, Ordering::AcqRel);
+}}
+"#,
+            attr
+        )
+    };
+    assert_eq!(atomic_scope(&src(""), None), Scope::Device as u32);
+    assert_eq!(
+        atomic_scope(&src(""), Some(AtomicScope::QueueFamily)),
+        Scope::QueueFamily as u32
+    );
+    assert_eq!(
+        atomic_scope(
+            &src(r#", atomic_scope = "workgroup""#),
+            Some(AtomicScope::QueueFamily)
+        ),
+        Scope::Workgroup as u32
+    );
+}
+
+#[test]
+fn declared_capability_unused() {
+    let _lock = global_lock();
+    let project = setup(
+        r#"
+#[spirv(fragment)]
+pub fn main() {}
+"#,
+    )
+    .expect("Failed to set up project");
+    let spirv_module = SpirvBuilder::new(&project)
+        .print_metadata(false)
+        .release(false)
+        .capability(Capability::Int64)
+        .build()
+        .expect("Failed to build test");
+    let module = read_module(&spirv_module).unwrap();
+    assert!(!module
+        .capabilities
+        .iter()
+        .any(|inst| inst.operands[0].unwrap_capability() == Capability::Int64));
+}
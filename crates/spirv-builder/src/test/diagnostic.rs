@@ -0,0 +1,33 @@
+use crate::{parse_cargo_output, DiagnosticLevel, DiagnosticSpan};
+use std::path::PathBuf;
+
+#[test]
+fn parse_compiler_messages() {
+    let stdout = r#"{"reason":"compiler-message","package_id":"shader 0.1.0 (path+file:///shader)","target":{"kind":["dylib"],"crate_types":["dylib"],"name":"shader","src_path":"/shader/src/lib.rs","edition":"2018","doctest":false,"test":true},"message":{"rendered":"error[E0308]: mismatched types\n","children":[],"code":{"code":"E0308","explanation":null},"level":"error","message":"mismatched types","spans":[{"byte_end":50,"byte_start":45,"column_end":18,"column_start":13,"expansion":null,"file_name":"src/lib.rs","is_primary":true,"label":"expected `u32`, found `f32`","line_end":3,"line_start":3,"suggested_replacement":null,"suggestion_applicability":null,"text":[]}]}}
+not json
+{"reason":"build-finished","success":false}"#;
+    let (artifact, diagnostics) = parse_cargo_output(stdout);
+    assert_eq!(artifact, None);
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.level, DiagnosticLevel::Error);
+    assert_eq!(diagnostic.message, "mismatched types");
+    assert_eq!(diagnostic.code.as_deref(), Some("E0308"));
+    assert_eq!(
+        diagnostic.rendered.as_deref(),
+        Some("error[E0308]: mismatched types\n")
+    );
+    assert_eq!(
+        diagnostic.span,
+        Some(DiagnosticSpan {
+            // `/shader/src/lib.rs` doesn't exist, so the path stays relative.
+            file: PathBuf::from("src/lib.rs"),
+            byte_start: 45,
+            byte_end: 50,
+            line_start: 3,
+            line_end: 3,
+            column_start: 13,
+            column_end: 18,
+        })
+    );
+}
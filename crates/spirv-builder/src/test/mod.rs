@@ -1,4 +1,7 @@
 mod basic;
+mod diagnostic;
+mod shader_modules;
+mod watch;
 
 use lazy_static::lazy_static;
 use rustc_codegen_spirv::rspirv;
@@ -44,6 +47,8 @@ crate-type = ["dylib"]
 [profile.dev]
 overflow-checks = false
 debug-assertions = false
+# Keep `OpLine`s out of the disassembly the tests compare against.
+debug = false
 
 [dependencies]
 spirv-std = { path = "../../crates/spirv-std", features=["const-generics"] }
@@ -99,6 +104,11 @@ fn val(src: &str) {
     build(src);
 }
 
+fn reflect(src: &str) -> crate::ModuleReflection {
+    let _lock = global_lock();
+    crate::ModuleReflection::load(build(src)).expect("Failed to load reflection")
+}
+
 fn assert_str_eq(expected: &str, result: &str) {
     let expected = expected
         .split('\n')
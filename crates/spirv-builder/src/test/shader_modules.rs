@@ -0,0 +1,57 @@
+use super::PrettyString;
+use crate::shader_modules::generate;
+
+#[test]
+fn generate_single_module() {
+    let source = generate(
+        "sky_shader",
+        &[(None, vec![0x0723_0203, 0x0001_0000, 0, 1, 2, 3, 4, 5, 6])],
+        &["main_fs", "main_vs"],
+    );
+    assert_eq!(
+        PrettyString(&source),
+        PrettyString(
+            r#"// Generated by spirv-builder from the `sky_shader` shader crate.
+
+pub const SKY_SHADER: &[u32] = &[
+    0x07230203, 0x00010000, 0x00000000, 0x00000001, 0x00000002, 0x00000003, 0x00000004, 0x00000005,
+    0x00000006,
+];
+
+pub mod sky_shader {
+    pub const MAIN_FS: &str = "main_fs";
+    pub const MAIN_VS: &str = "main_vs";
+}
+"#
+        )
+    );
+}
+
+#[test]
+fn generate_multimodule() {
+    let source = generate(
+        "shader",
+        &[(Some("main_cs"), vec![1]), (Some("2::fs"), vec![2])],
+        &["main_cs", "2::fs"],
+    );
+    assert_eq!(
+        PrettyString(&source),
+        PrettyString(
+            r#"// Generated by spirv-builder from the `shader` shader crate.
+
+pub const SHADER_MAIN_CS: &[u32] = &[
+    0x00000001,
+];
+
+pub const SHADER__2__FS: &[u32] = &[
+    0x00000002,
+];
+
+pub mod shader {
+    pub const MAIN_CS: &str = "main_cs";
+    pub const _2__FS: &str = "2::fs";
+}
+"#
+        )
+    );
+}
@@ -0,0 +1,106 @@
+use crate::deps_of;
+use crate::watch::{crate_files, wait_for_changes};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// An empty directory for the test `name`, under the system temp dir.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("spirv-builder-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn touch(path: &Path) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, "").unwrap();
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[test]
+fn crate_files_lists_manifest_and_sources() {
+    let dir = temp_dir("crate-files");
+    for file in &["Cargo.toml", "build.rs", "src/lib.rs", "src/nested/mod.rs"] {
+        touch(&dir.join(file));
+    }
+    assert_eq!(
+        crate_files(&dir),
+        [
+            dir.join("Cargo.toml"),
+            dir.join("src/lib.rs"),
+            dir.join("src/nested/mod.rs"),
+        ]
+    );
+}
+
+#[test]
+fn deps_of_reads_dep_file() {
+    let dir = temp_dir("deps-of");
+    let artifact = dir.join("shader.spv");
+    let dep_file = format!(
+        "{0}/shader.spv: {0}/src/lib.rs {0}/src/with\\ space.rs\n",
+        dir.display()
+    );
+    fs::write(dir.join("shader.d"), dep_file).unwrap();
+    let deps: Vec<String> = deps_of(&artifact)
+        .unwrap()
+        .iter()
+        .map(|dep| dep.to_string())
+        .collect();
+    assert_eq!(
+        deps,
+        [
+            format!("{}/src/lib.rs", dir.display()),
+            format!("{}/src/with space.rs", dir.display()),
+        ]
+    );
+}
+
+#[test]
+fn wait_for_changes_sees_edits_between_polls() {
+    let dir = temp_dir("wait-for-edits");
+    let file = dir.join("src/lib.rs");
+    touch(&file);
+    let initial = modified(&file);
+    let (_stop, stopped) = mpsc::channel();
+    let editor = {
+        let file = file.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            // Modification times can be coarse, so keep editing until it changes.
+            while modified(&file) == initial {
+                fs::write(&file, "// edited").unwrap();
+                thread::sleep(Duration::from_millis(50));
+            }
+        })
+    };
+    assert!(wait_for_changes(&[file], vec![initial], &stopped));
+    editor.join().unwrap();
+}
+
+#[test]
+fn wait_for_changes_sees_edits_before_waiting() {
+    let dir = temp_dir("wait-for-earlier-edits");
+    let file = dir.join("src/lib.rs");
+    touch(&file);
+    let (_stop, stopped) = mpsc::channel();
+    // As if the file was created after the snapshot before the build was taken.
+    assert!(wait_for_changes(&[file], vec![None], &stopped));
+}
+
+#[test]
+fn wait_for_changes_stops_with_watcher() {
+    let dir = temp_dir("wait-for-stop");
+    let file = dir.join("src/lib.rs");
+    touch(&file);
+    let (stop, stopped) = mpsc::channel();
+    drop(stop);
+    let initial = vec![modified(&file)];
+    assert!(!wait_for_changes(&[file], initial, &stopped));
+}
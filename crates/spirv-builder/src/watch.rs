@@ -0,0 +1,126 @@
+//! Rebuilding shader crates whenever their sources change, for [`SpirvBuilder::watch`].
+//!
+//! Files are watched by polling their modification times, which avoids depending on
+//! platform-specific notification APIs, and is cheap for the handful of files a shader
+//! crate is made of.
+
+use crate::{CompileResult, SpirvBuilder, SpirvBuilderError};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often the sources are checked for changes, and how long they have to stay unchanged
+/// before rebuilding (editors often write files in several steps).
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Watches a shader crate for changes, returned by [`SpirvBuilder::watch`].
+///
+/// Dropping it stops watching.
+pub struct Watcher {
+    _stop: mpsc::Sender<()>,
+}
+
+pub(crate) fn spawn(
+    builder: SpirvBuilder,
+    multimodule: bool,
+    mut on_build: impl FnMut(Result<CompileResult, SpirvBuilderError>) + Send + 'static,
+) -> Result<Watcher, SpirvBuilderError> {
+    let (stop, stopped) = mpsc::channel::<()>();
+    thread::Builder::new()
+        .name("spirv-builder watch".to_string())
+        .spawn(move || {
+            // After a failed build, keep watching the sources of the last successful one,
+            // or, if there wasn't any, everything the crate could be built from.
+            let watched = |sources: &[PathBuf]| {
+                if sources.is_empty() {
+                    crate_files(&builder.path_to_crate)
+                } else {
+                    sources.to_vec()
+                }
+            };
+            let mut sources = Vec::new();
+            loop {
+                // Taken before building, so that edits made during the build aren't missed.
+                let before_build: HashMap<PathBuf, Option<SystemTime>> = {
+                    let files = watched(&sources);
+                    files.iter().cloned().zip(modified_times(&files)).collect()
+                };
+
+                let result =
+                    builder
+                        .build_compile_result(multimodule)
+                        .map(|(result, new_sources)| {
+                            sources = new_sources;
+                            result
+                        });
+                on_build(result);
+
+                let files = watched(&sources);
+                let initial = files
+                    .iter()
+                    .zip(modified_times(&files))
+                    .map(|(file, now)| before_build.get(file).copied().unwrap_or(now))
+                    .collect();
+                if !wait_for_changes(&files, initial, &stopped) {
+                    return;
+                }
+            }
+        })
+        .map_err(SpirvBuilderError::WatchThreadUnspawnable)?;
+    Ok(Watcher { _stop: stop })
+}
+
+/// Blocks until any of `files` differs from its `initial` modification time (and then stays
+/// unchanged for a [`POLL_INTERVAL`]), returning `false` instead if the [`Watcher`] was dropped
+/// in the meantime.
+pub(crate) fn wait_for_changes(
+    files: &[PathBuf],
+    initial: Vec<Option<SystemTime>>,
+    stopped: &mpsc::Receiver<()>,
+) -> bool {
+    let mut changed: Option<Vec<Option<SystemTime>>> = None;
+    loop {
+        match stopped.recv_timeout(POLL_INTERVAL) {
+            Err(RecvTimeoutError::Timeout) => {}
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => return false,
+        }
+        let current = modified_times(files);
+        match changed {
+            Some(ref previous) if *previous == current => return true,
+            _ if current != initial => changed = Some(current),
+            _ => changed = None,
+        }
+    }
+}
+
+fn modified_times(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| fs::metadata(file).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+/// The files under `src` of the crate at `path_to_crate`, and its `Cargo.toml`.
+pub(crate) fn crate_files(path_to_crate: &Path) -> Vec<PathBuf> {
+    fn recurse(dir: &Path, files: &mut Vec<PathBuf>) {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                recurse(&path, files);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    let mut files = vec![path_to_crate.join("Cargo.toml")];
+    recurse(&path_to_crate.join("src"), &mut files);
+    files.sort();
+    files
+}
@@ -7,14 +7,23 @@ use crate::{scalar::Scalar, vector::Vector};
 
 mod arithmetic;
 #[cfg(feature = "const-generics")]
+mod atomics;
+#[cfg(feature = "const-generics")]
 mod barrier;
 mod derivative;
+mod mesh_shading;
+mod non_uniform;
 mod primitive;
+pub mod subgroup;
 
 pub use arithmetic::*;
 #[cfg(feature = "const-generics")]
+pub use atomics::*;
+#[cfg(feature = "const-generics")]
 pub use barrier::*;
 pub use derivative::*;
+pub use mesh_shading::*;
+pub use non_uniform::*;
 pub use primitive::*;
 
 /// Result is true if any component of `vector` is true, otherwise result is
@@ -0,0 +1,22 @@
+/// Writes four 8-bit vertex indices, packed into `packed_indices`, into the
+/// `primitive_indices_nv` output of a mesh shader, starting at
+/// `index_offset`. `index_offset` must be a multiple of 4.
+///
+/// The number of primitives output is set by writing to the
+/// `primitive_count_nv` builtin.
+///
+/// # Safety
+/// Must only be used in mesh shaders, with `index_offset + 3` in bounds of
+/// the `primitive_indices_nv` output array.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpWritePackedPrimitiveIndices4x8NV")]
+#[inline]
+pub unsafe fn write_packed_primitive_indices_4x8_nv(index_offset: u32, packed_indices: u32) {
+    asm! {
+        "%index_offset = OpLoad _ {index_offset}",
+        "%packed_indices = OpLoad _ {packed_indices}",
+        "OpWritePackedPrimitiveIndices4x8NV %index_offset %packed_indices",
+        index_offset = in(reg) &index_offset,
+        packed_indices = in(reg) &packed_indices,
+    }
+}
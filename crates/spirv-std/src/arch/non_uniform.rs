@@ -0,0 +1,29 @@
+use crate::integer::Integer;
+
+/// Marks `index` as not being dynamically uniform (i.e. possibly differing
+/// between invocations), which is required when using it to index into a
+/// descriptor array (e.g. `&[Image2d]`).
+///
+/// The compiler propagates the `NonUniform` decoration from the result to the
+/// descriptors accessed with it, and declares the `ShaderNonUniform`
+/// capability, along with the capability for non-uniformly indexing into each
+/// kind of descriptor array.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "NonUniform")]
+#[inline]
+pub fn non_uniform<T: Integer>(index: T) -> T {
+    let mut result = T::default();
+
+    unsafe {
+        asm! {
+            "%index = OpLoad _ {index}",
+            "%result = OpCopyObject _ %index",
+            "OpDecorate %result NonUniform",
+            "OpStore {result} %result",
+            index = in(reg) &index,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
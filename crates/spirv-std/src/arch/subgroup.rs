@@ -0,0 +1,1013 @@
+//! Subgroup (also known as "wave" or "warp") operations, mapping to the
+//! `OpGroupNonUniform*` family of instructions.
+//!
+//! All operations here use [`crate::memory::Scope::Subgroup`] as their
+//! execution scope, and only take the currently active invocations of the
+//! subgroup into account.
+use crate::{
+    float::Float,
+    integer::{Integer, SignedInteger, UnsignedInteger},
+    scalar::Scalar,
+    vector::Vector,
+};
+
+#[cfg(target_arch = "spirv")]
+const SUBGROUP: u32 = crate::memory::Scope::Subgroup as u32;
+
+#[cfg(target_arch = "spirv")]
+macro_rules! subgroup_fn {
+    ($capability:ident, $value:ident, $inst:ident $(, $group_op:ident)?) => {
+        unsafe {
+            let mut result = Default::default();
+            asm!(
+                "OpCapability GroupNonUniform",
+                concat!("OpCapability ", stringify!($capability)),
+                "%u32 = OpTypeInt 32 0",
+                "%subgroup = OpConstant %u32 {subgroup}",
+                "%value = OpLoad _ {value}",
+                concat!(
+                    "%result = ",
+                    stringify!($inst),
+                    " _ %subgroup ",
+                    $(stringify!($group_op), " ",)?
+                    "%value"
+                ),
+                "OpStore {result} %result",
+                subgroup = const SUBGROUP,
+                value = in(reg) &$value,
+                result = in(reg) &mut result,
+            );
+            result
+        }
+    };
+}
+
+/// Result is `true` only in the active invocation with the lowest id in the
+/// subgroup, otherwise result is `false`.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformElect")]
+#[inline]
+pub fn subgroup_elect() -> bool {
+    let mut result = false;
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "%bool = OpTypeBool",
+            "%u8 = OpTypeInt 8 0",
+            "%u8_0 = OpConstant %u8 0",
+            "%u8_1 = OpConstant %u8 1",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%result = OpGroupNonUniformElect %bool %subgroup",
+            "%boolean = OpSelect %u8 %result %u8_1 %u8_0",
+            "OpStore {result} %boolean",
+            subgroup = const SUBGROUP,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Evaluates `predicate` for all active invocations in the subgroup. Result is
+/// `true` if `predicate` evaluates to `true` for all of them, otherwise result
+/// is `false`.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformAll")]
+#[inline]
+pub fn subgroup_all(predicate: bool) -> bool {
+    let mut result = false;
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformVote",
+            "%bool = OpTypeBool",
+            "%u8 = OpTypeInt 8 0",
+            "%u8_0 = OpConstant %u8 0",
+            "%u8_1 = OpConstant %u8 1",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%predicate = OpLoad %u8 {predicate}",
+            "%predicate_bool = OpINotEqual %bool %predicate %u8_0",
+            "%result = OpGroupNonUniformAll %bool %subgroup %predicate_bool",
+            "%boolean = OpSelect %u8 %result %u8_1 %u8_0",
+            "OpStore {result} %boolean",
+            subgroup = const SUBGROUP,
+            predicate = in(reg) &predicate,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Evaluates `predicate` for all active invocations in the subgroup. Result is
+/// `true` if `predicate` evaluates to `true` for any of them, otherwise result
+/// is `false`.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformAny")]
+#[inline]
+pub fn subgroup_any(predicate: bool) -> bool {
+    let mut result = false;
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformVote",
+            "%bool = OpTypeBool",
+            "%u8 = OpTypeInt 8 0",
+            "%u8_0 = OpConstant %u8 0",
+            "%u8_1 = OpConstant %u8 1",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%predicate = OpLoad %u8 {predicate}",
+            "%predicate_bool = OpINotEqual %bool %predicate %u8_0",
+            "%result = OpGroupNonUniformAny %bool %subgroup %predicate_bool",
+            "%boolean = OpSelect %u8 %result %u8_1 %u8_0",
+            "OpStore {result} %boolean",
+            subgroup = const SUBGROUP,
+            predicate = in(reg) &predicate,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Evaluates `value` for all active invocations in the subgroup. Result is
+/// `true` if `value` is equal for all of them, otherwise result is `false`.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformAllEqual")]
+#[inline]
+pub fn subgroup_all_equal<T: Scalar>(value: T) -> bool {
+    let mut result = false;
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformVote",
+            "%bool = OpTypeBool",
+            "%u8 = OpTypeInt 8 0",
+            "%u8_0 = OpConstant %u8 0",
+            "%u8_1 = OpConstant %u8 1",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%value = OpLoad _ {value}",
+            "%result = OpGroupNonUniformAllEqual %bool %subgroup %value",
+            "%boolean = OpSelect %u8 %result %u8_1 %u8_0",
+            "OpStore {result} %boolean",
+            subgroup = const SUBGROUP,
+            value = in(reg) &value,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Like [`subgroup_all_equal`], but compares whole vectors.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformAllEqual")]
+#[inline]
+pub fn subgroup_all_equal_vector<T: Scalar, V: Vector<T, N>, const N: usize>(value: V) -> bool {
+    let mut result = false;
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformVote",
+            "%bool = OpTypeBool",
+            "%u8 = OpTypeInt 8 0",
+            "%u8_0 = OpConstant %u8 0",
+            "%u8_1 = OpConstant %u8 1",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%value = OpLoad _ {value}",
+            "%result = OpGroupNonUniformAllEqual %bool %subgroup %value",
+            "%boolean = OpSelect %u8 %result %u8_1 %u8_0",
+            "OpStore {result} %boolean",
+            subgroup = const SUBGROUP,
+            value = in(reg) &value,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Returns a bitfield of 4 `u32`s, where bit `i` (counting from the lowest bit
+/// of the first component) is set if `predicate` is `true` for the active
+/// invocation with subgroup id `i`.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBallot")]
+#[inline]
+pub fn subgroup_ballot<V: Vector<u32, 4>>(predicate: bool) -> V {
+    let mut result = V::default();
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformBallot",
+            "%bool = OpTypeBool",
+            "%u8 = OpTypeInt 8 0",
+            "%u8_0 = OpConstant %u8 0",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%predicate = OpLoad %u8 {predicate}",
+            "%predicate_bool = OpINotEqual %bool %predicate %u8_0",
+            "%result = OpGroupNonUniformBallot typeof*{result} %subgroup %predicate_bool",
+            "OpStore {result} %result",
+            subgroup = const SUBGROUP,
+            predicate = in(reg) &predicate,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Returns the number of bits set in `ballot`, only considering the bits of
+/// invocations that are within the subgroup.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBallotBitCount")]
+#[inline]
+pub fn subgroup_ballot_bit_count<V: Vector<u32, 4>>(ballot: V) -> u32 {
+    let mut result = 0;
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformBallot",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%ballot = OpLoad _ {ballot}",
+            "%result = OpGroupNonUniformBallotBitCount %u32 %subgroup Reduce %ballot",
+            "OpStore {result} %result",
+            subgroup = const SUBGROUP,
+            ballot = in(reg) &ballot,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Returns the value of `component` from the invocation with subgroup id
+/// `ID`. `ID` must identify an active invocation, otherwise the result is
+/// undefined.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBroadcast")]
+#[inline]
+pub fn subgroup_broadcast<T: Scalar, const ID: u32>(component: T) -> T {
+    let mut result = T::default();
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformBallot",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%id = OpConstant %u32 {id}",
+            "%value = OpLoad _ {value}",
+            "%result = OpGroupNonUniformBroadcast _ %subgroup %value %id",
+            "OpStore {result} %result",
+            subgroup = const SUBGROUP,
+            id = const ID,
+            value = in(reg) &component,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Vector version of [`subgroup_broadcast`].
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBroadcast")]
+#[inline]
+pub fn subgroup_broadcast_vector<T: Scalar, V: Vector<T, N>, const N: usize, const ID: u32>(
+    component: V,
+) -> V {
+    let mut result = V::default();
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformBallot",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%id = OpConstant %u32 {id}",
+            "%value = OpLoad _ {value}",
+            "%result = OpGroupNonUniformBroadcast _ %subgroup %value %id",
+            "OpStore {result} %result",
+            subgroup = const SUBGROUP,
+            id = const ID,
+            value = in(reg) &component,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Returns the value of `component` from the active invocation with the
+/// lowest subgroup id.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBroadcastFirst")]
+#[inline]
+pub fn subgroup_broadcast_first<T: Scalar>(component: T) -> T {
+    subgroup_fn!(
+        GroupNonUniformBallot,
+        component,
+        OpGroupNonUniformBroadcastFirst
+    )
+}
+
+#[cfg(target_arch = "spirv")]
+macro_rules! shuffle_fn {
+    ($capability:ident, $value:ident, $inst:ident, $operand:ident) => {
+        unsafe {
+            let mut result = Default::default();
+            asm!(
+                "OpCapability GroupNonUniform",
+                concat!("OpCapability ", stringify!($capability)),
+                "%u32 = OpTypeInt 32 0",
+                "%subgroup = OpConstant %u32 {subgroup}",
+                "%value = OpLoad _ {value}",
+                concat!(
+                    "%result = ",
+                    stringify!($inst),
+                    " _ %subgroup %value {operand}"
+                ),
+                "OpStore {result} %result",
+                subgroup = const SUBGROUP,
+                value = in(reg) &$value,
+                operand = in(reg) $operand,
+                result = in(reg) &mut result,
+            );
+            result
+        }
+    };
+}
+
+/// Returns the value of `component` from the invocation with subgroup id
+/// `id`. The result is undefined if that invocation is inactive, or if `id`
+/// is greater than or equal to the subgroup size.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformShuffle")]
+#[inline]
+pub fn subgroup_shuffle<T: Scalar>(component: T, id: u32) -> T {
+    shuffle_fn!(
+        GroupNonUniformShuffle,
+        component,
+        OpGroupNonUniformShuffle,
+        id
+    )
+}
+
+/// Returns the value of `component` from the invocation whose subgroup id is
+/// the current invocation's subgroup id XOR'd with `mask`.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformShuffleXor")]
+#[inline]
+pub fn subgroup_shuffle_xor<T: Scalar>(component: T, mask: u32) -> T {
+    shuffle_fn!(
+        GroupNonUniformShuffle,
+        component,
+        OpGroupNonUniformShuffleXor,
+        mask
+    )
+}
+
+/// Returns the value of `component` from the invocation whose subgroup id is
+/// the current invocation's subgroup id minus `delta`.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformShuffleUp")]
+#[inline]
+pub fn subgroup_shuffle_up<T: Scalar>(component: T, delta: u32) -> T {
+    shuffle_fn!(
+        GroupNonUniformShuffleRelative,
+        component,
+        OpGroupNonUniformShuffleUp,
+        delta
+    )
+}
+
+/// Returns the value of `component` from the invocation whose subgroup id is
+/// the current invocation's subgroup id plus `delta`.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformShuffleDown")]
+#[inline]
+pub fn subgroup_shuffle_down<T: Scalar>(component: T, delta: u32) -> T {
+    shuffle_fn!(
+        GroupNonUniformShuffleRelative,
+        component,
+        OpGroupNonUniformShuffleDown,
+        delta
+    )
+}
+
+/// Integer addition of `component` across all active invocations of the
+/// subgroup.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformIAdd")]
+#[inline]
+pub fn subgroup_i_add<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformIAdd,
+        Reduce
+    )
+}
+
+/// Inclusive scan of [`subgroup_i_add`]: the result for each invocation
+/// includes `component` of all active invocations with a lower or equal
+/// subgroup id.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformIAdd")]
+#[inline]
+pub fn subgroup_inclusive_i_add<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformIAdd,
+        InclusiveScan
+    )
+}
+
+/// Exclusive scan of [`subgroup_i_add`]: the result for each invocation
+/// includes `component` of all active invocations with a lower subgroup id.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformIAdd")]
+#[inline]
+pub fn subgroup_exclusive_i_add<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformIAdd,
+        ExclusiveScan
+    )
+}
+
+/// Floating-point addition of `component` across all active invocations of
+/// the subgroup. The order of the additions is unspecified.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformFAdd")]
+#[inline]
+pub fn subgroup_f_add<F: Float>(component: F) -> F {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformFAdd,
+        Reduce
+    )
+}
+
+/// Inclusive scan of [`subgroup_f_add`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformFAdd")]
+#[inline]
+pub fn subgroup_inclusive_f_add<F: Float>(component: F) -> F {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformFAdd,
+        InclusiveScan
+    )
+}
+
+/// Exclusive scan of [`subgroup_f_add`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformFAdd")]
+#[inline]
+pub fn subgroup_exclusive_f_add<F: Float>(component: F) -> F {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformFAdd,
+        ExclusiveScan
+    )
+}
+
+/// Signed integer minimum of `component` across all active invocations of
+/// the subgroup.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformSMin")]
+#[inline]
+pub fn subgroup_s_min<S: SignedInteger>(component: S) -> S {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformSMin,
+        Reduce
+    )
+}
+
+/// Inclusive scan of [`subgroup_s_min`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformSMin")]
+#[inline]
+pub fn subgroup_inclusive_s_min<S: SignedInteger>(component: S) -> S {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformSMin,
+        InclusiveScan
+    )
+}
+
+/// Exclusive scan of [`subgroup_s_min`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformSMin")]
+#[inline]
+pub fn subgroup_exclusive_s_min<S: SignedInteger>(component: S) -> S {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformSMin,
+        ExclusiveScan
+    )
+}
+
+/// Unsigned integer minimum of `component` across all active invocations of
+/// the subgroup.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformUMin")]
+#[inline]
+pub fn subgroup_u_min<U: UnsignedInteger>(component: U) -> U {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformUMin,
+        Reduce
+    )
+}
+
+/// Inclusive scan of [`subgroup_u_min`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformUMin")]
+#[inline]
+pub fn subgroup_inclusive_u_min<U: UnsignedInteger>(component: U) -> U {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformUMin,
+        InclusiveScan
+    )
+}
+
+/// Exclusive scan of [`subgroup_u_min`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformUMin")]
+#[inline]
+pub fn subgroup_exclusive_u_min<U: UnsignedInteger>(component: U) -> U {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformUMin,
+        ExclusiveScan
+    )
+}
+
+/// Floating-point minimum of `component` across all active invocations of
+/// the subgroup.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformFMin")]
+#[inline]
+pub fn subgroup_f_min<F: Float>(component: F) -> F {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformFMin,
+        Reduce
+    )
+}
+
+/// Inclusive scan of [`subgroup_f_min`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformFMin")]
+#[inline]
+pub fn subgroup_inclusive_f_min<F: Float>(component: F) -> F {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformFMin,
+        InclusiveScan
+    )
+}
+
+/// Exclusive scan of [`subgroup_f_min`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformFMin")]
+#[inline]
+pub fn subgroup_exclusive_f_min<F: Float>(component: F) -> F {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformFMin,
+        ExclusiveScan
+    )
+}
+
+/// Signed integer maximum of `component` across all active invocations of
+/// the subgroup.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformSMax")]
+#[inline]
+pub fn subgroup_s_max<S: SignedInteger>(component: S) -> S {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformSMax,
+        Reduce
+    )
+}
+
+/// Inclusive scan of [`subgroup_s_max`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformSMax")]
+#[inline]
+pub fn subgroup_inclusive_s_max<S: SignedInteger>(component: S) -> S {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformSMax,
+        InclusiveScan
+    )
+}
+
+/// Exclusive scan of [`subgroup_s_max`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformSMax")]
+#[inline]
+pub fn subgroup_exclusive_s_max<S: SignedInteger>(component: S) -> S {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformSMax,
+        ExclusiveScan
+    )
+}
+
+/// Unsigned integer maximum of `component` across all active invocations of
+/// the subgroup.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformUMax")]
+#[inline]
+pub fn subgroup_u_max<U: UnsignedInteger>(component: U) -> U {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformUMax,
+        Reduce
+    )
+}
+
+/// Inclusive scan of [`subgroup_u_max`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformUMax")]
+#[inline]
+pub fn subgroup_inclusive_u_max<U: UnsignedInteger>(component: U) -> U {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformUMax,
+        InclusiveScan
+    )
+}
+
+/// Exclusive scan of [`subgroup_u_max`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformUMax")]
+#[inline]
+pub fn subgroup_exclusive_u_max<U: UnsignedInteger>(component: U) -> U {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformUMax,
+        ExclusiveScan
+    )
+}
+
+/// Floating-point maximum of `component` across all active invocations of
+/// the subgroup.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformFMax")]
+#[inline]
+pub fn subgroup_f_max<F: Float>(component: F) -> F {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformFMax,
+        Reduce
+    )
+}
+
+/// Inclusive scan of [`subgroup_f_max`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformFMax")]
+#[inline]
+pub fn subgroup_inclusive_f_max<F: Float>(component: F) -> F {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformFMax,
+        InclusiveScan
+    )
+}
+
+/// Exclusive scan of [`subgroup_f_max`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformFMax")]
+#[inline]
+pub fn subgroup_exclusive_f_max<F: Float>(component: F) -> F {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformFMax,
+        ExclusiveScan
+    )
+}
+
+/// Bitwise AND of `component` across all active invocations of the subgroup.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBitwiseAnd")]
+#[inline]
+pub fn subgroup_and<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformBitwiseAnd,
+        Reduce
+    )
+}
+
+/// Inclusive scan of [`subgroup_and`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBitwiseAnd")]
+#[inline]
+pub fn subgroup_inclusive_and<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformBitwiseAnd,
+        InclusiveScan
+    )
+}
+
+/// Exclusive scan of [`subgroup_and`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBitwiseAnd")]
+#[inline]
+pub fn subgroup_exclusive_and<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformBitwiseAnd,
+        ExclusiveScan
+    )
+}
+
+/// Bitwise OR of `component` across all active invocations of the subgroup.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBitwiseOr")]
+#[inline]
+pub fn subgroup_or<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformBitwiseOr,
+        Reduce
+    )
+}
+
+/// Inclusive scan of [`subgroup_or`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBitwiseOr")]
+#[inline]
+pub fn subgroup_inclusive_or<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformBitwiseOr,
+        InclusiveScan
+    )
+}
+
+/// Exclusive scan of [`subgroup_or`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBitwiseOr")]
+#[inline]
+pub fn subgroup_exclusive_or<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformBitwiseOr,
+        ExclusiveScan
+    )
+}
+
+/// Bitwise XOR of `component` across all active invocations of the subgroup.
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBitwiseXor")]
+#[inline]
+pub fn subgroup_xor<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformBitwiseXor,
+        Reduce
+    )
+}
+
+/// Inclusive scan of [`subgroup_xor`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBitwiseXor")]
+#[inline]
+pub fn subgroup_inclusive_xor<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformBitwiseXor,
+        InclusiveScan
+    )
+}
+
+/// Exclusive scan of [`subgroup_xor`].
+#[spirv_std_macros::vectorized]
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformBitwiseXor")]
+#[inline]
+pub fn subgroup_exclusive_xor<I: Integer>(component: I) -> I {
+    subgroup_fn!(
+        GroupNonUniformArithmetic,
+        component,
+        OpGroupNonUniformBitwiseXor,
+        ExclusiveScan
+    )
+}
+
+/// Returns the value of `component` from the invocation within the quad with
+/// quad index `INDEX`. `INDEX` must be less than 4.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformQuadBroadcast")]
+#[inline]
+pub fn subgroup_quad_broadcast<T: Scalar, const INDEX: u32>(component: T) -> T {
+    let mut result = T::default();
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformQuad",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%index = OpConstant %u32 {index}",
+            "%value = OpLoad _ {value}",
+            "%result = OpGroupNonUniformQuadBroadcast _ %subgroup %value %index",
+            "OpStore {result} %result",
+            subgroup = const SUBGROUP,
+            index = const INDEX,
+            value = in(reg) &component,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Vector version of [`subgroup_quad_broadcast`].
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformQuadBroadcast")]
+#[inline]
+pub fn subgroup_quad_broadcast_vector<
+    T: Scalar,
+    V: Vector<T, N>,
+    const N: usize,
+    const INDEX: u32,
+>(
+    component: V,
+) -> V {
+    let mut result = V::default();
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformQuad",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%index = OpConstant %u32 {index}",
+            "%value = OpLoad _ {value}",
+            "%result = OpGroupNonUniformQuadBroadcast _ %subgroup %value %index",
+            "OpStore {result} %result",
+            subgroup = const SUBGROUP,
+            index = const INDEX,
+            value = in(reg) &component,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Swaps `component` with another invocation within the quad. `DIRECTION`
+/// selects the invocation to swap with: `0` swaps horizontally, `1` swaps
+/// vertically and `2` swaps diagonally.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformQuadSwap")]
+#[inline]
+pub fn subgroup_quad_swap<T: Scalar, const DIRECTION: u32>(component: T) -> T {
+    let mut result = T::default();
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformQuad",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%direction = OpConstant %u32 {direction}",
+            "%value = OpLoad _ {value}",
+            "%result = OpGroupNonUniformQuadSwap _ %subgroup %value %direction",
+            "OpStore {result} %result",
+            subgroup = const SUBGROUP,
+            direction = const DIRECTION,
+            value = in(reg) &component,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
+
+/// Vector version of [`subgroup_quad_swap`].
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpGroupNonUniformQuadSwap")]
+#[inline]
+pub fn subgroup_quad_swap_vector<
+    T: Scalar,
+    V: Vector<T, N>,
+    const N: usize,
+    const DIRECTION: u32,
+>(
+    component: V,
+) -> V {
+    let mut result = V::default();
+
+    unsafe {
+        asm! {
+            "OpCapability GroupNonUniform",
+            "OpCapability GroupNonUniformQuad",
+            "%u32 = OpTypeInt 32 0",
+            "%subgroup = OpConstant %u32 {subgroup}",
+            "%direction = OpConstant %u32 {direction}",
+            "%value = OpLoad _ {value}",
+            "%result = OpGroupNonUniformQuadSwap _ %subgroup %value %direction",
+            "OpStore {result} %result",
+            subgroup = const SUBGROUP,
+            direction = const DIRECTION,
+            value = in(reg) &component,
+            result = in(reg) &mut result,
+        }
+    }
+
+    result
+}
@@ -78,13 +78,17 @@ pub mod arch;
 pub mod float;
 pub mod integer;
 pub mod memory;
+pub mod number;
+pub mod ray_tracing;
 pub mod scalar;
 pub(crate) mod sealed;
 mod textures;
+mod typed_buffer;
 pub mod vector;
 
 pub use num_traits;
 pub use textures::*;
+pub use typed_buffer::*;
 
 /// Calls the `OpDemoteToHelperInvocationEXT` instruction, which corresponds to discard() in HLSL
 #[spirv_std_macros::gpu_only]
@@ -0,0 +1,696 @@
+//! Ray tracing (`SPV_KHR_ray_tracing`) types and intrinsics.
+use crate::vector::Vector;
+
+/// An acceleration structure that rays can be traced against, as provided by
+/// the host in a descriptor set.
+#[spirv(acceleration_structure)]
+#[derive(Copy, Clone)]
+pub struct AccelerationStructure {
+    pub(crate) _private: u32,
+}
+
+impl AccelerationStructure {
+    /// Traces a ray into this acceleration structure.
+    ///
+    /// `payload` must point to a variable in the `ray_payload_khr` storage
+    /// class, which the invoked hit and miss shaders can read from and write
+    /// to through their `incoming_ray_payload_khr` parameter.
+    ///
+    /// # Safety
+    /// The shader binding table offset, stride and miss index must be valid
+    /// for the bound pipeline, and `payload` must be a ray payload variable.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpTraceRayKHR")]
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn trace_ray<T>(
+        &self,
+        ray_flags: RayFlags,
+        cull_mask: i32,
+        sbt_offset: i32,
+        sbt_stride: i32,
+        miss_index: i32,
+        ray_origin: impl Vector<f32, 3>,
+        ray_tmin: f32,
+        ray_direction: impl Vector<f32, 3>,
+        ray_tmax: f32,
+        payload: &mut T,
+    ) {
+        asm! {
+            "OpExtension \"SPV_KHR_ray_tracing\"",
+            "OpCapability RayTracingKHR",
+            "%acceleration_structure = OpLoad _ {acceleration_structure}",
+            "%ray_flags = OpLoad _ {ray_flags}",
+            "%cull_mask = OpLoad _ {cull_mask}",
+            "%sbt_offset = OpLoad _ {sbt_offset}",
+            "%sbt_stride = OpLoad _ {sbt_stride}",
+            "%miss_index = OpLoad _ {miss_index}",
+            "%ray_origin = OpLoad _ {ray_origin}",
+            "%ray_tmin = OpLoad _ {ray_tmin}",
+            "%ray_direction = OpLoad _ {ray_direction}",
+            "%ray_tmax = OpLoad _ {ray_tmax}",
+            "OpTraceRayKHR \
+            %acceleration_structure \
+            %ray_flags \
+            %cull_mask \
+            %sbt_offset \
+            %sbt_stride \
+            %miss_index \
+            %ray_origin \
+            %ray_tmin \
+            %ray_direction \
+            %ray_tmax \
+            {payload}",
+            acceleration_structure = in(reg) self,
+            ray_flags = in(reg) &ray_flags.bits,
+            cull_mask = in(reg) &cull_mask,
+            sbt_offset = in(reg) &sbt_offset,
+            sbt_stride = in(reg) &sbt_stride,
+            miss_index = in(reg) &miss_index,
+            ray_origin = in(reg) &ray_origin,
+            ray_tmin = in(reg) &ray_tmin,
+            ray_direction = in(reg) &ray_direction,
+            ray_tmax = in(reg) &ray_tmax,
+            payload = in(reg) payload,
+        }
+    }
+}
+
+/// Flags controlling the properties of an [`AccelerationStructure::trace_ray`]
+/// call. Flags can be combined with `|`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RayFlags {
+    bits: u32,
+}
+
+impl RayFlags {
+    /// No flags specified.
+    pub const NONE: Self = Self { bits: 0 };
+    /// Force all intersections with the trace to be opaque.
+    pub const OPAQUE: Self = Self { bits: 0x1 };
+    /// Force all intersections with the trace to be non-opaque.
+    pub const NO_OPAQUE: Self = Self { bits: 0x2 };
+    /// Accept the first intersection found, and stop the traversal.
+    pub const TERMINATE_ON_FIRST_HIT: Self = Self { bits: 0x4 };
+    /// Do not execute a closest hit shader.
+    pub const SKIP_CLOSEST_HIT_SHADER: Self = Self { bits: 0x8 };
+    /// Do not intersect with the back face of triangles.
+    pub const CULL_BACK_FACING_TRIANGLES: Self = Self { bits: 0x10 };
+    /// Do not intersect with the front face of triangles.
+    pub const CULL_FRONT_FACING_TRIANGLES: Self = Self { bits: 0x20 };
+    /// Do not intersect with opaque geometry.
+    pub const CULL_OPAQUE: Self = Self { bits: 0x40 };
+    /// Do not intersect with non-opaque geometry.
+    pub const CULL_NO_OPAQUE: Self = Self { bits: 0x80 };
+    /// Do not intersect with any triangle geometry.
+    pub const SKIP_TRIANGLES: Self = Self { bits: 0x100 };
+    /// Do not intersect with any AABB (procedural) geometry.
+    pub const SKIP_AABBS: Self = Self { bits: 0x200 };
+
+    /// Returns the raw value of the flags.
+    pub const fn bits(self) -> u32 {
+        self.bits
+    }
+
+    /// Returns `true` if all of the flags in `other` are set in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.bits & other.bits == other.bits
+    }
+}
+
+impl Default for RayFlags {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+impl core::ops::BitOr for RayFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            bits: self.bits | rhs.bits,
+        }
+    }
+}
+
+impl core::ops::BitOrAssign for RayFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.bits |= rhs.bits;
+    }
+}
+
+/// Reports an intersection back to the traversal infrastructure, from an
+/// intersection shader.
+///
+/// `hit` is the parametric distance along the ray, and `hit_kind` is an
+/// application-defined value (up to 127) made available to the any hit and
+/// closest hit shaders. Result is `true` if the intersection was accepted.
+///
+/// # Safety
+/// Must only be called from an intersection shader.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpReportIntersectionKHR")]
+#[inline]
+pub unsafe fn report_intersection(hit: f32, hit_kind: u32) -> bool {
+    let mut result = false;
+
+    asm! {
+        "OpExtension \"SPV_KHR_ray_tracing\"",
+        "OpCapability RayTracingKHR",
+        "%bool = OpTypeBool",
+        "%u8 = OpTypeInt 8 0",
+        "%u8_0 = OpConstant %u8 0",
+        "%u8_1 = OpConstant %u8 1",
+        "%hit = OpLoad _ {hit}",
+        "%hit_kind = OpLoad _ {hit_kind}",
+        "%result = OpReportIntersectionKHR %bool %hit %hit_kind",
+        "%boolean = OpSelect %u8 %result %u8_1 %u8_0",
+        "OpStore {result} %boolean",
+        hit = in(reg) &hit,
+        hit_kind = in(reg) &hit_kind,
+        result = in(reg) &mut result,
+    }
+
+    result
+}
+
+/// Ignores the current potential intersection, terminating the invocation of
+/// the any hit shader and continuing the ray traversal.
+///
+/// # Safety
+/// Must only be called from an any hit shader.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpIgnoreIntersectionKHR")]
+#[inline]
+pub unsafe fn ignore_intersection() {
+    asm!(
+        "OpExtension \"SPV_KHR_ray_tracing\"",
+        "OpCapability RayTracingKHR",
+        "OpIgnoreIntersectionKHR",
+        "%unused = OpLabel"
+    );
+}
+
+/// Accepts the current intersection and stops the ray traversal, terminating
+/// the invocation of the any hit shader.
+///
+/// # Safety
+/// Must only be called from an any hit shader.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpTerminateRayKHR")]
+#[inline]
+pub unsafe fn terminate_ray() {
+    asm!(
+        "OpExtension \"SPV_KHR_ray_tracing\"",
+        "OpCapability RayTracingKHR",
+        "OpTerminateRayKHR",
+        "%unused = OpLabel"
+    );
+}
+
+/// Invokes the callable shader at index `sbt_index` of the shader binding
+/// table, passing it `data`.
+///
+/// # Safety
+/// `data` must be a variable in the `callable_data_khr` storage class, and
+/// `sbt_index` must be a valid callable shader index for the bound pipeline.
+#[spirv_std_macros::gpu_only]
+#[doc(alias = "OpExecuteCallableKHR")]
+#[inline]
+pub unsafe fn execute_callable<T>(data: &T, sbt_index: u32) {
+    asm! {
+        "OpExtension \"SPV_KHR_ray_tracing\"",
+        "OpCapability RayTracingKHR",
+        "%sbt_index = OpLoad _ {sbt_index}",
+        "OpExecuteCallableKHR %sbt_index {data}",
+        sbt_index = in(reg) &sbt_index,
+        data = in(reg) data,
+    }
+}
+
+/// Declares a new, uninitialized [`RayQuery`] named `$name`, for use with
+/// inline ray tracing.
+///
+/// Ray query objects can only live in `Function` storage class variables, so
+/// this macro is the only way to create one. The query has to be initialized
+/// with [`RayQuery::initialize`] before anything else is done with it.
+///
+/// ```ignore
+/// spirv_std::ray_query!(let mut query);
+/// query.initialize(acceleration_structure, RayFlags::NONE, 0xff, origin, 0.0, direction, 1000.0);
+/// ```
+#[macro_export]
+macro_rules! ray_query {
+    (let $name:ident) => {
+        $crate::ray_query!(@inner $name)
+    };
+    (let mut $name:ident) => {
+        $crate::ray_query!(@inner $name, mut)
+    };
+    (@inner $name:ident $(, $mut:tt)?) => {
+        let $name: &$($mut)? $crate::ray_tracing::RayQuery = unsafe {
+            let $name: *mut $crate::ray_tracing::RayQuery;
+            asm! {
+                "%ray_query = OpTypeRayQueryKHR",
+                "%ray_query_ptr = OpTypePointer Generic %ray_query",
+                "{name} = OpVariable %ray_query_ptr Function",
+                name = out(reg) $name,
+            }
+            &$($mut)? *$name
+        };
+    };
+}
+
+/// A ray query object, used to trace rays inline from any shader stage (see
+/// [`ray_query!`](crate::ray_query) on how to declare one).
+///
+/// Using a ray query makes the module require the `RayQueryKHR` capability
+/// and the `SPV_KHR_ray_query` extension.
+#[spirv(ray_query)]
+pub struct RayQuery {
+    _private: u32,
+}
+
+/// The type of the candidate intersection of a [`RayQuery`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CandidateIntersection {
+    /// A potential intersection with a triangle is being considered.
+    Triangle = 0,
+    /// A potential intersection with an axis-aligned bounding box is being
+    /// considered.
+    Aabb = 1,
+}
+
+/// The type of the committed intersection of a [`RayQuery`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CommittedIntersection {
+    /// No intersection has been committed.
+    None = 0,
+    /// An intersection with a triangle has been committed.
+    Triangle = 1,
+    /// A user-generated intersection has been committed.
+    Generated = 2,
+}
+
+macro_rules! intersection_getters {
+    ($(#[doc = $doc:literal] $op:literal, $candidate:ident, $committed:ident -> $ty:ty;)+) => {
+        $(
+            intersection_getters!(@getter $doc, $op, $candidate, 0, $ty);
+            intersection_getters!(@getter $doc, $op, $committed, 1, $ty);
+        )+
+    };
+    (@getter $doc:literal, $op:literal, $name:ident, $intersection:literal, $ty:ty) => {
+        #[doc = $doc]
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = $op)]
+        #[inline]
+        pub fn $name(&self) -> $ty {
+            let mut result = Default::default();
+
+            unsafe {
+                asm! {
+                    "%u32 = OpTypeInt 32 0",
+                    concat!("%intersection = OpConstant %u32 ", $intersection),
+                    concat!("%result = ", $op, " typeof*{result} {ray_query} %intersection"),
+                    "OpStore {result} %result",
+                    ray_query = in(reg) self,
+                    result = in(reg) &mut result,
+                }
+            }
+
+            result
+        }
+    };
+}
+
+macro_rules! intersection_vector_getters {
+    ($(#[doc = $doc:literal] $op:literal, $candidate:ident, $committed:ident -> $n:literal;)+) => {
+        $(
+            intersection_vector_getters!(@getter $doc, $op, $candidate, 0, $n);
+            intersection_vector_getters!(@getter $doc, $op, $committed, 1, $n);
+        )+
+    };
+    (@getter $doc:literal, $op:literal, $name:ident, $intersection:literal, $n:literal) => {
+        #[doc = $doc]
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = $op)]
+        #[inline]
+        pub fn $name<V: Vector<f32, $n>>(&self) -> V {
+            let mut result = Default::default();
+
+            unsafe {
+                asm! {
+                    "%u32 = OpTypeInt 32 0",
+                    concat!("%intersection = OpConstant %u32 ", $intersection),
+                    concat!("%result = ", $op, " typeof*{result} {ray_query} %intersection"),
+                    "OpStore {result} %result",
+                    ray_query = in(reg) self,
+                    result = in(reg) &mut result,
+                }
+            }
+
+            result
+        }
+    };
+}
+
+impl RayQuery {
+    /// Initializes the ray query, setting up the ray to be traced through
+    /// `acceleration_structure`. Any previous traversal state is discarded.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryInitializeKHR")]
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        acceleration_structure: &AccelerationStructure,
+        ray_flags: RayFlags,
+        cull_mask: u32,
+        ray_origin: impl Vector<f32, 3>,
+        ray_tmin: f32,
+        ray_direction: impl Vector<f32, 3>,
+        ray_tmax: f32,
+    ) {
+        unsafe {
+            asm! {
+                "%acceleration_structure = OpLoad _ {acceleration_structure}",
+                "%ray_flags = OpLoad _ {ray_flags}",
+                "%cull_mask = OpLoad _ {cull_mask}",
+                "%ray_origin = OpLoad _ {ray_origin}",
+                "%ray_tmin = OpLoad _ {ray_tmin}",
+                "%ray_direction = OpLoad _ {ray_direction}",
+                "%ray_tmax = OpLoad _ {ray_tmax}",
+                "OpRayQueryInitializeKHR \
+                {ray_query} \
+                %acceleration_structure \
+                %ray_flags \
+                %cull_mask \
+                %ray_origin \
+                %ray_tmin \
+                %ray_direction \
+                %ray_tmax",
+                ray_query = in(reg) self,
+                acceleration_structure = in(reg) acceleration_structure,
+                ray_flags = in(reg) &ray_flags.bits,
+                cull_mask = in(reg) &cull_mask,
+                ray_origin = in(reg) &ray_origin,
+                ray_tmin = in(reg) &ray_tmin,
+                ray_direction = in(reg) &ray_direction,
+                ray_tmax = in(reg) &ray_tmax,
+            }
+        }
+    }
+
+    /// Advances the traversal of the ray query. Result is `true` if the
+    /// traversal found a new candidate intersection, and `false` once the
+    /// traversal is complete.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryProceedKHR")]
+    #[inline]
+    pub fn proceed(&mut self) -> bool {
+        let mut result = false;
+
+        unsafe {
+            asm! {
+                "%bool = OpTypeBool",
+                "%u8 = OpTypeInt 8 0",
+                "%u8_0 = OpConstant %u8 0",
+                "%u8_1 = OpConstant %u8 1",
+                "%result = OpRayQueryProceedKHR %bool {ray_query}",
+                "%boolean = OpSelect %u8 %result %u8_1 %u8_0",
+                "OpStore {result} %boolean",
+                ray_query = in(reg) self,
+                result = in(reg) &mut result,
+            }
+        }
+
+        result
+    }
+
+    /// Terminates the traversal of the ray query, so that the next call to
+    /// [`Self::proceed`] returns `false`.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryTerminateKHR")]
+    #[inline]
+    pub fn terminate(&mut self) {
+        unsafe {
+            asm! {
+                "OpRayQueryTerminateKHR {ray_query}",
+                ray_query = in(reg) self,
+            }
+        }
+    }
+
+    /// Commits the current candidate triangle intersection. Must only be
+    /// called while the candidate intersection type is
+    /// [`CandidateIntersection::Triangle`].
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryConfirmIntersectionKHR")]
+    #[inline]
+    pub fn confirm_intersection(&mut self) {
+        unsafe {
+            asm! {
+                "OpRayQueryConfirmIntersectionKHR {ray_query}",
+                ray_query = in(reg) self,
+            }
+        }
+    }
+
+    /// Commits a user-generated intersection at parametric distance `hit_t`
+    /// for the current candidate AABB. Must only be called while the candidate
+    /// intersection type is [`CandidateIntersection::Aabb`].
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryGenerateIntersectionKHR")]
+    #[inline]
+    pub fn generate_intersection(&mut self, hit_t: f32) {
+        unsafe {
+            asm! {
+                "%hit_t = OpLoad _ {hit_t}",
+                "OpRayQueryGenerateIntersectionKHR {ray_query} %hit_t",
+                ray_query = in(reg) self,
+                hit_t = in(reg) &hit_t,
+            }
+        }
+    }
+
+    /// Returns the type of the current candidate intersection.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryGetIntersectionTypeKHR")]
+    #[inline]
+    pub fn candidate_intersection_type(&self) -> CandidateIntersection {
+        match self.intersection_type::<0>() {
+            0 => CandidateIntersection::Triangle,
+            _ => CandidateIntersection::Aabb,
+        }
+    }
+
+    /// Returns the type of the committed intersection.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryGetIntersectionTypeKHR")]
+    #[inline]
+    pub fn committed_intersection_type(&self) -> CommittedIntersection {
+        match self.intersection_type::<1>() {
+            0 => CommittedIntersection::None,
+            1 => CommittedIntersection::Triangle,
+            _ => CommittedIntersection::Generated,
+        }
+    }
+
+    #[spirv_std_macros::gpu_only]
+    #[inline]
+    fn intersection_type<const INTERSECTION: u32>(&self) -> u32 {
+        let mut result = 0u32;
+
+        unsafe {
+            asm! {
+                "%u32 = OpTypeInt 32 0",
+                "%intersection = OpConstant %u32 {intersection}",
+                "%result = OpRayQueryGetIntersectionTypeKHR %u32 {ray_query} %intersection",
+                "OpStore {result} %result",
+                ray_query = in(reg) self,
+                intersection = const INTERSECTION,
+                result = in(reg) &mut result,
+            }
+        }
+
+        result
+    }
+
+    /// Returns the parametric `tmin` value the ray query was initialized with.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryGetRayTMinKHR")]
+    #[inline]
+    pub fn ray_tmin(&self) -> f32 {
+        let mut result = 0.0;
+
+        unsafe {
+            asm! {
+                "%f32 = OpTypeFloat 32",
+                "%result = OpRayQueryGetRayTMinKHR %f32 {ray_query}",
+                "OpStore {result} %result",
+                ray_query = in(reg) self,
+                result = in(reg) &mut result,
+            }
+        }
+
+        result
+    }
+
+    /// Returns the ray flags the ray query was initialized with.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryGetRayFlagsKHR")]
+    #[inline]
+    pub fn ray_flags(&self) -> RayFlags {
+        let mut bits = 0u32;
+
+        unsafe {
+            asm! {
+                "%u32 = OpTypeInt 32 0",
+                "%result = OpRayQueryGetRayFlagsKHR %u32 {ray_query}",
+                "OpStore {bits} %result",
+                ray_query = in(reg) self,
+                bits = in(reg) &mut bits,
+            }
+        }
+
+        RayFlags { bits }
+    }
+
+    /// Returns the world-space origin the ray query was initialized with.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryGetWorldRayOriginKHR")]
+    #[inline]
+    pub fn world_ray_origin<V: Vector<f32, 3>>(&self) -> V {
+        let mut result = Default::default();
+
+        unsafe {
+            asm! {
+                "%result = OpRayQueryGetWorldRayOriginKHR typeof*{result} {ray_query}",
+                "OpStore {result} %result",
+                ray_query = in(reg) self,
+                result = in(reg) &mut result,
+            }
+        }
+
+        result
+    }
+
+    /// Returns the world-space direction the ray query was initialized with.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryGetWorldRayDirectionKHR")]
+    #[inline]
+    pub fn world_ray_direction<V: Vector<f32, 3>>(&self) -> V {
+        let mut result = Default::default();
+
+        unsafe {
+            asm! {
+                "%result = OpRayQueryGetWorldRayDirectionKHR typeof*{result} {ray_query}",
+                "OpStore {result} %result",
+                ray_query = in(reg) self,
+                result = in(reg) &mut result,
+            }
+        }
+
+        result
+    }
+
+    /// Returns `true` if the current candidate AABB intersection is opaque.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryGetIntersectionCandidateAABBOpaqueKHR")]
+    #[inline]
+    pub fn candidate_aabb_opaque(&self) -> bool {
+        let mut result = false;
+
+        unsafe {
+            asm! {
+                "%bool = OpTypeBool",
+                "%u8 = OpTypeInt 8 0",
+                "%u8_0 = OpConstant %u8 0",
+                "%u8_1 = OpConstant %u8 1",
+                "%result = OpRayQueryGetIntersectionCandidateAABBOpaqueKHR %bool {ray_query}",
+                "%boolean = OpSelect %u8 %result %u8_1 %u8_0",
+                "OpStore {result} %boolean",
+                ray_query = in(reg) self,
+                result = in(reg) &mut result,
+            }
+        }
+
+        result
+    }
+
+    /// Returns `true` if the candidate triangle intersection is with the front
+    /// face of the triangle.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryGetIntersectionFrontFaceKHR")]
+    #[inline]
+    pub fn candidate_front_face(&self) -> bool {
+        self.front_face::<0>()
+    }
+
+    /// Returns `true` if the committed triangle intersection is with the front
+    /// face of the triangle.
+    #[spirv_std_macros::gpu_only]
+    #[doc(alias = "OpRayQueryGetIntersectionFrontFaceKHR")]
+    #[inline]
+    pub fn committed_front_face(&self) -> bool {
+        self.front_face::<1>()
+    }
+
+    #[spirv_std_macros::gpu_only]
+    #[inline]
+    fn front_face<const INTERSECTION: u32>(&self) -> bool {
+        let mut result = false;
+
+        unsafe {
+            asm! {
+                "%bool = OpTypeBool",
+                "%u8 = OpTypeInt 8 0",
+                "%u8_0 = OpConstant %u8 0",
+                "%u8_1 = OpConstant %u8 1",
+                "%u32 = OpTypeInt 32 0",
+                "%intersection = OpConstant %u32 {intersection}",
+                "%result = OpRayQueryGetIntersectionFrontFaceKHR %bool {ray_query} %intersection",
+                "%boolean = OpSelect %u8 %result %u8_1 %u8_0",
+                "OpStore {result} %boolean",
+                ray_query = in(reg) self,
+                intersection = const INTERSECTION,
+                result = in(reg) &mut result,
+            }
+        }
+
+        result
+    }
+
+    intersection_getters! {
+        #[doc = "Returns the parametric `t` value of the intersection."]
+        "OpRayQueryGetIntersectionTKHR", candidate_t, committed_t -> f32;
+        #[doc = "Returns the custom index of the instance that was intersected."]
+        "OpRayQueryGetIntersectionInstanceCustomIndexKHR",
+        candidate_instance_custom_index, committed_instance_custom_index -> u32;
+        #[doc = "Returns the index of the instance that was intersected."]
+        "OpRayQueryGetIntersectionInstanceIdKHR",
+        candidate_instance_id, committed_instance_id -> u32;
+        #[doc = "Returns the shader binding table record offset of the instance that was intersected."]
+        "OpRayQueryGetIntersectionInstanceShaderBindingTableRecordOffsetKHR",
+        candidate_instance_sbt_record_offset, committed_instance_sbt_record_offset -> u32;
+        #[doc = "Returns the index of the geometry that was intersected."]
+        "OpRayQueryGetIntersectionGeometryIndexKHR",
+        candidate_geometry_index, committed_geometry_index -> u32;
+        #[doc = "Returns the index of the primitive within the geometry that was intersected."]
+        "OpRayQueryGetIntersectionPrimitiveIndexKHR",
+        candidate_primitive_index, committed_primitive_index -> u32;
+    }
+
+    intersection_vector_getters! {
+        #[doc = "Returns the barycentric coordinates of a triangle intersection."]
+        "OpRayQueryGetIntersectionBarycentricsKHR",
+        candidate_barycentrics, committed_barycentrics -> 2;
+        #[doc = "Returns the object-space direction of the ray at the intersection."]
+        "OpRayQueryGetIntersectionObjectRayDirectionKHR",
+        candidate_object_ray_direction, committed_object_ray_direction -> 3;
+        #[doc = "Returns the object-space origin of the ray at the intersection."]
+        "OpRayQueryGetIntersectionObjectRayOriginKHR",
+        candidate_object_ray_origin, committed_object_ray_origin -> 3;
+    }
+}
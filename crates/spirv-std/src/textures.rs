@@ -1,4 +1,35 @@
-use crate::{integer::Integer, vector::Vector};
+use crate::{integer::Integer, scalar::Scalar, vector::Vector};
+use core::marker::PhantomData;
+
+/// Abstract trait representing the component type an image can be sampled
+/// as: `f32`, `i32` or `u32`.
+pub unsafe trait SampledType: Scalar {}
+
+unsafe impl SampledType for f32 {}
+unsafe impl SampledType for i32 {}
+unsafe impl SampledType for u32 {}
+
+/// Four constant texel offsets, one for each texel gathered by the
+/// `gather_with_const_offsets` methods. The offsets have to be known at
+/// compile time, so they are given as an associated constant:
+///
+/// ```ignore
+/// struct Pcf;
+///
+/// impl GatherOffsets for Pcf {
+///     const OFFSETS: [[i32; 2]; 4] = [[-1, -1], [1, -1], [-1, 1], [1, 1]];
+/// }
+/// ```
+#[cfg(feature = "const-generics")]
+pub trait GatherOffsets {
+    const OFFSETS: [[i32; 2]; 4];
+}
+
+/// A pair of `i32`s with the SPIR-V type of a single `ConstOffsets` entry.
+#[cfg(all(target_arch = "spirv", feature = "const-generics"))]
+#[repr(simd)]
+#[derive(Copy, Clone, Default)]
+struct ConstOffset(i32, i32);
 
 #[spirv(sampler)]
 #[derive(Copy, Clone)]
@@ -6,868 +37,1307 @@ pub struct Sampler {
     _x: u32,
 }
 
-#[spirv(image_type(
-    // sampled_type is hardcoded to f32 for now
-    dim = "Dim2D",
-    depth = 0,
-    arrayed = 0,
-    multisampled = 0,
-    sampled = 1,
-    image_format = "Unknown"
-))]
-#[derive(Copy, Clone)]
-pub struct Image2d {
-    _x: u32,
+/// `sample`, `sample_by_lod` and `sample_by_gradient`, for images addressed
+/// by `$coordinate` whose derivatives are `$gradient`.
+macro_rules! sample_methods {
+    ($coordinate:ty, $gradient:ty) => {
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleImplicitLod")]
+        pub fn sample<V: Vector<T, 4>>(&self, sampler: Sampler, coordinate: $coordinate) -> V {
+            unsafe {
+                let mut result = Default::default();
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleImplicitLod _ %sampledImage %coordinate",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                );
+                result
+            }
+        }
+
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleExplicitLod")]
+        /// Sample the image at a coordinate by a lod
+        pub fn sample_by_lod<V: Vector<T, 4>>(
+            &self,
+            sampler: Sampler,
+            coordinate: $coordinate,
+            lod: f32,
+        ) -> V {
+            let mut result = Default::default();
+            unsafe {
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%lod = OpLoad _ {lod}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleExplicitLod _ %sampledImage %coordinate Lod %lod",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    lod = in(reg) &lod
+                );
+            }
+            result
+        }
+
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleExplicitLod")]
+        /// Sample the image based on a gradient formed by (dx, dy). Specifically, ([du/dx, dv/dx], [du/dy, dv/dy])
+        pub fn sample_by_gradient<V: Vector<T, 4>>(
+            &self,
+            sampler: Sampler,
+            coordinate: $coordinate,
+            gradient_dx: $gradient,
+            gradient_dy: $gradient,
+        ) -> V {
+            let mut result = Default::default();
+            unsafe {
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%gradient_dx = OpLoad _ {gradient_dx}",
+                    "%gradient_dy = OpLoad _ {gradient_dy}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleExplicitLod _ %sampledImage %coordinate Grad %gradient_dx %gradient_dy",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    gradient_dx = in(reg) &gradient_dx,
+                    gradient_dy = in(reg) &gradient_dy,
+                );
+            }
+            result
+        }
+    };
 }
 
-impl Image2d {
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleImplicitLod")]
-    pub fn sample<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 2>,
-    ) -> V {
-        unsafe {
+/// The `sample_with_project_coordinate*` family, for non-arrayed 1D, 2D and
+/// 3D images.
+macro_rules! sample_with_project_coordinate_methods {
+    ($project_coordinate:ty, $gradient:ty) => {
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleProjImplicitLod")]
+        pub fn sample_with_project_coordinate<V: Vector<T, 4>>(
+            &self,
+            sampler: Sampler,
+            project_coordinate: $project_coordinate,
+        ) -> V {
+            unsafe {
+                let mut result = Default::default();
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%project_coordinate = OpLoad _ {project_coordinate}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleProjImplicitLod _ %sampledImage %project_coordinate",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    project_coordinate = in(reg) &project_coordinate,
+                );
+                result
+            }
+        }
+
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleProjExplicitLod")]
+        /// Sample the image with a project coordinate by a lod
+        pub fn sample_with_project_coordinate_by_lod<V: Vector<T, 4>>(
+            &self,
+            sampler: Sampler,
+            project_coordinate: $project_coordinate,
+            lod: f32,
+        ) -> V {
             let mut result = Default::default();
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleImplicitLod _ %sampledImage %coordinate",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-            );
+            unsafe {
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%project_coordinate = OpLoad _ {project_coordinate}",
+                    "%lod = OpLoad _ {lod}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleProjExplicitLod _ %sampledImage %project_coordinate Lod %lod",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    project_coordinate = in(reg) &project_coordinate,
+                    lod = in(reg) &lod
+                );
+            }
             result
         }
-    }
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleExplicitLod")]
-    /// Sample the image at a coordinate by a lod
-    pub fn sample_by_lod<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 2>,
-        lod: f32,
-    ) -> V {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%lod = OpLoad _ {lod}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleExplicitLod _ %sampledImage %coordinate Lod %lod",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                lod = in(reg) &lod
-            );
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleProjExplicitLod")]
+        /// Sample the image with a project coordinate based on a gradient formed by (dx, dy). Specifically, ([du/dx, dv/dx], [du/dy, dv/dy])
+        pub fn sample_with_project_coordinate_by_gradient<V: Vector<T, 4>>(
+            &self,
+            sampler: Sampler,
+            project_coordinate: $project_coordinate,
+            gradient_dx: $gradient,
+            gradient_dy: $gradient,
+        ) -> V {
+            let mut result = Default::default();
+            unsafe {
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%project_coordinate = OpLoad _ {project_coordinate}",
+                    "%gradient_dx = OpLoad _ {gradient_dx}",
+                    "%gradient_dy = OpLoad _ {gradient_dy}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleProjExplicitLod _ %sampledImage %project_coordinate Grad %gradient_dx %gradient_dy",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    project_coordinate = in(reg) &project_coordinate,
+                    gradient_dx = in(reg) &gradient_dx,
+                    gradient_dy = in(reg) &gradient_dy,
+                );
+            }
+            result
         }
-        result
-    }
+    };
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleExplicitLod")]
-    /// Sample the image based on a gradient formed by (dx, dy). Specifically, ([du/dx, dv/dx], [du/dy, dv/dy])
-    pub fn sample_by_gradient<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 2>,
-        gradient_dx: impl Vector<f32, 2>,
-        gradient_dy: impl Vector<f32, 2>,
-    ) -> V {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%gradient_dx = OpLoad _ {gradient_dx}",
-                "%gradient_dy = OpLoad _ {gradient_dy}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleExplicitLod _ %sampledImage %coordinate Grad %gradient_dx %gradient_dy",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                gradient_dx = in(reg) &gradient_dx,
-                gradient_dy = in(reg) &gradient_dy,
-            );
+/// The `sample_depth_reference*` family.
+macro_rules! sample_depth_reference_methods {
+    ($coordinate:ty, $gradient:ty) => {
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleDrefImplicitLod")]
+        /// Sample the image's depth reference
+        pub fn sample_depth_reference(
+            &self,
+            sampler: Sampler,
+            coordinate: $coordinate,
+            depth_reference: f32,
+        ) -> T {
+            let mut result = Default::default();
+            unsafe {
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%depth_reference = OpLoad _ {depth_reference}", // not required to do this way, but done for consistency
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleDrefImplicitLod _ %sampledImage %coordinate %depth_reference",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    depth_reference = in(reg) &depth_reference,
+                );
+            }
+            result
         }
-        result
-    }
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleProjImplicitLod")]
-    pub fn sample_with_project_coordinate<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        project_coordinate: impl Vector<f32, 3>,
-    ) -> V {
-        unsafe {
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleDrefExplicitLod")]
+        /// Sample the image's depth reference based on an explicit lod
+        pub fn sample_depth_reference_by_lod(
+            &self,
+            sampler: Sampler,
+            coordinate: $coordinate,
+            depth_reference: f32,
+            lod: f32,
+        ) -> T {
             let mut result = Default::default();
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%project_coordinate = OpLoad _ {project_coordinate}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleProjImplicitLod _ %sampledImage %project_coordinate",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                project_coordinate = in(reg) &project_coordinate,
-            );
+            unsafe {
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%depth_reference = OpLoad _ {depth_reference}",
+                    "%lod = OpLoad _ {lod}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleDrefExplicitLod _ %sampledImage %coordinate %depth_reference Lod %lod",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    depth_reference = in(reg) &depth_reference,
+                    lod = in(reg) &lod,
+                )
+            }
             result
         }
-    }
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleProjExplicitLod")]
-    /// Sample the image with a project coordinate by a lod
-    pub fn sample_with_project_coordinate_by_lod<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        project_coordinate: impl Vector<f32, 3>,
-        lod: f32,
-    ) -> V {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%project_coordinate = OpLoad _ {project_coordinate}",
-                "%lod = OpLoad _ {lod}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleProjExplicitLod _ %sampledImage %project_coordinate Lod %lod",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                project_coordinate = in(reg) &project_coordinate,
-                lod = in(reg) &lod
-            );
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleDrefExplicitLod")]
+        /// Sample the image's depth reference based on a gradient formed by (dx, dy).
+        /// Specifically, ([du/dx, dv/dx], [du/dy, dv/dy])
+        pub fn sample_depth_reference_by_gradient(
+            &self,
+            sampler: Sampler,
+            coordinate: $coordinate,
+            depth_reference: f32,
+            gradient_dx: $gradient,
+            gradient_dy: $gradient,
+        ) -> T {
+            let mut result = Default::default();
+            unsafe {
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%depth_reference = OpLoad _ {depth_reference}",
+                    "%gradient_dx = OpLoad _ {gradient_dx}",
+                    "%gradient_dy = OpLoad _ {gradient_dy}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleDrefExplicitLod _ %sampledImage %coordinate %depth_reference Grad %gradient_dx %gradient_dy",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    depth_reference = in(reg) &depth_reference,
+                    gradient_dx = in(reg) &gradient_dx,
+                    gradient_dy = in(reg) &gradient_dy,
+                );
+            }
+            result
         }
-        result
-    }
+    };
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleProjExplicitLod")]
-    /// Sample the image with a project coordinate based on a gradient formed by (dx, dy). Specifically, ([du/dx, dv/dx], [du/dy, dv/dy])
-    pub fn sample_with_project_coordinate_by_gradient<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        project_coordinate: impl Vector<f32, 3>,
-        gradient_dx: impl Vector<f32, 2>,
-        gradient_dy: impl Vector<f32, 2>,
-    ) -> V {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%project_coordinate = OpLoad _ {project_coordinate}",
-                "%gradient_dx = OpLoad _ {gradient_dx}",
-                "%gradient_dy = OpLoad _ {gradient_dy}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleProjExplicitLod _ %sampledImage %project_coordinate Grad %gradient_dx %gradient_dy",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                project_coordinate = in(reg) &project_coordinate,
-                gradient_dx = in(reg) &gradient_dx,
-                gradient_dy = in(reg) &gradient_dy,
-            );
+/// The `sample_depth_reference_with_project_coordinate*` family, for
+/// non-arrayed 1D and 2D images.
+macro_rules! sample_depth_reference_with_project_coordinate_methods {
+    ($project_coordinate:ty, $gradient:ty) => {
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleProjDrefImplicitLod")]
+        /// Sample the image's depth reference with the project coordinate
+        pub fn sample_depth_reference_with_project_coordinate(
+            &self,
+            sampler: Sampler,
+            project_coordinate: $project_coordinate,
+            depth_reference: f32,
+        ) -> T {
+            let mut result = Default::default();
+            unsafe {
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%project_coordinate = OpLoad _ {project_coordinate}",
+                    "%depth_reference = OpLoad _ {depth_reference}", // not required to do this way, but done for consistency
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleProjDrefImplicitLod _ %sampledImage %project_coordinate %depth_reference",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    project_coordinate = in(reg) &project_coordinate,
+                    depth_reference = in(reg) &depth_reference,
+                );
+            }
+            result
         }
-        result
-    }
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleDrefImplicitLod")]
-    /// Sample the image's depth reference
-    pub fn sample_depth_reference(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 2>,
-        depth_reference: f32,
-    ) -> f32 {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}", // not required to do this way, but done for consistency
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleDrefImplicitLod _ %sampledImage %coordinate %depth_reference",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                depth_reference = in(reg) &depth_reference,
-            );
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleProjDrefExplicitLod")]
+        /// Sample the image's depth reference with the project coordinate based on an explicit lod
+        pub fn sample_depth_reference_with_project_coordinate_by_lod(
+            &self,
+            sampler: Sampler,
+            coordinate: $project_coordinate,
+            depth_reference: f32,
+            lod: f32,
+        ) -> T {
+            let mut result = Default::default();
+            unsafe {
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%depth_reference = OpLoad _ {depth_reference}",
+                    "%lod = OpLoad _ {lod}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleProjDrefExplicitLod _ %sampledImage %coordinate %depth_reference Lod %lod",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    depth_reference = in(reg) &depth_reference,
+                    lod = in(reg) &lod,
+                )
+            }
+            result
         }
-        result
-    }
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleDrefExplicitLod")]
-    /// Sample the image's depth reference based on an explicit lod
-    pub fn sample_depth_reference_by_lod(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 2>,
-        depth_reference: f32,
-        lod: f32,
-    ) -> f32 {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}",
-                "%lod = OpLoad _ {lod}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleDrefExplicitLod _ %sampledImage %coordinate %depth_reference Lod %lod",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                depth_reference = in(reg) &depth_reference,
-                lod = in(reg) &lod,
-            )
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleProjDrefExplicitLod")]
+        /// Sample the image's depth reference with the project coordinate based on a gradient formed by (dx, dy).
+        /// Specifically, ([du/dx, dv/dx], [du/dy, dv/dy])
+        pub fn sample_depth_reference_with_project_coordinate_by_gradient(
+            &self,
+            sampler: Sampler,
+            coordinate: $project_coordinate,
+            depth_reference: f32,
+            gradient_dx: $gradient,
+            gradient_dy: $gradient,
+        ) -> T {
+            let mut result = Default::default();
+            unsafe {
+                asm!(
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%depth_reference = OpLoad _ {depth_reference}",
+                    "%gradient_dx = OpLoad _ {gradient_dx}",
+                    "%gradient_dy = OpLoad _ {gradient_dy}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleProjDrefExplicitLod _ %sampledImage %coordinate %depth_reference Grad %gradient_dx %gradient_dy",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    depth_reference = in(reg) &depth_reference,
+                    gradient_dx = in(reg) &gradient_dx,
+                    gradient_dy = in(reg) &gradient_dy,
+                );
+            }
+            result
         }
-        result
-    }
+    };
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleDrefExplicitLod")]
-    /// Sample the image's depth reference based on a gradient formed by (dx, dy).
-    /// Specifically, ([du/dx, dv/dx], [du/dy, dv/dy])
-    pub fn sample_depth_reference_by_gradient(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 2>,
-        depth_reference: f32,
-        gradient_dx: impl Vector<f32, 2>,
-        gradient_dy: impl Vector<f32, 2>,
-    ) -> f32 {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}",
-                "%gradient_dx = OpLoad _ {gradient_dx}",
-                "%gradient_dy = OpLoad _ {gradient_dy}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleDrefExplicitLod _ %sampledImage %coordinate %depth_reference Grad %gradient_dx %gradient_dy",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                depth_reference = in(reg) &depth_reference,
-                gradient_dx = in(reg) &gradient_dx,
-                gradient_dy = in(reg) &gradient_dy,
-            );
+/// The integer coordinate of a texel: a scalar for 1D and buffer images and a
+/// vector otherwise.
+macro_rules! texel_coordinate {
+    (1) => { I };
+    ($dimensions:literal) => { impl Vector<I, $dimensions> };
+}
+
+/// `fetch`, for single-sampled sampled images.
+macro_rules! fetch_method {
+    (@impl [$($coordinate:tt)*], [$($generics:tt)*]) => {
+        /// Fetch a single texel with a sampler set at compile time
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageFetch")]
+        pub fn fetch<$($generics)*>(&self, coordinate: $($coordinate)*) -> V
+        where
+            V: Vector<T, 4>,
+            I: Integer,
+        {
+            let mut result = V::default();
+            unsafe {
+                asm! {
+                    "%image = OpLoad _ {this}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%result = OpImageFetch typeof*{result} %image %coordinate",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    coordinate = in(reg) &coordinate,
+                }
+            }
+
+            result
         }
-        result
-    }
+    };
+    (1) => {
+        fetch_method!(@impl [I], [V, I]);
+    };
+    // `N` (which has to be `$dimensions`) is kept for callers that specify it explicitly.
+    ($dimensions:literal) => {
+        fetch_method!(@impl [impl Vector<I, N>], [V, I, const N: usize]);
+    };
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleProjDrefImplicitLod")]
-    /// Sample the image's depth reference with the project coordinate
-    pub fn sample_depth_reference_with_project_coordinate(
-        &self,
-        sampler: Sampler,
-        project_coordinate: impl Vector<f32, 3>,
-        depth_reference: f32,
-    ) -> f32 {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%project_coordinate = OpLoad _ {project_coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}", // not required to do this way, but done for consistency
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleProjDrefImplicitLod _ %sampledImage %project_coordinate %depth_reference",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                project_coordinate = in(reg) &project_coordinate,
-                depth_reference = in(reg) &depth_reference,
-            );
+/// `gather`, for 2D, 2D-array, cube and cube-array images.
+macro_rules! gather_method {
+    ($coordinate:literal) => {
+        /// Gather the requested component (0 = red, ..., 3 = alpha) of the
+        /// four texels that would be used for bilinear filtering.
+        ///
+        /// The component has to be a constant, so it is passed as a const
+        /// generic: `image.gather::<_, _, 1>(sampler, coordinate)`.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageGather")]
+        pub fn gather<V, C, const COMPONENT: u32>(&self, sampler: Sampler, coordinate: C) -> V
+        where
+            V: Vector<T, 4>,
+            C: Vector<f32, $coordinate>,
+        {
+            let mut result = V::default();
+            unsafe {
+                asm! {
+                    "%u32 = OpTypeInt 32 0",
+                    "%component = OpConstant %u32 {component}",
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageGather typeof*{result} %sampledImage %coordinate %component",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    component = const COMPONENT,
+                }
+            }
+            result
         }
-        result
-    }
+    };
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleProjDrefExplicitLod")]
-    /// Sample the image's depth reference with the project coordinate based on an explicit lod
-    pub fn sample_depth_reference_with_project_coordinate_by_lod(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        depth_reference: f32,
-        lod: f32,
-    ) -> f32 {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}",
-                "%lod = OpLoad _ {lod}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleProjDrefExplicitLod _ %sampledImage %coordinate %depth_reference Lod %lod",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                depth_reference = in(reg) &depth_reference,
-                lod = in(reg) &lod,
-            )
+/// `query_size_lod` (for single-sampled sampled images) or `query_size` (for
+/// multisampled, buffer and storage images). The size is a scalar for 1D and
+/// buffer images and a vector otherwise.
+macro_rules! query_size_method {
+    (size_lod, 1) => {
+        /// Query the dimensions of the image at a mip level.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageQuerySizeLod")]
+        pub fn query_size_lod(&self, lod: u32) -> u32 {
+            let mut result = Default::default();
+            unsafe {
+                asm! {
+                    "OpCapability ImageQuery",
+                    "%image = OpLoad _ {this}",
+                    "%lod = OpLoad _ {lod}",
+                    "%result = OpImageQuerySizeLod typeof*{result} %image %lod",
+                    "OpStore {result} %result",
+                    this = in(reg) self,
+                    lod = in(reg) &lod,
+                    result = in(reg) &mut result,
+                }
+            }
+            result
         }
-        result
-    }
+    };
+    (size_lod, $dimensions:literal) => {
+        /// Query the dimensions of the image at a mip level.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageQuerySizeLod")]
+        pub fn query_size_lod<Size: Vector<u32, $dimensions>>(&self, lod: u32) -> Size {
+            let mut result = Size::default();
+            unsafe {
+                asm! {
+                    "OpCapability ImageQuery",
+                    "%image = OpLoad _ {this}",
+                    "%lod = OpLoad _ {lod}",
+                    "%result = OpImageQuerySizeLod typeof*{result} %image %lod",
+                    "OpStore {result} %result",
+                    this = in(reg) self,
+                    lod = in(reg) &lod,
+                    result = in(reg) &mut result,
+                }
+            }
+            result
+        }
+    };
+    (size, 1) => {
+        /// Query the dimensions of the image.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageQuerySize")]
+        pub fn query_size(&self) -> u32 {
+            let mut result = Default::default();
+            unsafe {
+                asm! {
+                    "OpCapability ImageQuery",
+                    "%image = OpLoad _ {this}",
+                    "%result = OpImageQuerySize typeof*{result} %image",
+                    "OpStore {result} %result",
+                    this = in(reg) self,
+                    result = in(reg) &mut result,
+                }
+            }
+            result
+        }
+    };
+    (size, $dimensions:literal) => {
+        /// Query the dimensions of the image.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageQuerySize")]
+        pub fn query_size<Size: Vector<u32, $dimensions>>(&self) -> Size {
+            let mut result = Size::default();
+            unsafe {
+                asm! {
+                    "OpCapability ImageQuery",
+                    "%image = OpLoad _ {this}",
+                    "%result = OpImageQuerySize typeof*{result} %image",
+                    "OpStore {result} %result",
+                    this = in(reg) self,
+                    result = in(reg) &mut result,
+                }
+            }
+            result
+        }
+    };
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleProjDrefExplicitLod")]
-    /// Sample the image's depth reference with the project coordinate based on a gradient formed by (dx, dy).
-    /// Specifically, ([du/dx, dv/dx], [du/dy, dv/dy])
-    pub fn sample_depth_reference_with_project_coordinate_by_gradient(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        depth_reference: f32,
-        gradient_dx: impl Vector<f32, 2>,
-        gradient_dy: impl Vector<f32, 2>,
-    ) -> f32 {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}",
-                "%gradient_dx = OpLoad _ {gradient_dx}",
-                "%gradient_dy = OpLoad _ {gradient_dy}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleProjDrefExplicitLod _ %sampledImage %coordinate %depth_reference Grad %gradient_dx %gradient_dy",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                depth_reference = in(reg) &depth_reference,
-                gradient_dx = in(reg) &gradient_dx,
-                gradient_dy = in(reg) &gradient_dy,
-            );
+/// `query_levels`, for single-sampled sampled images.
+macro_rules! query_levels_method {
+    () => {
+        /// Query the number of mip levels of the image.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageQueryLevels")]
+        pub fn query_levels(&self) -> u32 {
+            let mut result = Default::default();
+            unsafe {
+                asm! {
+                    "OpCapability ImageQuery",
+                    "%image = OpLoad _ {this}",
+                    "%result = OpImageQueryLevels typeof*{result} %image",
+                    "OpStore {result} %result",
+                    this = in(reg) self,
+                    result = in(reg) &mut result,
+                }
+            }
+            result
         }
-        result
-    }
+    };
+}
 
-    /// Fetch a single texel with a sampler set at compile time
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageFetch")]
-    pub fn fetch<V, I, const N: usize>(&self, coordinate: impl Vector<I, N>) -> V
-    where
-        V: Vector<f32, 4>,
-        I: Integer,
-    {
-        let mut result = V::default();
-        unsafe {
+/// `query_lod`, for sampled images. Only available in fragment shaders.
+macro_rules! query_lod_method {
+    ($coordinate:ty) => {
+        /// Query the mipmap level and the level of detail that would be used
+        /// when sampling the image at a coordinate, as a `(level, lod)` pair.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageQueryLod")]
+        pub fn query_lod<V: Vector<f32, 2>>(&self, sampler: Sampler, coordinate: $coordinate) -> V {
+            let mut result = Default::default();
+            unsafe {
+                asm! {
+                    "OpCapability ImageQuery",
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageQueryLod typeof*{result} %sampledImage %coordinate",
+                    "OpStore {result} %result",
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    result = in(reg) &mut result,
+                }
+            }
+            result
+        }
+    };
+}
+
+/// `gather_depth_reference`, for 2D, 2D-array, cube and cube-array images.
+macro_rules! gather_depth_reference_method {
+    ($coordinate:ty) => {
+        /// Compare the four texels that would be used for bilinear filtering
+        /// against a depth reference, e.g. for percentage-closer filtering.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageDrefGather")]
+        pub fn gather_depth_reference<V: Vector<T, 4>>(
+            &self,
+            sampler: Sampler,
+            coordinate: $coordinate,
+            depth_reference: f32,
+        ) -> V {
+            let mut result = Default::default();
+            unsafe {
+                asm! {
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%depth_reference = OpLoad _ {depth_reference}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageDrefGather typeof*{result} %sampledImage %coordinate %depth_reference",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    depth_reference = in(reg) &depth_reference,
+                }
+            }
+            result
+        }
+    };
+}
+
+/// Sampling and gathering with texel offsets, for 2D and 2D-array images.
+///
+/// Constant offsets are passed as const generics, e.g.
+/// `image.sample_with_const_offset::<_, _, 1, { -1 }>(sampler, coordinate)`.
+/// Gathering with offsets and sampling with non-constant offsets declare the
+/// `ImageGatherExtended` capability.
+macro_rules! offset_methods {
+    ($coordinate:literal) => {
+        /// Sample the image with a constant texel offset.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleImplicitLod")]
+        pub fn sample_with_const_offset<V, C, const X: i32, const Y: i32>(
+            &self,
+            sampler: Sampler,
+            coordinate: C,
+        ) -> V
+        where
+            V: Vector<T, 4>,
+            C: Vector<f32, $coordinate>,
+        {
+            let mut result = V::default();
+            unsafe {
+                asm! {
+                    "%i32 = OpTypeInt 32 1",
+                    "%v2i32 = OpTypeVector %i32 2",
+                    "%x = OpConstant %i32 {x}",
+                    "%y = OpConstant %i32 {y}",
+                    "%offset = OpConstantComposite %v2i32 %x %y",
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleImplicitLod _ %sampledImage %coordinate ConstOffset %offset",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    x = const X,
+                    y = const Y,
+                }
+            }
+            result
+        }
+
+        /// Sample the image with a texel offset that isn't known at compile
+        /// time.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageSampleImplicitLod")]
+        pub fn sample_with_offset<V: Vector<T, 4>>(
+            &self,
+            sampler: Sampler,
+            coordinate: impl Vector<f32, $coordinate>,
+            offset: impl Vector<i32, 2>,
+        ) -> V {
+            let mut result = Default::default();
+            unsafe {
+                asm! {
+                    "OpCapability ImageGatherExtended",
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%offset = OpLoad _ {offset}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageSampleImplicitLod _ %sampledImage %coordinate Offset %offset",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    offset = in(reg) &offset,
+                }
+            }
+            result
+        }
+
+        /// Gather a component of the four texels that would be used for
+        /// bilinear filtering, with a constant texel offset.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageGather")]
+        pub fn gather_with_const_offset<V, C, const COMPONENT: u32, const X: i32, const Y: i32>(
+            &self,
+            sampler: Sampler,
+            coordinate: C,
+        ) -> V
+        where
+            V: Vector<T, 4>,
+            C: Vector<f32, $coordinate>,
+        {
+            let mut result = V::default();
+            unsafe {
+                asm! {
+                    "OpCapability ImageGatherExtended",
+                    "%u32 = OpTypeInt 32 0",
+                    "%i32 = OpTypeInt 32 1",
+                    "%v2i32 = OpTypeVector %i32 2",
+                    "%component = OpConstant %u32 {component}",
+                    "%x = OpConstant %i32 {x}",
+                    "%y = OpConstant %i32 {y}",
+                    "%offset = OpConstantComposite %v2i32 %x %y",
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageGather typeof*{result} %sampledImage %coordinate %component ConstOffset %offset",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    component = const COMPONENT,
+                    x = const X,
+                    y = const Y,
+                }
+            }
+            result
+        }
+
+        /// Gather a component of the four texels that would be used for
+        /// bilinear filtering, with a texel offset that isn't known at
+        /// compile time.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageGather")]
+        pub fn gather_with_offset<V, C, O, const COMPONENT: u32>(
+            &self,
+            sampler: Sampler,
+            coordinate: C,
+            offset: O,
+        ) -> V
+        where
+            V: Vector<T, 4>,
+            C: Vector<f32, $coordinate>,
+            O: Vector<i32, 2>,
+        {
+            let mut result = V::default();
+            unsafe {
+                asm! {
+                    "OpCapability ImageGatherExtended",
+                    "%u32 = OpTypeInt 32 0",
+                    "%component = OpConstant %u32 {component}",
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%offset = OpLoad _ {offset}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageGather typeof*{result} %sampledImage %coordinate %component Offset %offset",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    offset = in(reg) &offset,
+                    component = const COMPONENT,
+                }
+            }
+            result
+        }
+
+        /// Gather a component of four texels, each at its own constant offset
+        /// from the coordinate.
+        #[cfg(feature = "const-generics")]
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageGather")]
+        pub fn gather_with_const_offsets<V, C, O, const COMPONENT: u32>(
+            &self,
+            sampler: Sampler,
+            coordinate: C,
+        ) -> V
+        where
+            V: Vector<T, 4>,
+            C: Vector<f32, $coordinate>,
+            O: GatherOffsets,
+        {
+            let mut result = V::default();
+            unsafe {
+                asm! {
+                    "OpCapability ImageGatherExtended",
+                    "%u32 = OpTypeInt 32 0",
+                    "%i32 = OpTypeInt 32 1",
+                    "%v2i32 = OpTypeVector %i32 2",
+                    "%component = OpConstant %u32 {component}",
+                    "%x0 = OpConstant %i32 {x0}",
+                    "%y0 = OpConstant %i32 {y0}",
+                    "%x1 = OpConstant %i32 {x1}",
+                    "%y1 = OpConstant %i32 {y1}",
+                    "%x2 = OpConstant %i32 {x2}",
+                    "%y2 = OpConstant %i32 {y2}",
+                    "%x3 = OpConstant %i32 {x3}",
+                    "%y3 = OpConstant %i32 {y3}",
+                    "%offset0 = OpConstantComposite %v2i32 %x0 %y0",
+                    "%offset1 = OpConstantComposite %v2i32 %x1 %y1",
+                    "%offset2 = OpConstantComposite %v2i32 %x2 %y2",
+                    "%offset3 = OpConstantComposite %v2i32 %x3 %y3",
+                    "%offsets = OpConstantComposite typeof*{offsets_type} %offset0 %offset1 %offset2 %offset3",
+                    "%image = OpLoad _ {this}",
+                    "%sampler = OpLoad _ {sampler}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%sampledImage = OpSampledImage _ %image %sampler",
+                    "%result = OpImageGather typeof*{result} %sampledImage %coordinate %component ConstOffsets %offsets",
+                    "OpStore {result} %result",
+                    result = in(reg) &mut result,
+                    this = in(reg) self,
+                    sampler = in(reg) &sampler,
+                    coordinate = in(reg) &coordinate,
+                    // `OpTypeArray` can't be declared from `asm!`, so borrow
+                    // the type of the offset array from a local.
+                    offsets_type = in(reg) &[ConstOffset::default(); 4],
+                    component = const COMPONENT,
+                    x0 = const O::OFFSETS[0][0],
+                    y0 = const O::OFFSETS[0][1],
+                    x1 = const O::OFFSETS[1][0],
+                    y1 = const O::OFFSETS[1][1],
+                    x2 = const O::OFFSETS[2][0],
+                    y2 = const O::OFFSETS[2][1],
+                    x3 = const O::OFFSETS[3][0],
+                    y3 = const O::OFFSETS[3][1],
+                }
+            }
+            result
+        }
+    };
+}
+
+/// `read` and `write`, for storage images.
+macro_rules! read_write_methods {
+    ($dimensions:tt) => {
+        /// Read a texel from an image without a sampler.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageRead")]
+        pub fn read<I, V, const N: usize>(&self, coordinate: texel_coordinate!($dimensions)) -> V
+        where
+            I: Integer,
+            V: Vector<T, N>,
+        {
+            let mut result = V::default();
+
+            unsafe {
+                asm! {
+                    "%image = OpLoad _ {this}",
+                    "%coordinate = OpLoad _ {coordinate}",
+                    "%result = OpImageRead typeof*{result} %image %coordinate",
+                    "OpStore {result} %result",
+                    this = in(reg) self,
+                    coordinate = in(reg) &coordinate,
+                    result = in(reg) &mut result,
+                }
+            }
+
+            result
+        }
+
+        /// Write a texel to an image without a sampler.
+        #[spirv_std_macros::gpu_only]
+        #[doc(alias = "OpImageWrite")]
+        pub unsafe fn write<I, const N: usize>(
+            &self,
+            coordinate: texel_coordinate!($dimensions),
+            texels: impl Vector<T, N>,
+        ) where
+            I: Integer,
+        {
             asm! {
                 "%image = OpLoad _ {this}",
                 "%coordinate = OpLoad _ {coordinate}",
-                "%result = OpImageFetch typeof*{result} %image %coordinate",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
+                "%texels = OpLoad _ {texels}",
+                "OpImageWrite %image %coordinate %texels",
                 this = in(reg) self,
                 coordinate = in(reg) &coordinate,
+                texels = in(reg) &texels,
             }
         }
+    };
+}
+
+#[spirv(image_type(
+    dim = "Dim1D",
+    depth = 0,
+    arrayed = 0,
+    multisampled = 0,
+    sampled = 1,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct Image1d<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: SampledType> Image1d<T> {
+    sample_methods!(f32, f32);
+    sample_with_project_coordinate_methods!(impl Vector<f32, 2>, f32);
+    sample_depth_reference_methods!(f32, f32);
+    sample_depth_reference_with_project_coordinate_methods!(impl Vector<f32, 2>, f32);
+    fetch_method!(1);
+    query_size_method!(size_lod, 1);
+    query_levels_method!();
+}
+
+#[spirv(image_type(
+    dim = "Dim2D",
+    depth = 0,
+    arrayed = 0,
+    multisampled = 0,
+    sampled = 1,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct Image2d<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: SampledType> Image2d<T> {
+    sample_methods!(impl Vector<f32, 2>, impl Vector<f32, 2>);
+    sample_with_project_coordinate_methods!(impl Vector<f32, 3>, impl Vector<f32, 2>);
+    sample_depth_reference_methods!(impl Vector<f32, 2>, impl Vector<f32, 2>);
+    sample_depth_reference_with_project_coordinate_methods!(
+        impl Vector<f32, 3>,
+        impl Vector<f32, 2>
+    );
+    fetch_method!(2);
+    gather_method!(2);
+    gather_depth_reference_method!(impl Vector<f32, 2>);
+    offset_methods!(2);
+    query_size_method!(size_lod, 2);
+    query_levels_method!();
+    query_lod_method!(impl Vector<f32, 2>);
+}
+
+#[spirv(image_type(
+    dim = "Dim3D",
+    depth = 0,
+    arrayed = 0,
+    multisampled = 0,
+    sampled = 1,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct Image3d<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: SampledType> Image3d<T> {
+    sample_methods!(impl Vector<f32, 3>, impl Vector<f32, 3>);
+    sample_with_project_coordinate_methods!(impl Vector<f32, 4>, impl Vector<f32, 3>);
+    fetch_method!(3);
+    query_size_method!(size_lod, 3);
+    query_levels_method!();
+}
+
+#[spirv(image_type(
+    dim = "Dim1D",
+    depth = 0,
+    arrayed = 1,
+    multisampled = 0,
+    sampled = 1,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct Image1dArray<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: SampledType> Image1dArray<T> {
+    sample_methods!(impl Vector<f32, 2>, f32);
+    sample_depth_reference_methods!(impl Vector<f32, 2>, f32);
+    fetch_method!(2);
+    query_size_method!(size_lod, 2);
+    query_levels_method!();
+}
+
+#[spirv(image_type(
+    dim = "Dim2D",
+    depth = 0,
+    arrayed = 1,
+    multisampled = 0,
+    sampled = 1,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct Image2dArray<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: SampledType> Image2dArray<T> {
+    sample_methods!(impl Vector<f32, 3>, impl Vector<f32, 2>);
+    sample_depth_reference_methods!(impl Vector<f32, 3>, impl Vector<f32, 2>);
+    fetch_method!(3);
+    gather_method!(3);
+    gather_depth_reference_method!(impl Vector<f32, 3>);
+    offset_methods!(3);
+    query_size_method!(size_lod, 3);
+    query_levels_method!();
+    query_lod_method!(impl Vector<f32, 2>);
+}
+
+#[spirv(image_type(
+    dim = "DimCube",
+    depth = 0,
+    arrayed = 0,
+    multisampled = 0,
+    sampled = 1,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct Cubemap<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: SampledType> Cubemap<T> {
+    sample_methods!(impl Vector<f32, 3>, impl Vector<f32, 3>);
+    sample_depth_reference_methods!(impl Vector<f32, 3>, impl Vector<f32, 3>);
+    gather_method!(3);
+    gather_depth_reference_method!(impl Vector<f32, 3>);
+    query_size_method!(size_lod, 2);
+    query_levels_method!();
+    query_lod_method!(impl Vector<f32, 3>);
+}
+
+#[spirv(image_type(
+    dim = "DimCube",
+    depth = 0,
+    arrayed = 1,
+    multisampled = 0,
+    sampled = 1,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct CubemapArray<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
 
-        result
-    }
+impl<T: SampledType> CubemapArray<T> {
+    sample_methods!(impl Vector<f32, 4>, impl Vector<f32, 3>);
+    gather_method!(4);
+    query_size_method!(size_lod, 3);
+    query_levels_method!();
 }
 
 #[spirv(image_type(
-    // sampled_type is hardcoded to f32 for now
     dim = "Dim2D",
     depth = 0,
     arrayed = 0,
-    multisampled = 0,
-    sampled = 2,
+    multisampled = 1,
+    sampled = 1,
     image_format = "Unknown"
 ))]
 #[derive(Copy, Clone)]
-pub struct StorageImage2d {
+pub struct Image2dMultisampled<T: SampledType = f32> {
     _x: u32,
+    _marker: PhantomData<T>,
 }
 
-impl StorageImage2d {
-    /// Read a texel from an image without a sampler.
+impl<T: SampledType> Image2dMultisampled<T> {
+    /// Fetch a single sample of a texel.
     #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageRead")]
-    pub fn read<I, V, const N: usize>(&self, coordinate: impl Vector<I, 2>) -> V
+    #[doc(alias = "OpImageFetch")]
+    pub fn fetch<V, I>(&self, coordinate: impl Vector<I, 2>, sample: u32) -> V
     where
+        V: Vector<T, 4>,
         I: Integer,
-        V: Vector<f32, N>,
     {
         let mut result = V::default();
-
         unsafe {
             asm! {
                 "%image = OpLoad _ {this}",
                 "%coordinate = OpLoad _ {coordinate}",
-                "%result = OpImageRead typeof*{result} %image %coordinate",
+                "%sample = OpLoad _ {sample}",
+                "%result = OpImageFetch typeof*{result} %image %coordinate Sample %sample",
                 "OpStore {result} %result",
+                result = in(reg) &mut result,
                 this = in(reg) self,
                 coordinate = in(reg) &coordinate,
-                result = in(reg) &mut result,
+                sample = in(reg) &sample,
             }
         }
 
         result
     }
 
-    /// Write a texel to an image without a sampler.
+    query_size_method!(size, 2);
+
+    /// Query the number of samples per texel of the image.
     #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageWrite")]
-    pub unsafe fn write<I, const N: usize>(
-        &self,
-        coordinate: impl Vector<I, 2>,
-        texels: impl Vector<f32, N>,
-    ) where
-        I: Integer,
-    {
-        asm! {
-            "%image = OpLoad _ {this}",
-            "%coordinate = OpLoad _ {coordinate}",
-            "%texels = OpLoad _ {texels}",
-            "OpImageWrite %image %coordinate %texels",
-            this = in(reg) self,
-            coordinate = in(reg) &coordinate,
-            texels = in(reg) &texels,
+    #[doc(alias = "OpImageQuerySamples")]
+    pub fn query_samples(&self) -> u32 {
+        let mut result = Default::default();
+        unsafe {
+            asm! {
+                "OpCapability ImageQuery",
+                "%image = OpLoad _ {this}",
+                "%result = OpImageQuerySamples typeof*{result} %image",
+                "OpStore {result} %result",
+                this = in(reg) self,
+                result = in(reg) &mut result,
+            }
         }
+        result
     }
 }
 
+/// A uniform texel buffer.
 #[spirv(image_type(
-    // sampled_type is hardcoded to f32 for now
-    dim = "Dim2D",
+    dim = "DimBuffer",
     depth = 0,
-    arrayed = 1,
+    arrayed = 0,
     multisampled = 0,
     sampled = 1,
     image_format = "Unknown"
 ))]
 #[derive(Copy, Clone)]
-pub struct Image2dArray {
+pub struct ImageBuffer<T: SampledType = f32> {
     _x: u32,
+    _marker: PhantomData<T>,
 }
 
-impl Image2dArray {
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleImplicitLod")]
-    pub fn sample<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-    ) -> V {
-        unsafe {
-            let mut result = V::default();
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleImplicitLod _ %sampledImage %coordinate",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-            );
-            result
-        }
-    }
+impl<T: SampledType> ImageBuffer<T> {
+    fetch_method!(1);
+    query_size_method!(size, 1);
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleExplicitLod")]
-    /// Sample the image at a coordinate by a lod
-    pub fn sample_by_lod<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        lod: f32,
-    ) -> V {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%lod = OpLoad _ {lod}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleExplicitLod _ %sampledImage %coordinate Lod %lod",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                lod = in(reg) &lod
-            );
-        }
-        result
-    }
+#[spirv(image_type(
+    dim = "Dim1D",
+    depth = 0,
+    arrayed = 0,
+    multisampled = 0,
+    sampled = 2,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct StorageImage1d<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleExplicitLod")]
-    /// Sample the image based on a gradient formed by (dx, dy). Specifically, ([du/dx, dv/dx], [du/dy, dv/dy])
-    pub fn sample_by_gradient<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        gradient_dx: impl Vector<f32, 2>,
-        gradient_dy: impl Vector<f32, 2>,
-    ) -> V {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%gradient_dx = OpLoad _ {gradient_dx}",
-                "%gradient_dy = OpLoad _ {gradient_dy}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleExplicitLod _ %sampledImage %coordinate Grad %gradient_dx %gradient_dy",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                gradient_dx = in(reg) &gradient_dx,
-                gradient_dy = in(reg) &gradient_dy,
-            );
-        }
-        result
-    }
+impl<T: SampledType> StorageImage1d<T> {
+    read_write_methods!(1);
+    query_size_method!(size, 1);
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleDrefImplicitLod")]
-    /// Sample the image's depth reference
-    pub fn sample_depth_reference(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        depth_reference: f32,
-    ) -> f32 {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}", // not required to do this way, but done for consistency
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleDrefImplicitLod _ %sampledImage %coordinate %depth_reference",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                depth_reference = in(reg) &depth_reference,
-            );
-        }
-        result
-    }
+#[spirv(image_type(
+    dim = "Dim2D",
+    depth = 0,
+    arrayed = 0,
+    multisampled = 0,
+    sampled = 2,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct StorageImage2d<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleDrefExplicitLod")]
-    /// Sample the image's depth reference based on an explicit lod
-    pub fn sample_depth_reference_by_lod(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        depth_reference: f32,
-        lod: f32,
-    ) -> f32 {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}",
-                "%lod = OpLoad _ {lod}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleDrefExplicitLod _ %sampledImage %coordinate %depth_reference Lod %lod",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                depth_reference = in(reg) &depth_reference,
-                lod = in(reg) &lod,
-            )
-        }
-        result
-    }
+impl<T: SampledType> StorageImage2d<T> {
+    read_write_methods!(2);
+    query_size_method!(size, 2);
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleDrefExplicitLod")]
-    /// Sample the image's depth reference based on a gradient formed by (dx, dy).
-    /// Specifically, ([du/dx, dv/dx], [du/dy, dv/dy])
-    pub fn sample_depth_reference_by_gradient(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        depth_reference: f32,
-        gradient_dx: impl Vector<f32, 2>,
-        gradient_dy: impl Vector<f32, 2>,
-    ) -> f32 {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}",
-                "%gradient_dx = OpLoad _ {gradient_dx}",
-                "%gradient_dy = OpLoad _ {gradient_dy}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleDrefExplicitLod _ %sampledImage %coordinate %depth_reference Grad %gradient_dx %gradient_dy",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                depth_reference = in(reg) &depth_reference,
-                gradient_dx = in(reg) &gradient_dx,
-                gradient_dy = in(reg) &gradient_dy,
-            );
-        }
-        result
-    }
+#[spirv(image_type(
+    dim = "Dim3D",
+    depth = 0,
+    arrayed = 0,
+    multisampled = 0,
+    sampled = 2,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct StorageImage3d<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: SampledType> StorageImage3d<T> {
+    read_write_methods!(3);
+    query_size_method!(size, 3);
 }
 
 #[spirv(image_type(
-    // sampled_type is hardcoded to f32 for now
-    dim = "DimCube",
+    dim = "Dim2D",
+    depth = 0,
+    arrayed = 1,
+    multisampled = 0,
+    sampled = 2,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct StorageImage2dArray<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: SampledType> StorageImage2dArray<T> {
+    read_write_methods!(3);
+    query_size_method!(size, 3);
+}
+
+/// A storage texel buffer.
+#[spirv(image_type(
+    dim = "DimBuffer",
     depth = 0,
     arrayed = 0,
     multisampled = 0,
-    sampled = 1,
+    sampled = 2,
     image_format = "Unknown"
 ))]
 #[derive(Copy, Clone)]
-pub struct Cubemap {
+pub struct StorageImageBuffer<T: SampledType = f32> {
     _x: u32,
+    _marker: PhantomData<T>,
 }
 
-impl Cubemap {
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpSampledImage")]
-    pub fn sample<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-    ) -> V {
-        unsafe {
-            let mut result = Default::default();
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleImplicitLod _ %sampledImage %coordinate",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-            );
-            result
-        }
-    }
+impl<T: SampledType> StorageImageBuffer<T> {
+    read_write_methods!(1);
+    query_size_method!(size, 1);
+}
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleExplicitLod")]
-    /// Sample the image at a coordinate by a lod
-    pub fn sample_by_lod<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        lod: f32,
-    ) -> V {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%lod = OpLoad _ {lod}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleExplicitLod _ %sampledImage %coordinate Lod %lod",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                lod = in(reg) &lod
-            );
-        }
-        result
-    }
+/// A subpass input attachment. Entry point parameters of this type also need
+/// `#[spirv(input_attachment_index = N)]`.
+#[spirv(image_type(
+    dim = "DimSubpassData",
+    depth = 0,
+    arrayed = 0,
+    multisampled = 0,
+    sampled = 2,
+    image_format = "Unknown"
+))]
+#[derive(Copy, Clone)]
+pub struct SubpassInput<T: SampledType = f32> {
+    _x: u32,
+    _marker: PhantomData<T>,
+}
 
+impl<T: SampledType> SubpassInput<T> {
+    /// Read the texel of the attachment at an offset from the current
+    /// fragment, which is usually `(0, 0)`.
     #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleExplicitLod")]
-    /// Sample the image based on a gradient formed by (dx, dy). Specifically, ([du/dx, dv/dx, dw/dx], [du/dy, dv/dy, dw/dy])
-    pub fn sample_by_gradient<V: Vector<f32, 4>>(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        gradient_dx: impl Vector<f32, 3>,
-        gradient_dy: impl Vector<f32, 3>,
-    ) -> V {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%gradient_dx = OpLoad _ {gradient_dx}",
-                "%gradient_dy = OpLoad _ {gradient_dy}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleExplicitLod _ %sampledImage %coordinate Grad %gradient_dx %gradient_dy",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                gradient_dx = in(reg) &gradient_dx,
-                gradient_dy = in(reg) &gradient_dy,
-            );
-        }
-        result
-    }
+    #[doc(alias = "OpImageRead")]
+    pub fn read<I, V>(&self, coordinate: impl Vector<I, 2>) -> V
+    where
+        I: Integer,
+        V: Vector<T, 4>,
+    {
+        let mut result = V::default();
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleDrefImplicitLod")]
-    /// Sample the image's depth reference
-    pub fn sample_depth_reference(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        depth_reference: f32,
-    ) -> f32 {
-        let mut result = Default::default();
         unsafe {
-            asm!(
+            asm! {
                 "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
                 "%coordinate = OpLoad _ {coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}", // not required to do this way, but done for consistency
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleDrefImplicitLod _ %sampledImage %coordinate %depth_reference",
+                "%result = OpImageRead typeof*{result} %image %coordinate",
                 "OpStore {result} %result",
-                result = in(reg) &mut result,
                 this = in(reg) self,
-                sampler = in(reg) &sampler,
                 coordinate = in(reg) &coordinate,
-                depth_reference = in(reg) &depth_reference,
-            );
-        }
-        result
-    }
-
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleDrefExplicitLod")]
-    /// Sample the image's depth reference based on an explicit lod
-    pub fn sample_depth_reference_by_lod(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        depth_reference: f32,
-        lod: f32,
-    ) -> f32 {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}",
-                "%lod = OpLoad _ {lod}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleDrefExplicitLod _ %sampledImage %coordinate %depth_reference Lod %lod",
-                "OpStore {result} %result",
                 result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                depth_reference = in(reg) &depth_reference,
-                lod = in(reg) &lod,
-            )
+            }
         }
-        result
-    }
 
-    #[spirv_std_macros::gpu_only]
-    #[doc(alias = "OpImageSampleDrefExplicitLod")]
-    /// Sample the image's depth reference based on a gradient formed by (dx, dy).
-    /// Specifically, ([du/dx, dv/dx, dw/dx], [du/dy, dv/dy, dw/dy])
-    pub fn sample_depth_reference_by_gradient(
-        &self,
-        sampler: Sampler,
-        coordinate: impl Vector<f32, 3>,
-        depth_reference: f32,
-        gradient_dx: impl Vector<f32, 3>,
-        gradient_dy: impl Vector<f32, 3>,
-    ) -> f32 {
-        let mut result = Default::default();
-        unsafe {
-            asm!(
-                "%image = OpLoad _ {this}",
-                "%sampler = OpLoad _ {sampler}",
-                "%coordinate = OpLoad _ {coordinate}",
-                "%depth_reference = OpLoad _ {depth_reference}",
-                "%gradient_dx = OpLoad _ {gradient_dx}",
-                "%gradient_dy = OpLoad _ {gradient_dy}",
-                "%sampledImage = OpSampledImage _ %image %sampler",
-                "%result = OpImageSampleDrefExplicitLod _ %sampledImage %coordinate %depth_reference Grad %gradient_dx %gradient_dy",
-                "OpStore {result} %result",
-                result = in(reg) &mut result,
-                this = in(reg) self,
-                sampler = in(reg) &sampler,
-                coordinate = in(reg) &coordinate,
-                depth_reference = in(reg) &depth_reference,
-                gradient_dx = in(reg) &gradient_dx,
-                gradient_dy = in(reg) &gradient_dy,
-            );
-        }
         result
     }
 }
@@ -878,10 +1348,10 @@ pub struct SampledImage<I> {
     _image: I,
 }
 
-impl SampledImage<Image2d> {
+impl<T: SampledType> SampledImage<Image2d<T>> {
     #[spirv_std_macros::gpu_only]
     #[doc(alias = "OpImageSampleImplicitLod")]
-    pub fn sample<V: Vector<f32, 4>>(&self, coordinate: impl Vector<f32, 2>) -> V {
+    pub fn sample<V: Vector<T, 4>>(&self, coordinate: impl Vector<f32, 2>) -> V {
         unsafe {
             let mut result = Default::default();
             asm!(
@@ -0,0 +1,31 @@
+use core::ops::{Deref, DerefMut};
+
+/// A uniform or storage buffer holding a `T`, for binding arrays of buffers
+/// (descriptor arrays), e.g.:
+///
+/// ```ignore
+/// #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffers: &mut [TypedBuffer<Lights>],
+/// ```
+///
+/// (a plain `&[T]` storage buffer parameter is a single buffer holding a
+/// runtime-sized array of `T` instead).
+///
+/// The number of buffers in a slice of them is only known to the host: the slice's
+/// `len()` is a placeholder (`u32::MAX`), and indexing it isn't bounds checked.
+#[spirv(typed_buffer)]
+#[repr(transparent)]
+pub struct TypedBuffer<T>(T);
+
+impl<T> Deref for TypedBuffer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for TypedBuffer<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
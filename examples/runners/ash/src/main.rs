@@ -44,7 +44,6 @@
 
 use ash::{
     extensions::{ext, khr},
-    util::read_spv,
     version::{DeviceV1_0, EntryV1_0, InstanceV1_0},
     vk,
 };
@@ -59,9 +58,7 @@ use std::{
     collections::HashMap,
     default::Default,
     ffi::{CStr, CString},
-    fs::File,
     ops::Drop,
-    path::PathBuf,
     sync::mpsc::{sync_channel, TryRecvError, TrySendError},
     thread,
 };
@@ -171,18 +168,13 @@ pub fn main() {
 }
 
 pub fn compile_shaders() -> Vec<SpvFile> {
-    let spv_paths: Vec<PathBuf> = vec![SpirvBuilder::new("examples/shaders/sky-shader")
-        .print_metadata(false)
-        .build()
-        .unwrap()];
-    let mut spv_files = Vec::<SpvFile>::with_capacity(spv_paths.len());
-    for path in spv_paths.iter() {
-        spv_files.push(SpvFile {
-            name: path.file_stem().unwrap().to_str().unwrap().to_owned(),
-            data: read_spv(&mut File::open(path).unwrap()).unwrap(),
-        })
-    }
-    spv_files
+    vec![SpvFile {
+        name: "sky_shader".to_owned(),
+        data: SpirvBuilder::new("examples/shaders/sky-shader")
+            .print_metadata(false)
+            .build_to_memory()
+            .unwrap(),
+    }]
 }
 
 #[derive(Debug)]
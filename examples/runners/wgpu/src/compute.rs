@@ -1,4 +1,4 @@
-use super::{shader_module, Options};
+use super::{shader_module, shaders, Options};
 use core::num::NonZeroU64;
 
 fn create_device_queue() -> (wgpu::Device, wgpu::Queue) {
@@ -67,7 +67,7 @@ pub fn start(options: &Options) {
         label: None,
         layout: Some(&pipeline_layout),
         module: &module,
-        entry_point: "main_cs",
+        entry_point: shaders::compute_shader::MAIN_CS,
     });
 
     let buf = device.create_buffer(&wgpu::BufferDescriptor {
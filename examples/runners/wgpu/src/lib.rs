@@ -43,11 +43,20 @@
 )]
 
 use clap::Clap;
+use std::borrow::Cow;
 use strum::{Display, EnumString};
 
 mod compute;
 mod graphics;
 
+#[allow(dead_code)]
+mod shaders {
+    include!(concat!(env!("OUT_DIR"), "/simplest_shader.rs"));
+    include!(concat!(env!("OUT_DIR"), "/sky_shader.rs"));
+    include!(concat!(env!("OUT_DIR"), "/compute_shader.rs"));
+    include!(concat!(env!("OUT_DIR"), "/mouse_shader.rs"));
+}
+
 #[derive(EnumString, Display, PartialEq, Copy, Clone)]
 pub enum RustGPUShader {
     Simplest,
@@ -57,11 +66,16 @@ pub enum RustGPUShader {
 }
 
 fn shader_module(shader: RustGPUShader) -> wgpu::ShaderModuleDescriptor<'static> {
-    match shader {
-        RustGPUShader::Simplest => wgpu::include_spirv!(env!("simplest_shader.spv")),
-        RustGPUShader::Sky => wgpu::include_spirv!(env!("sky_shader.spv")),
-        RustGPUShader::Compute => wgpu::include_spirv!(env!("compute_shader.spv")),
-        RustGPUShader::Mouse => wgpu::include_spirv!(env!("mouse_shader.spv")),
+    let (label, spirv) = match shader {
+        RustGPUShader::Simplest => ("simplest_shader", shaders::SIMPLEST_SHADER),
+        RustGPUShader::Sky => ("sky_shader", shaders::SKY_SHADER),
+        RustGPUShader::Compute => ("compute_shader", shaders::COMPUTE_SHADER),
+        RustGPUShader::Mouse => ("mouse_shader", shaders::MOUSE_SHADER),
+    };
+    wgpu::ShaderModuleDescriptor {
+        label: Some(label),
+        source: wgpu::ShaderSource::SpirV(Cow::Borrowed(spirv)),
+        flags: wgpu::ShaderFlags::VALIDATION,
     }
 }
 
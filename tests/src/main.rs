@@ -119,6 +119,7 @@ impl Runner {
                 ),
                 &*format!("--extern spirv_std={}", deps.spirv_std.display()),
                 &*format!("--extern glam={}", deps.glam.display()),
+                &*format!("--extern libm={}", deps.libm.display()),
                 "--crate-type dylib",
                 "-Zunstable-options",
                 "-Zcrate-attr=no_std",
@@ -197,6 +198,7 @@ fn build_deps(deps_target_dir: &Path, codegen_backend_path: &Path, target: &str)
     let core = find_lib(deps_target_dir, "core", DepKind::SpirvLib, target).unwrap();
     let spirv_std = find_lib(deps_target_dir, "spirv_std", DepKind::SpirvLib, target).unwrap();
     let glam = find_lib(deps_target_dir, "glam", DepKind::SpirvLib, target).unwrap();
+    let libm = find_lib(deps_target_dir, "libm", DepKind::SpirvLib, target).unwrap();
     let spirv_std_macros = find_lib(
         deps_target_dir,
         "spirv_std_macros",
@@ -210,6 +212,7 @@ fn build_deps(deps_target_dir: &Path, codegen_backend_path: &Path, target: &str)
         &core,
         &spirv_std,
         &glam,
+        &libm,
         &spirv_std_macros,
     ]
     .iter()
@@ -221,6 +224,7 @@ fn build_deps(deps_target_dir: &Path, codegen_backend_path: &Path, target: &str)
         TestDeps {
             core: core.unwrap(),
             glam: glam.unwrap(),
+            libm: libm.unwrap(),
             compiler_builtins: compiler_builtins.unwrap(),
             spirv_std: spirv_std.unwrap(),
             spirv_std_macros: spirv_std_macros.unwrap(),
@@ -289,6 +293,7 @@ struct TestDeps {
     spirv_std: PathBuf,
     spirv_std_macros: PathBuf,
     glam: PathBuf,
+    libm: PathBuf,
 }
 
 /// The RUSTFLAGS passed to all SPIR-V builds.
@@ -0,0 +1,17 @@
+// build-pass
+
+#![feature(const_generics)]
+#![allow(incomplete_features)]
+
+use spirv_std::memory::{Scope, Semantics};
+
+#[spirv(compute(threads(64)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [f32]) {
+    let reference = &mut buffer[0];
+
+    let _old = unsafe {
+        spirv_std::arch::atomic_f_add::<_, { Scope::Workgroup }, { Semantics::None as u32 }>(
+            reference, 1.5,
+        )
+    };
+}
@@ -0,0 +1,9 @@
+// build-pass
+
+use spirv_std::arch::subgroup;
+
+#[spirv(compute(threads(64)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [u32]) {
+    let ballot: glam::UVec4 = subgroup::subgroup_ballot(buffer[0] > 5);
+    buffer[1] = subgroup::subgroup_ballot_bit_count(ballot);
+}
@@ -0,0 +1,12 @@
+// build-pass
+
+use spirv_std::arch::subgroup;
+
+#[spirv(compute(threads(64)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [f32]) {
+    let value = buffer[0];
+    let first = subgroup::subgroup_broadcast_first(value);
+    let third = subgroup::subgroup_broadcast::<_, 3>(value);
+    let vector = subgroup::subgroup_broadcast_first_vector(glam::Vec2::new(value, first));
+    buffer[1] = first + third + vector.x;
+}
@@ -0,0 +1,10 @@
+// build-pass
+
+use spirv_std::arch::subgroup;
+
+#[spirv(compute(threads(64)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [u32]) {
+    if subgroup::subgroup_elect() {
+        buffer[0] = 1;
+    }
+}
@@ -0,0 +1,13 @@
+// build-pass
+
+use spirv_std::arch::subgroup;
+
+#[spirv(compute(threads(64)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [u32]) {
+    let value = buffer[0];
+    let sum = subgroup::subgroup_i_add(value);
+    let inclusive = subgroup::subgroup_inclusive_i_add(value);
+    let exclusive = subgroup::subgroup_exclusive_i_add(value);
+    let vector = subgroup::subgroup_i_add_vector(glam::UVec2::new(value, sum));
+    buffer[1] = sum + inclusive + exclusive + vector.y;
+}
@@ -0,0 +1,14 @@
+// build-pass
+
+use spirv_std::arch::subgroup;
+
+#[spirv(compute(threads(64)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [f32]) {
+    let value = buffer[0];
+    let min = subgroup::subgroup_f_min(value);
+    let max = subgroup::subgroup_inclusive_f_max(value);
+    let signed = subgroup::subgroup_s_max(value as i32);
+    let unsigned = subgroup::subgroup_exclusive_u_min(value as u32);
+    let bits = subgroup::subgroup_xor(unsigned) | subgroup::subgroup_and(unsigned);
+    buffer[1] = min + max + signed as f32 + bits as f32;
+}
@@ -0,0 +1,13 @@
+// build-pass
+
+use spirv_std::arch::subgroup;
+
+#[spirv(compute(threads(64)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [f32]) {
+    let value = buffer[0];
+    let broadcast = subgroup::subgroup_quad_broadcast::<_, 2>(value);
+    let horizontal = subgroup::subgroup_quad_swap::<_, 0>(value);
+    let diagonal =
+        subgroup::subgroup_quad_swap_vector::<_, _, 2, 2>(glam::Vec2::new(value, broadcast));
+    buffer[1] = broadcast + horizontal + diagonal.x;
+}
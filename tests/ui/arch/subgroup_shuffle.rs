@@ -0,0 +1,13 @@
+// build-pass
+
+use spirv_std::arch::subgroup;
+
+#[spirv(compute(threads(64)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [u32]) {
+    let value = buffer[0];
+    let shuffled = subgroup::subgroup_shuffle(value, 4);
+    let xored = subgroup::subgroup_shuffle_xor(value, 1);
+    let up = subgroup::subgroup_shuffle_up(value, 2);
+    let down = subgroup::subgroup_shuffle_down(value, 2);
+    buffer[1] = shuffled + xored + up + down;
+}
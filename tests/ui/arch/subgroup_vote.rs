@@ -0,0 +1,15 @@
+// build-pass
+
+use spirv_std::arch::subgroup;
+
+#[spirv(compute(threads(64)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [u32]) {
+    let value = buffer[0];
+    let all = subgroup::subgroup_all(value > 5);
+    let any = subgroup::subgroup_any(value > 5);
+    let all_equal = subgroup::subgroup_all_equal(value);
+    let all_equal_vector = subgroup::subgroup_all_equal_vector(glam::UVec2::new(value, 1));
+    if all && any && all_equal && all_equal_vector {
+        buffer[1] = 1;
+    }
+}
@@ -0,0 +1,21 @@
+// build-pass
+
+#[spirv(mesh_nv(
+    threads(1),
+    output_vertices = 3,
+    output_primitives_nv = 1,
+    output_triangles_nv
+))]
+pub fn main(
+    #[spirv(position)] positions: &mut [glam::Vec4; 3],
+    #[spirv(primitive_count_nv)] primitive_count: &mut u32,
+    #[spirv(primitive_indices_nv)] _indices: &mut [u32; 3],
+) {
+    positions[0] = glam::vec4(-0.5, 0.5, 0.0, 1.0);
+    positions[1] = glam::vec4(0.5, 0.5, 0.0, 1.0);
+    positions[2] = glam::vec4(0.0, -0.5, 0.0, 1.0);
+    *primitive_count = 1;
+    unsafe {
+        spirv_std::arch::write_packed_primitive_indices_4x8_nv(0, 0x00_02_01_00);
+    }
+}
@@ -0,0 +1,17 @@
+// Test `OpImageFetch` on integer-sampled, multisampled and texel buffer images
+// build-pass
+
+use spirv_std::{arch, Image2d, Image2dMultisampled, ImageBuffer};
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0)] image: &Image2d<u32>,
+    #[spirv(descriptor_set = 0, binding = 1)] multisampled: &Image2dMultisampled<i32>,
+    #[spirv(descriptor_set = 0, binding = 2)] buffer: &ImageBuffer<u32>,
+    output: &mut glam::UVec4,
+    signed_output: &mut glam::IVec4,
+) {
+    let texel: glam::UVec4 = image.fetch(glam::IVec2::new(0, 1));
+    *output = texel + buffer.fetch::<glam::UVec4, _>(3);
+    *signed_output = multisampled.fetch(glam::IVec2::new(0, 1), 2);
+}
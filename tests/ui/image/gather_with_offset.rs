@@ -0,0 +1,28 @@
+// Test `OpImageGather` with `ConstOffset`, `Offset` and `ConstOffsets`
+// build-pass
+
+#![feature(const_generics)]
+#![allow(incomplete_features)]
+
+use spirv_std::{arch, GatherOffsets, Image2d, Image2dArray, Sampler};
+
+struct Pcf;
+
+impl GatherOffsets for Pcf {
+    const OFFSETS: [[i32; 2]; 4] = [[-1, -1], [1, -1], [-1, 1], [1, 1]];
+}
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0)] image2d: &Image2d,
+    #[spirv(descriptor_set = 1, binding = 1)] image2d_array: &Image2dArray,
+    #[spirv(descriptor_set = 2, binding = 2)] sampler: &Sampler,
+    #[spirv(flat)] offset: glam::IVec2,
+    output: &mut glam::Vec4,
+) {
+    let v2 = glam::Vec2::new(0.0, 1.0);
+    let v3 = glam::Vec3::new(0.0, 1.0, 0.5);
+    *output = image2d.gather_with_const_offset::<_, _, 0, 1, { -1 }>(*sampler, v2);
+    *output += image2d_array.gather_with_offset::<_, _, _, 2>(*sampler, v3, offset);
+    *output += image2d.gather_with_const_offsets::<_, _, Pcf, 1>(*sampler, v2);
+}
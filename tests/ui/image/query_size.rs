@@ -0,0 +1,23 @@
+// Test `OpImageQuerySizeLod`, `OpImageQuerySize`, `OpImageQueryLevels` and `OpImageQuerySamples`
+// build-pass
+
+use spirv_std::{arch, Image1d, Image2d, Image2dMultisampled, StorageImage3d};
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0)] image1d: &Image1d,
+    #[spirv(descriptor_set = 0, binding = 1)] image2d: &Image2d,
+    #[spirv(descriptor_set = 0, binding = 2)] multisampled: &Image2dMultisampled,
+    #[spirv(descriptor_set = 0, binding = 3)] storage: &StorageImage3d,
+    output: &mut glam::UVec4,
+) {
+    let size: glam::UVec2 = image2d.query_size_lod(0);
+    let ms_size: glam::UVec2 = multisampled.query_size();
+    let storage_size: glam::UVec3 = storage.query_size();
+    *output = glam::UVec4::new(
+        image1d.query_size_lod(1) + size.x + ms_size.y,
+        image2d.query_levels(),
+        multisampled.query_samples(),
+        storage_size.z,
+    );
+}
@@ -0,0 +1,19 @@
+// Test `OpImageQuerySizeLod`, `OpImageQueryLevels` and `OpImageQuerySize` on the 2D image types
+// build-pass
+
+use spirv_std::{arch, Cubemap, Image2d, Image2dArray, StorageImage2d};
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0)] image2d: &Image2d,
+    #[spirv(descriptor_set = 1, binding = 1)] image2d_array: &Image2dArray,
+    #[spirv(descriptor_set = 2, binding = 2)] cubemap: &Cubemap,
+    #[spirv(descriptor_set = 3, binding = 3)] storage: &StorageImage2d,
+    output: &mut glam::UVec3,
+) {
+    let size: glam::UVec2 = image2d.query_size_lod(0);
+    let array_size: glam::UVec3 = image2d_array.query_size_lod(1);
+    let cube_size: glam::UVec2 = cubemap.query_size_lod(2);
+    let storage_size: glam::UVec2 = storage.query_size();
+    *output = array_size + (size + cube_size + storage_size).extend(cubemap.query_levels());
+}
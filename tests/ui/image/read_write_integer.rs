@@ -0,0 +1,19 @@
+// Test `OpImageRead` and `OpImageWrite` on integer 3D and texel buffer storage images
+// build-pass
+
+use spirv_std::{arch, StorageImage3d, StorageImageBuffer};
+
+#[spirv(compute(threads(1)))]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0)] image: &StorageImage3d<u32>,
+    #[spirv(descriptor_set = 0, binding = 1)] buffer: &StorageImageBuffer<i32>,
+) {
+    unsafe {
+        asm!("OpCapability StorageImageReadWithoutFormat");
+        asm!("OpCapability StorageImageWriteWithoutFormat");
+        let texel: glam::UVec4 = image.read(glam::IVec3::new(0, 1, 2));
+        image.write(glam::IVec3::new(2, 1, 0), texel);
+        let texel: glam::IVec4 = buffer.read(7);
+        buffer.write(8, texel);
+    }
+}
@@ -0,0 +1,22 @@
+// Test `OpImageSampleImplicitLod` on the 1D, 3D, 1D-array and cube-array image types
+// build-pass
+
+use spirv_std::{arch, CubemapArray, Image1d, Image1dArray, Image3d, Sampler};
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0)] image1d: &Image1d,
+    #[spirv(descriptor_set = 0, binding = 1)] image3d: &Image3d,
+    #[spirv(descriptor_set = 0, binding = 2)] image1d_array: &Image1dArray,
+    #[spirv(descriptor_set = 0, binding = 3)] cubemap_array: &CubemapArray,
+    #[spirv(descriptor_set = 1, binding = 0)] sampler: &Sampler,
+    output: &mut glam::Vec4,
+) {
+    let v2 = glam::Vec2::new(0.0, 1.0);
+    let v3 = glam::Vec3::new(0.0, 1.0, 0.5);
+    let v4 = glam::Vec4::new(0.0, 1.0, 0.5, 2.0);
+    *output = image1d.sample(*sampler, 0.5);
+    *output += image3d.sample(*sampler, v3);
+    *output += image1d_array.sample(*sampler, v2);
+    *output += cubemap_array.sample(*sampler, v4);
+}
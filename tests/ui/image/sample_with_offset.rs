@@ -0,0 +1,19 @@
+// Test `OpImageSampleImplicitLod` with `ConstOffset` and `Offset`
+// build-pass
+
+use spirv_std::{arch, Image2d, Image2dArray, Sampler};
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0)] image2d: &Image2d,
+    #[spirv(descriptor_set = 1, binding = 1)] image2d_array: &Image2dArray,
+    #[spirv(descriptor_set = 2, binding = 2)] sampler: &Sampler,
+    #[spirv(flat)] offset: glam::IVec2,
+    output: &mut glam::Vec4,
+) {
+    let v2 = glam::Vec2::new(0.0, 1.0);
+    let v3 = glam::Vec3::new(0.0, 1.0, 0.5);
+    *output = image2d.sample_with_const_offset::<_, _, 1, { -1 }>(*sampler, v2);
+    *output += image2d_array.sample_with_const_offset::<_, _, 0, 2>(*sampler, v3);
+    *output += image2d.sample_with_offset(*sampler, v2, offset);
+}
@@ -0,0 +1,28 @@
+// Test that arrayed multisampled storage images declare both `StorageImageMultisample`
+// and `ImageMSArray` (checked by spirv-val).
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(image_type(
+    dim = "Dim2D",
+    depth = 0,
+    arrayed = 1,
+    multisampled = 1,
+    sampled = 2,
+    image_format = "Rgba8"
+))]
+#[derive(Copy, Clone)]
+pub struct StorageImage2dMultisampledArray {
+    _x: u32,
+}
+
+#[spirv(fragment)]
+pub fn main(#[spirv(descriptor_set = 0, binding = 0)] image: &StorageImage2dMultisampledArray) {
+    unsafe {
+        asm!(
+            "%image = OpLoad _ {0}",
+            in(reg) image,
+        );
+    }
+}
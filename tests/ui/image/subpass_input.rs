@@ -0,0 +1,12 @@
+// Test `OpImageRead` on a subpass input attachment
+// build-pass
+
+use spirv_std::{arch, SubpassInput};
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0, input_attachment_index = 0)] color: &SubpassInput,
+    output: &mut glam::Vec4,
+) {
+    *output = color.read(glam::IVec2::new(0, 0));
+}
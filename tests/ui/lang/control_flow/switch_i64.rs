@@ -0,0 +1,14 @@
+// Test matching on signed 64-bit values, including negative ones.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(compute(threads(1)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [i64]) {
+    buffer[1] = match buffer[0] {
+        i64::MIN => 1,
+        -1 => 2,
+        0x7fff_ffff_ffff => 3,
+        _ => 4,
+    };
+}
@@ -0,0 +1,14 @@
+// Test matching on 64-bit values, including ones above `u32::MAX`.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(compute(threads(1)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [u64]) {
+    buffer[1] = match buffer[0] {
+        0 => 1,
+        0x1_0000_0000 => 2,
+        u64::MAX => 3,
+        _ => 4,
+    };
+}
@@ -0,0 +1,12 @@
+// Test changing the default scope of atomics with `--atomic-scope`.
+// build-pass
+// compile-flags: -C llvm-args=--atomic-scope=queue_family
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use spirv_std as _;
+
+#[spirv(compute(threads(64)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [u32; 2]) {
+    let atomic = unsafe { &*(&mut buffer[0] as *mut u32 as *const AtomicU32) };
+    buffer[1] = atomic.fetch_add(1, Ordering::AcqRel);
+}
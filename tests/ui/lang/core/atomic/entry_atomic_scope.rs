@@ -0,0 +1,18 @@
+// Test overriding the scope of atomics with `atomic_scope` on an entry point.
+// build-pass
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use spirv_std as _;
+
+fn increment(value: &mut u32) -> u32 {
+    let atomic = unsafe { &*(value as *mut u32 as *const AtomicU32) };
+    atomic.fetch_add(1, Ordering::Relaxed)
+}
+
+#[spirv(compute(threads(64), atomic_scope = "workgroup"))]
+pub fn main(
+    #[spirv(workgroup)] counter: &mut u32,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut u32,
+) {
+    *buffer = increment(counter);
+}
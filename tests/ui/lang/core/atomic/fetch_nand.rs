@@ -0,0 +1,11 @@
+// Test `fetch_nand`, which has no SPIR-V instruction and is lowered to a compare-exchange loop.
+// build-pass
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use spirv_std as _;
+
+#[spirv(compute(threads(64)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [u32; 2]) {
+    let atomic = unsafe { &*(&mut buffer[0] as *mut u32 as *const AtomicU32) };
+    buffer[1] = atomic.fetch_nand(0xf0f0_f0f0, Ordering::Relaxed);
+}
@@ -0,0 +1,43 @@
+// Test dynamic dispatch through `&dyn Trait`, whose vtable is a constant array of function
+// indices, and whose erased receiver is cast back after inlining.
+// build-pass
+
+use spirv_std as _;
+
+trait Material {
+    fn shade(&self, x: f32) -> f32;
+}
+
+struct Constant(f32);
+
+impl Material for Constant {
+    fn shade(&self, _: f32) -> f32 {
+        self.0
+    }
+}
+
+struct Scale {
+    factor: f32,
+    offset: f32,
+}
+
+impl Material for Scale {
+    fn shade(&self, x: f32) -> f32 {
+        x * self.factor + self.offset
+    }
+}
+
+fn shade(material: &dyn Material, x: f32) -> f32 {
+    material.shade(x)
+}
+
+#[spirv(fragment)]
+pub fn main(input: f32, output: &mut f32) {
+    let constant = Constant(0.5);
+    let scale = Scale {
+        factor: 2.0,
+        offset: 1.0,
+    };
+    let material: &dyn Material = if input > 0.0 { &constant } else { &scale };
+    *output = shade(material, input);
+}
@@ -0,0 +1,23 @@
+// Test calling function pointers chosen at runtime, which are lowered to a switch over the
+// functions they can point to.
+// build-pass
+
+use spirv_std as _;
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn square(x: u32) -> u32 {
+    x * x
+}
+
+fn apply(f: fn(u32) -> u32, x: u32) -> u32 {
+    f(x)
+}
+
+#[spirv(fragment)]
+pub fn main(#[spirv(flat)] x: u32, output: &mut u32) {
+    let f: fn(u32) -> u32 = if x > 8 { double } else { square };
+    *output = apply(f, x);
+}
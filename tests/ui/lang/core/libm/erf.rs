@@ -0,0 +1,9 @@
+// Test that `erf` is lowered to an approximation (or OpenCL's `erf`).
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(i: f32, o: &mut f32) {
+    *o = libm::erff(i);
+}
@@ -0,0 +1,10 @@
+// Test that `erf` and `erfc` on `f64` are lowered to their `f64` approximation.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(compute(threads(1)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [f64; 3]) {
+    buffer[1] = libm::erf(buffer[0]);
+    buffer[2] = libm::erfc(buffer[0]);
+}
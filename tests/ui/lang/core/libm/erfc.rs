@@ -0,0 +1,9 @@
+// Test that `erfc` is lowered to an approximation (or OpenCL's `erfc`).
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(i: f32, o: &mut f32) {
+    *o = libm::erfcf(i);
+}
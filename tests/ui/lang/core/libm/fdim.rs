@@ -0,0 +1,9 @@
+// Test that `fdim` works.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(a: f32, b: f32, o: &mut f32) {
+    *o = libm::fdimf(a, b);
+}
@@ -0,0 +1,9 @@
+// Test that `hypot` works through `num_traits::Float`.
+// build-pass
+
+use spirv_std::num_traits::Float;
+
+#[spirv(fragment)]
+pub fn main(a: f32, b: f32, o: &mut f32) {
+    *o = a.hypot(b);
+}
@@ -0,0 +1,9 @@
+// Test that `ilogb` works.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(i: f32, #[spirv(flat)] o: &mut i32) {
+    *o = libm::ilogbf(i);
+}
@@ -0,0 +1,9 @@
+// Test that `j1` is lowered to an approximation.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(i: f32, o: &mut f32) {
+    *o = libm::j1f(i);
+}
@@ -0,0 +1,9 @@
+// Test that `jn` is lowered to a recurrence over `j0` and `j1`.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(#[spirv(flat)] n: i32, x: f32, o: &mut f32) {
+    *o = libm::jnf(n, x);
+}
@@ -0,0 +1,9 @@
+// Test that `lgamma` is lowered to an approximation (or OpenCL's `lgamma`).
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(i: f32, o: &mut f32) {
+    *o = libm::lgammaf(i);
+}
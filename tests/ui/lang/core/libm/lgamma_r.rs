@@ -0,0 +1,11 @@
+// Test that `lgamma_r` returns both `lgamma` and the sign of `gamma`.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(i: f32, o: &mut f32, #[spirv(flat)] sign: &mut i32) {
+    let (lgamma, s) = libm::lgammaf_r(i);
+    *o = lgamma;
+    *sign = s;
+}
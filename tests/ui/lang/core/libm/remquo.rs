@@ -0,0 +1,11 @@
+// Test that `remquo` returns both the remainder and the quotient.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(a: f32, b: f32, o: &mut f32, #[spirv(flat)] quo: &mut i32) {
+    let (r, q) = libm::remquof(a, b);
+    *o = r;
+    *quo = q;
+}
@@ -0,0 +1,12 @@
+// Test that `remquo` reduces the quotient before converting it, even for quotients that don't
+// fit in an `i32`.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(b: f32, o: &mut f32, #[spirv(flat)] quo: &mut i32) {
+    let (r, q) = libm::remquof(1.0e30, b);
+    *o = r;
+    *quo = q;
+}
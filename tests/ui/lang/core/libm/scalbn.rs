@@ -0,0 +1,9 @@
+// Test that `scalbn` is lowered to `Ldexp`.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(x: f32, #[spirv(flat)] n: i32, o: &mut f32) {
+    *o = libm::scalbnf(x, n);
+}
@@ -0,0 +1,9 @@
+// Test that `y0` is lowered to an approximation.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(i: f32, o: &mut f32) {
+    *o = libm::y0f(i);
+}
@@ -0,0 +1,9 @@
+// Test that `yn` is lowered to a recurrence over `y0` and `y1`.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(#[spirv(flat)] n: i32, x: f32, o: &mut f32) {
+    *o = libm::ynf(n, x);
+}
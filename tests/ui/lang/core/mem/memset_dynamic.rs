@@ -0,0 +1,31 @@
+// Tests `memset` with sizes and fill bytes only known at runtime, on runtime
+// arrays, and zero-filling pointers.
+// build-pass
+
+use core::mem::MaybeUninit;
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(flat)] fill: u32,
+    #[spirv(flat)] count: u32,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] words: &mut [u32],
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 1)] floats: &mut [f32],
+    output: &mut u32,
+) {
+    unsafe {
+        // Dynamic size, constant pattern.
+        core::ptr::write_bytes(words.as_mut_ptr(), 0, count as usize);
+        // Dynamic size and pattern.
+        core::ptr::write_bytes(floats.as_mut_ptr(), fill as u8, count as usize);
+    }
+
+    // Constant size, dynamic pattern.
+    let mut local = [0u32; 4];
+    unsafe {
+        core::ptr::write_bytes(local.as_mut_ptr(), fill as u8, local.len());
+    }
+
+    let pointers: [Option<&u32>; 2] = unsafe { MaybeUninit::zeroed().assume_init() };
+    *output = local[1] + pointers.len() as u32;
+}
@@ -0,0 +1,27 @@
+// Tests `memset` on aggregates, through `MaybeUninit::zeroed`, array
+// default-initialization and `ptr::write_bytes`.
+// build-pass
+
+use core::mem::MaybeUninit;
+use spirv_std as _;
+
+#[derive(Copy, Clone, Default)]
+#[repr(C)]
+pub struct Light {
+    pub color: [f32; 3],
+    pub intensity: f32,
+    pub enabled: u32,
+}
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] lights: &mut [Light],
+) {
+    let zeroed: Light = unsafe { MaybeUninit::zeroed().assume_init() };
+    let mut defaults = [Light::default(); 4];
+    unsafe {
+        core::ptr::write_bytes(&mut defaults[1], 0xff, 1);
+    }
+    lights[0] = defaults[1];
+    lights[1] = zeroed;
+}
@@ -0,0 +1,15 @@
+// Test ordering comparisons on `bool`s.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(#[spirv(flat)] a: u32, #[spirv(flat)] b: u32, out: &mut u32) {
+    let (x, y) = (a != 0, b != 0);
+    let lt = (x < y) as u32;
+    let le = (x <= y) as u32;
+    let gt = (x > y) as u32;
+    let ge = (x >= y) as u32;
+    let max = x.max(y) as u32;
+    *out = lt | le << 1 | gt << 2 | ge << 3 | max << 4;
+}
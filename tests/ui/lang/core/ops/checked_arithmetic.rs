@@ -0,0 +1,25 @@
+// Test checked integer arithmetic, both explicit and through overflow checks.
+// build-pass
+// compile-flags: -C overflow-checks=on
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(flat)] a: u32,
+    #[spirv(flat)] b: u32,
+    #[spirv(flat)] c: i32,
+    #[spirv(flat)] d: i32,
+    out_u: &mut u32,
+    out_i: &mut i32,
+) {
+    let sum = a.checked_add(b).unwrap_or(0);
+    let diff = a.checked_sub(b).unwrap_or(0);
+    let prod = a.checked_mul(b).unwrap_or(0);
+    *out_u = sum + diff * prod;
+
+    let sum = c.checked_add(d).unwrap_or(0);
+    let diff = c.checked_sub(d).unwrap_or(0);
+    let prod = c.checked_mul(d).unwrap_or(0);
+    *out_i = sum + diff * prod;
+}
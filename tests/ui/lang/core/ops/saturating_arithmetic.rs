@@ -0,0 +1,17 @@
+// Test saturating integer arithmetic.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(flat)] a: u32,
+    #[spirv(flat)] b: u32,
+    #[spirv(flat)] c: i32,
+    #[spirv(flat)] d: i32,
+    out_u: &mut u32,
+    out_i: &mut i32,
+) {
+    *out_u = a.saturating_sub(b).saturating_add(b);
+    *out_i = c.saturating_sub(d).saturating_add(d);
+}
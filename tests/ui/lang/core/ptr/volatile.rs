@@ -0,0 +1,12 @@
+// Test volatile loads and stores through a storage buffer.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(compute(threads(1)))]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [u32; 2]) {
+    unsafe {
+        let value = core::ptr::read_volatile(&buffer[0]);
+        core::ptr::write_volatile(&mut buffer[1], value + 1);
+    }
+}
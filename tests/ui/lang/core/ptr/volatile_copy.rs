@@ -0,0 +1,21 @@
+// Test volatile copies between storage buffers and locals, validated with the Vulkan memory model
+// (where the copy gets no availability/visibility operands before SPIR-V 1.4).
+// build-pass
+
+#![feature(core_intrinsics)]
+
+use core::intrinsics::volatile_copy_nonoverlapping_memory;
+use spirv_std as _;
+
+#[spirv(compute(threads(1)))]
+pub fn main(
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] input: &[u32; 4],
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 1)] output: &mut [u32; 4],
+) {
+    let mut local = [0u32; 4];
+    unsafe {
+        volatile_copy_nonoverlapping_memory(&mut local, input, 1);
+        local[0] += 1;
+        volatile_copy_nonoverlapping_memory(output, &local, 1);
+    }
+}
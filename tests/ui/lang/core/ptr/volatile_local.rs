@@ -0,0 +1,13 @@
+// Test volatile accesses to function-local memory, which can't be `NonPrivatePointer`.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(#[spirv(flat)] i: u32, #[spirv(flat)] o: &mut u32) {
+    let mut local = 0;
+    unsafe {
+        core::ptr::write_volatile(&mut local, i);
+        *o = core::ptr::read_volatile(&local);
+    }
+}
@@ -0,0 +1,22 @@
+// Test that OpString/OpSource/OpLine and local OpNames, emitted with debuginfo enabled,
+// survive linking and pass spirv-val.
+// build-pass
+// compile-flags: -C debuginfo=2
+
+use spirv_std as _;
+
+fn scale(x: f32, factor: f32) -> f32 {
+    let scaled = x * factor;
+    scaled
+}
+
+#[spirv(fragment)]
+pub fn main(input: f32, output: &mut f32) {
+    let mut sum = 0.0;
+    let mut i = 0;
+    while i < 4 {
+        sum += scale(input, i as f32);
+        i += 1;
+    }
+    *output = sum;
+}
@@ -0,0 +1,20 @@
+// Test KHR ray tracing builtins in a closest hit shader
+// build-pass
+
+#[spirv(closest_hit_khr)]
+pub fn main(
+    #[spirv(launch_id_khr)] launch_id: glam::UVec3,
+    #[spirv(world_ray_direction_khr)] world_ray_direction: glam::Vec3,
+    #[spirv(ray_tmax_khr)] ray_tmax: f32,
+    #[spirv(hit_kind_khr)] hit_kind: u32,
+    #[spirv(incoming_ray_payload_khr)] payload: &mut glam::Vec3,
+) {
+    unsafe {
+        asm!(
+            "OpExtension \"SPV_KHR_ray_tracing\"",
+            "OpCapability RayTracingKHR"
+        );
+    }
+    *payload =
+        world_ray_direction * ray_tmax + glam::vec3(launch_id.x as f32, hit_kind as f32, 0.0);
+}
@@ -0,0 +1,9 @@
+// Test `OpExecuteCallableKHR` from a ray generation shader
+// build-pass
+
+#[spirv(ray_generation_khr)]
+pub fn main(#[spirv(callable_data_khr)] data: &glam::Vec3) {
+    unsafe {
+        spirv_std::ray_tracing::execute_callable(data, 0);
+    }
+}
@@ -0,0 +1,9 @@
+// Test `OpIgnoreIntersectionKHR` from an any hit shader
+// build-pass
+
+#[spirv(any_hit_khr)]
+pub fn main() {
+    unsafe {
+        spirv_std::ray_tracing::ignore_intersection();
+    }
+}
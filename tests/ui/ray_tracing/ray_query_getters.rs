@@ -0,0 +1,35 @@
+// Test the candidate/committed getters of an inline ray query in a fragment shader
+// build-pass
+
+use spirv_std::ray_query;
+use spirv_std::ray_tracing::{AccelerationStructure, CommittedIntersection, RayFlags};
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0)] acceleration_structure: &AccelerationStructure,
+    output: &mut glam::Vec4,
+) {
+    ray_query!(let mut query);
+    query.initialize(
+        acceleration_structure,
+        RayFlags::OPAQUE | RayFlags::TERMINATE_ON_FIRST_HIT,
+        0xff,
+        glam::vec3(0.0, 0.0, 0.0),
+        0.001,
+        glam::vec3(0.0, 0.0, 1.0),
+        1000.0,
+    );
+
+    if query.proceed() {
+        let barycentrics: glam::Vec2 = query.candidate_barycentrics();
+        if query.candidate_front_face() {
+            query.terminate();
+        }
+        *output = barycentrics.extend(query.candidate_t()).extend(0.0);
+    }
+
+    if query.committed_intersection_type() == CommittedIntersection::Triangle {
+        let origin: glam::Vec3 = query.committed_object_ray_origin();
+        *output = origin.extend(query.committed_primitive_index() as f32);
+    }
+}
@@ -0,0 +1,31 @@
+// Test an inline ray query traversal loop in a compute shader
+// build-pass
+
+use spirv_std::ray_query;
+use spirv_std::ray_tracing::{AccelerationStructure, CandidateIntersection, RayFlags};
+
+#[spirv(compute(threads(1)))]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0)] acceleration_structure: &AccelerationStructure,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 1)] output: &mut [f32],
+) {
+    ray_query!(let mut query);
+    query.initialize(
+        acceleration_structure,
+        RayFlags::NONE,
+        0xff,
+        glam::vec3(0.0, 0.0, 0.0),
+        0.001,
+        glam::vec3(0.0, 0.0, 1.0),
+        1000.0,
+    );
+
+    while query.proceed() {
+        match query.candidate_intersection_type() {
+            CandidateIntersection::Triangle => query.confirm_intersection(),
+            CandidateIntersection::Aabb => query.generate_intersection(0.5),
+        }
+    }
+
+    output[0] = query.committed_t();
+}
@@ -0,0 +1,9 @@
+// Test `OpReportIntersectionKHR` from an intersection shader
+// build-pass
+
+#[spirv(intersection_khr)]
+pub fn main() {
+    unsafe {
+        spirv_std::ray_tracing::report_intersection(2.0, 4);
+    }
+}
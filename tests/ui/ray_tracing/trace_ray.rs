@@ -0,0 +1,25 @@
+// Test `OpTraceRayKHR` from a ray generation shader
+// build-pass
+
+use spirv_std::ray_tracing::{AccelerationStructure, RayFlags};
+
+#[spirv(ray_generation_khr)]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0)] acceleration_structure: &AccelerationStructure,
+    #[spirv(ray_payload_khr)] payload: &mut glam::Vec3,
+) {
+    unsafe {
+        acceleration_structure.trace_ray(
+            RayFlags::OPAQUE | RayFlags::TERMINATE_ON_FIRST_HIT,
+            0xff,
+            0,
+            0,
+            0,
+            glam::vec3(1.0, 2.0, 3.0),
+            0.5,
+            glam::vec3(3.0, 2.0, 1.0),
+            1.0,
+            payload,
+        );
+    }
+}
@@ -0,0 +1,28 @@
+// Tests that the `#[spirv(block(layout = "..."))]` of the elements of slices and arrays
+// is checked too.
+// build-fail
+
+use spirv_std as _;
+
+#[spirv(block(layout = "std430"))]
+#[derive(Copy, Clone)]
+#[repr(C, packed(2))]
+pub struct Std430Entry {
+    pub flag: u16,
+    pub value: f32,
+}
+
+#[spirv(block(layout = "scalar"))]
+#[derive(Copy, Clone)]
+#[repr(C, packed(2))]
+pub struct ScalarEntry {
+    pub flag: u16,
+    pub value: f32,
+}
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] std430: &[Std430Entry],
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 1)] scalar: &mut [ScalarEntry; 2],
+) {
+}
@@ -0,0 +1,16 @@
+// Tests that blocks not laid out according to their `#[spirv(block(layout = "..."))]`
+// are errors naming the misplaced field.
+// build-fail
+
+use spirv_std as _;
+
+#[spirv(block(layout = "std140"))]
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct Material {
+    pub roughness: f32,
+    pub weights: [f32; 4],
+}
+
+#[spirv(fragment)]
+pub fn main(#[spirv(uniform, descriptor_set = 0, binding = 0)] material: &Material) {}
@@ -0,0 +1,30 @@
+// Tests that blocks laid out according to their `#[spirv(block(layout = "..."))]` pass.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(block(layout = "std430"))]
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct Lights {
+    pub count: u32,
+    pub intensities: [f32; 3],
+    pub colors: [[f32; 4]; 2],
+}
+
+#[spirv(block(layout = "scalar"))]
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct Params {
+    pub scale: f32,
+    pub offset: [f32; 3],
+}
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] lights: &mut Lights,
+    #[spirv(push_constant)] params: &Params,
+) {
+    lights.count += 1;
+    lights.intensities[0] *= params.scale + params.offset[2];
+}
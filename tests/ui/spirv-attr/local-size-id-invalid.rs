@@ -0,0 +1,10 @@
+// Tests that `local_size_id_*` is rejected where it would be ignored.
+// build-fail
+
+use spirv_std as _;
+
+#[spirv(fragment(local_size_id_x = 0))]
+pub fn fragment() {}
+
+#[spirv(compute(local_size_id_x = 0))]
+pub fn compute() {}
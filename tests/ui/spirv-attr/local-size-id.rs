@@ -0,0 +1,7 @@
+// Tests that `local_size_id_*` turns `threads` dimensions into specialization constants.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(compute(threads(64, 4), local_size_id_x = 0, local_size_id_y = 1))]
+pub fn main() {}
@@ -0,0 +1,10 @@
+// Tests that the SPV_EXT_mesh_shader execution models are rejected with a pointer to the NV ones
+// build-fail
+
+use spirv_std as _;
+
+#[spirv(mesh_ext(threads(1)))]
+pub fn mesh() {}
+
+#[spirv(task_ext(threads(1)))]
+pub fn task() {}
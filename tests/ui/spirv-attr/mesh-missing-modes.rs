@@ -0,0 +1,7 @@
+// Tests that mesh shaders must declare their output sizes and topology
+// build-fail
+
+use spirv_std as _;
+
+#[spirv(mesh_nv(threads(1)))]
+pub fn main() {}
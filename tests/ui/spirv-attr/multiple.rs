@@ -44,9 +44,14 @@ fn _entry(
     #[spirv(binding = 0, binding = 0)] _same_binding: (),
     #[spirv(binding = 0, binding = 1)] _diff_binding: (),
 
+    #[spirv(input_attachment_index = 0, input_attachment_index = 0)] _same_input_attachment: (),
+    #[spirv(input_attachment_index = 0, input_attachment_index = 1)] _diff_input_attachment: (),
+
     #[spirv(flat, flat)] _flat: (),
 
     #[spirv(invariant, invariant)] _invariant: (),
+
+    #[spirv(per_primitive_nv, per_primitive_nv)] _per_primitive_nv: (),
 ) {
 }
 
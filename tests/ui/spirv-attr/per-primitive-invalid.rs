@@ -0,0 +1,7 @@
+// Tests that the per_primitive_nv attribute can't be applied on vertex outputs
+// build-fail
+
+use spirv_std as _;
+
+#[spirv(vertex)]
+pub fn main(#[spirv(per_primitive_nv)] output: &mut f32) {}
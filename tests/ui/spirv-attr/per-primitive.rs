@@ -0,0 +1,30 @@
+// Tests that the per_primitive_nv attribute works on mesh shader outputs and
+// fragment shader inputs
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(mesh_nv(
+    threads(1),
+    output_vertices = 3,
+    output_primitives_nv = 1,
+    output_triangles_nv
+))]
+pub fn mesh(
+    #[spirv(position)] positions: &mut [glam::Vec4; 3],
+    #[spirv(primitive_count_nv)] primitive_count: &mut u32,
+    #[spirv(primitive_indices_nv)] indices: &mut [u32; 3],
+    #[spirv(per_primitive_nv)] primitive_colors: &mut [glam::Vec4; 1],
+) {
+    positions[0] = glam::vec4(-0.5, 0.5, 0.0, 1.0);
+    positions[1] = glam::vec4(0.5, 0.5, 0.0, 1.0);
+    positions[2] = glam::vec4(0.0, -0.5, 0.0, 1.0);
+    *indices = [0, 1, 2];
+    primitive_colors[0] = glam::vec4(1.0, 0.0, 0.0, 1.0);
+    *primitive_count = 1;
+}
+
+#[spirv(fragment)]
+pub fn fragment(#[spirv(per_primitive_nv)] primitive_color: glam::Vec4, output: &mut glam::Vec4) {
+    *output = primitive_color;
+}
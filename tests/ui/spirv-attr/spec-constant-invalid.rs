@@ -0,0 +1,7 @@
+// Tests that `#[spirv(spec_constant)]` entry parameters must be scalars.
+// build-fail
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(#[spirv(spec_constant(id = 0))] _x: u64) {}
@@ -0,0 +1,21 @@
+// Tests that `#[spirv(spec_constant)]` entry parameters are accepted.
+// build-pass
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(spec_constant(id = 0))] _zero: u32,
+    #[spirv(spec_constant(id = 1, default = 5))] count: u32,
+    #[spirv(spec_constant(id = 2, default = 0.5))] scale: f32,
+    #[spirv(spec_constant(id = 3, default = true))] enabled: bool,
+    #[spirv(spec_constant(id = 4, default = "-2"))] offset: i32,
+    #[spirv(spec_constant(id = 5, default = "-0.25"))] bias: f32,
+    output: &mut f32,
+) {
+    *output = if enabled {
+        count as f32 * scale + offset as f32 + bias
+    } else {
+        0.0
+    };
+}
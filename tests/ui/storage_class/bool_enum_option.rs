@@ -0,0 +1,46 @@
+// Test that `bool`s, fieldless `enum`s and `Option`s can be used in interface blocks, where
+// they're stored as `u32`s (and `struct { is_some: u32, value: T }` for `Option<T>`).
+
+// build-pass
+use spirv_std as _;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Mode {
+    Off,
+    Linear = 4,
+    Exponential,
+}
+
+#[derive(Copy, Clone)]
+pub struct Fog {
+    pub enabled: bool,
+    pub mode: Mode,
+    pub density: Option<f32>,
+    pub layers: [bool; 3],
+}
+
+#[derive(Copy, Clone)]
+pub struct ShaderConstants {
+    pub flip: bool,
+    pub mode: Option<Mode>,
+}
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] fog: &Fog,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 1)] state: &Fog,
+    #[spirv(push_constant)] constants: &ShaderConstants,
+    output: &mut f32,
+) {
+    let mut result = 0.0;
+    if fog.enabled && fog.mode == Mode::Linear && fog.layers[1] {
+        result += fog.density.unwrap_or(1.0);
+    }
+    if constants.flip {
+        result = -result;
+    }
+    if constants.mode == Some(state.mode) && state.enabled {
+        result *= state.density.unwrap_or(0.5);
+    }
+    *output = result;
+}
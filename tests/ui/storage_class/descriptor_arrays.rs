@@ -0,0 +1,21 @@
+// Tests that arrays of images, samplers and buffers are bound as descriptor arrays,
+// and can be indexed non-uniformly.
+// build-pass
+
+use spirv_std::arch::non_uniform;
+use spirv_std::{Image2d, Sampler, TypedBuffer};
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(descriptor_set = 0, binding = 0)] textures: &[Image2d],
+    #[spirv(descriptor_set = 0, binding = 1)] samplers: &[Sampler; 4],
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] scales: &[TypedBuffer<f32>; 2],
+    #[spirv(storage_buffer, descriptor_set = 1, binding = 1)] buffers: &mut [TypedBuffer<[u32; 4]>],
+    #[spirv(flat)] index: u32,
+    output: &mut glam::Vec4,
+) {
+    let i = non_uniform(index) as usize;
+    let color: glam::Vec4 = textures[i].sample(samplers[i % 4], glam::Vec2::new(0.0, 1.0));
+    *output = color * *scales[1];
+    buffers[i][0] += 1;
+}
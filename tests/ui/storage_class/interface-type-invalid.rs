@@ -0,0 +1,24 @@
+// Tests that types which can't be stored in interface blocks are errors naming the field.
+// build-fail
+
+use spirv_std as _;
+
+#[derive(Copy, Clone)]
+pub enum Shape {
+    Circle(f32),
+    Square(f32),
+}
+
+#[derive(Copy, Clone)]
+pub struct Scene {
+    pub count: u32,
+    pub shape: Shape,
+    pub tint: Option<[f32; 4]>,
+}
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] scene: &Scene,
+    #[spirv(push_constant)] tint: &Option<[f32; 4]>,
+) {
+}
@@ -0,0 +1,19 @@
+// Tests that `&mut` storage buffers of types that have to be stored differently are errors,
+// as writes couldn't be copied back without overwriting those of other invocations.
+// build-fail
+
+use spirv_std as _;
+
+#[derive(Copy, Clone)]
+pub struct State {
+    pub enabled: bool,
+    pub count: u32,
+}
+
+#[spirv(compute(threads(64)))]
+pub fn main(
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] state: &mut State,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 1)] flags: &mut [bool; 4],
+) {
+    state.enabled = flags[0];
+}
@@ -0,0 +1,22 @@
+// Tests that structs ending in a slice are rejected in interface blocks (even when the rest of
+// the struct would have to be lowered), naming the slice field.
+// build-fail
+
+use spirv_std as _;
+
+pub struct Flagged {
+    pub flag: bool,
+    pub data: [u32],
+}
+
+pub struct Nested {
+    pub count: u32,
+    pub inner: Flagged,
+}
+
+#[spirv(fragment)]
+pub fn main(
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] flagged: &Flagged,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 1)] nested: &mut Nested,
+) {
+}
@@ -0,0 +1,10 @@
+// Test that once capabilities are declared, using any other one is an error.
+// build-fail
+// compile-flags: -C target-feature=+cap:Int64
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [f64; 2]) {
+    buffer[1] = buffer[0] * 2.0;
+}
@@ -0,0 +1,10 @@
+// Test declaring the capabilities a shader may use with `+cap:...` target features.
+// build-pass
+// compile-flags: -C target-feature=+cap:Float64,+ext:SPV_KHR_physical_storage_buffer
+
+use spirv_std as _;
+
+#[spirv(fragment)]
+pub fn main(#[spirv(storage_buffer, descriptor_set = 0, binding = 0)] buffer: &mut [f64; 2]) {
+    buffer[1] = buffer[0] * 2.0;
+}